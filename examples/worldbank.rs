@@ -15,14 +15,23 @@ fn main() {
         header: Header {
             has_header_row: true,
             num_preamble_rows: 4,
+            num_footer_rows: 0,
         },
         quote: Quote::Some(b'"'),
         flexible: false,
+        terminator: Terminator::CRLF,
+        escape: Escape::Disabled,
+        comment: Comment::Disabled,
+        doublequote_escapes: true,
+        quote_style: QuoteStyle::Necessary,
+        num_trailing_empty_fields: 0,
+        trim: Trim::None,
+        skip_blank_lines: false,
+        encoding: Encoding::Utf8,
     };
     let mut reader = dialect.open_path(data_filepath).unwrap();
-    for result in reader.records() {
+    if let Some(result) = reader.records().next() {
         let record = result.unwrap();
         println!("{:?}", record);
-        break;
     }
 }
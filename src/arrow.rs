@@ -0,0 +1,107 @@
+/*!
+Conversion of the inferred field types to an [Arrow](https://docs.rs/arrow) schema (see
+[`Metadata::to_arrow_schema`](../metadata/struct.Metadata.html#method.to_arrow_schema)).
+*/
+use arrow_schema::{
+    DataType, Field, Schema, TimeUnit, DECIMAL128_MAX_PRECISION, DECIMAL256_MAX_PRECISION,
+};
+
+use crate::field_type::Type;
+use crate::metadata::Metadata;
+
+// The date-time, date and time formats that `arrow-csv` parses (ISO 8601, and times of day).
+const ARROW_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f%:z",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f%:z",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%d",
+    "%H:%M:%S%.f",
+    "%H:%M",
+    "%I:%M:%S %p",
+    "%I:%M %p",
+];
+
+/// The Arrow data type of a field of the provided type (and date-time format, if any), whose
+/// values are plain booleans or numbers (see `Metadata::plain_values`) or not, as read by
+/// `arrow-csv`. Values that `arrow-csv` doesn't parse as booleans, numbers, dates or times (e.g.
+/// `yes`, `1,234`, amounts with currency symbols, percentages, or dates like `03/04/2021`) are
+/// kept as strings.
+pub(crate) fn data_type(ty: Type, format: Option<&str>, is_plain: bool) -> DataType {
+    match ty {
+        Type::Unsigned
+        | Type::Signed
+        | Type::Boolean
+        | Type::Float
+        | Type::Decimal { .. }
+        | Type::BigInt
+            if !is_plain =>
+        {
+            DataType::Utf8
+        }
+        Type::DateTime | Type::Date | Type::Time
+            if !format.is_some_and(|format| ARROW_FORMATS.contains(&format)) =>
+        {
+            DataType::Utf8
+        }
+        Type::Unsigned => DataType::UInt64,
+        Type::Signed => DataType::Int64,
+        Type::Boolean => DataType::Boolean,
+        Type::Float => DataType::Float64,
+        Type::Decimal { precision, scale } => {
+            match (u8::try_from(precision), i8::try_from(scale)) {
+                (Ok(precision), Ok(scale)) if precision <= DECIMAL128_MAX_PRECISION => {
+                    DataType::Decimal128(precision, scale)
+                }
+                (Ok(precision), Ok(scale)) if precision <= DECIMAL256_MAX_PRECISION => {
+                    DataType::Decimal256(precision, scale)
+                }
+                _ => DataType::Float64,
+            }
+        }
+        Type::BigInt => DataType::Decimal256(DECIMAL256_MAX_PRECISION, 0),
+        Type::DateTime => {
+            // date-times with offsets are converted to UTC
+            let has_offset =
+                format.is_some_and(|format| format.contains('z') || format.ends_with('Z'));
+            DataType::Timestamp(TimeUnit::Nanosecond, has_offset.then(|| "UTC".into()))
+        }
+        Type::Date => DataType::Date32,
+        Type::Time => DataType::Time64(TimeUnit::Nanosecond),
+        Type::Null => DataType::Null,
+        Type::Categorical { .. } => {
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+        }
+        Type::Text
+        | Type::Currency { .. }
+        | Type::Percentage
+        | Type::Uuid
+        | Type::IpAddr
+        | Type::Json
+        | Type::NumericString
+        | Type::Hex
+        | Type::Binary { .. } => DataType::Utf8,
+    }
+}
+
+/// The Arrow schema of the fields of the provided metadata.
+pub(crate) fn schema(metadata: &Metadata) -> Schema {
+    let fields = metadata
+        .types
+        .iter()
+        .enumerate()
+        .map(|(i, &ty)| {
+            let format = metadata.formats.get(i).and_then(|format| format.as_deref());
+            // null values can't be ruled out from a sample
+            let nullable =
+                metadata.verification.is_none() || metadata.nullable.get(i) != Some(&false);
+            let is_plain = metadata.plain_values.get(i) == Some(&true);
+            Field::new(
+                metadata.field_name(i),
+                data_type(ty, format, is_plain),
+                nullable,
+            )
+        })
+        .collect::<Vec<_>>();
+    Schema::new(fields)
+}
@@ -0,0 +1,126 @@
+/*!
+Generation of an [Avro](https://avro.apache.org) record schema of the records of a CSV file (see
+[`Metadata::to_avro_schema`](../metadata/struct.Metadata.html#method.to_avro_schema)).
+*/
+use std::collections::HashSet;
+
+use serde_json::{json, Value};
+
+use crate::field_type::Type;
+use crate::metadata::Metadata;
+
+// Precision of the decimals holding integers beyond 64 bits.
+const BIGINT_PRECISION: usize = 39;
+
+// A valid Avro name for the provided name: characters other than ASCII letters, digits and
+// underscores are replaced with underscores, and names starting with a digit (or empty) get a
+// leading underscore.
+fn avro_name(name: &str) -> String {
+    let mut valid = name
+        .chars()
+        .map(|chr| {
+            if chr.is_ascii_alphanumeric() || chr == '_' {
+                chr
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    if !valid.starts_with(|chr: char| chr.is_ascii_alphabetic() || chr == '_') {
+        valid.insert(0, '_');
+    }
+    valid
+}
+
+fn logical(ty: &str, logical_type: &str) -> Value {
+    json!({"type": ty, "logicalType": logical_type})
+}
+
+fn decimal(precision: usize, scale: usize) -> Value {
+    json!({
+        "type": "bytes",
+        "logicalType": "decimal",
+        "precision": precision,
+        "scale": scale,
+    })
+}
+
+/// The Avro type of a field of the provided type (and date-time format, if any). Values that
+/// aren't plain numbers (e.g. amounts with currency symbols, or percentages) are strings.
+fn avro_type(ty: Type, format: Option<&str>) -> Value {
+    match ty {
+        Type::Unsigned | Type::Signed => json!("long"),
+        Type::BigInt => decimal(BIGINT_PRECISION, 0),
+        Type::Boolean => json!("boolean"),
+        Type::Float => json!("double"),
+        Type::Decimal { precision, scale } => decimal(precision.max(1), scale),
+        Type::DateTime => {
+            // date-times without offsets are local
+            let has_offset =
+                format.is_some_and(|format| format.contains('z') || format.ends_with('Z'));
+            logical(
+                "long",
+                if has_offset {
+                    "timestamp-millis"
+                } else {
+                    "local-timestamp-millis"
+                },
+            )
+        }
+        Type::Date => logical("int", "date"),
+        Type::Time => logical("int", "time-millis"),
+        Type::Uuid => logical("string", "uuid"),
+        Type::Null => json!("null"),
+        Type::Text
+        | Type::Currency { .. }
+        | Type::Percentage
+        | Type::IpAddr
+        | Type::Json
+        | Type::NumericString
+        | Type::Hex
+        | Type::Binary { .. }
+        | Type::Categorical { .. } => json!("string"),
+    }
+}
+
+/// The Avro record schema with the provided name and a field for each field of the provided
+/// metadata.
+pub(crate) fn schema(metadata: &Metadata, name: &str) -> Value {
+    let mut names = HashSet::new();
+    let fields = metadata
+        .types
+        .iter()
+        .enumerate()
+        .map(|(i, &ty)| {
+            let field_name = metadata.field_name(i);
+            // names made valid may collide, so they're made unique with a suffix
+            let mut avro_field_name = avro_name(&field_name);
+            let mut suffix = 1;
+            while !names.insert(avro_field_name.clone()) {
+                suffix += 1;
+                avro_field_name = format!("{}_{}", avro_name(&field_name), suffix);
+            }
+            let format = metadata.formats.get(i).and_then(|format| format.as_deref());
+            // null values can't be ruled out from a sample
+            let nullable =
+                metadata.verification.is_none() || metadata.nullable.get(i) != Some(&false);
+            let mut field = json!({"name": avro_field_name});
+            if avro_field_name != field_name {
+                field["doc"] = json!(field_name);
+            }
+            if nullable && ty != Type::Null {
+                field["type"] = json!(["null", avro_type(ty, format)]);
+                field["default"] = Value::Null;
+            } else {
+                field["type"] = avro_type(ty, format);
+            }
+            field
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "type": "record",
+        // the full name of the record may include a namespace
+        "name": name.split('.').map(avro_name).collect::<Vec<_>>().join("."),
+        "fields": fields,
+    })
+}
@@ -1,16 +1,31 @@
 extern crate csv_sniffer;
 
 use std::env;
+use std::io;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 2 {
-        eprintln!("Usage: {} <file>", args[0]);
+    let mut args: Vec<String> = env::args().collect();
+    // with `--rust-struct`, print a Rust struct for the records instead of the metadata
+    let rust_struct = args.iter().position(|arg| arg == "--rust-struct");
+    if let Some(i) = rust_struct {
+        args.remove(i);
+    }
+    if args.len() > 2 {
+        eprintln!("Usage: {} [--rust-struct] [<file> | -]", args[0]);
         ::std::process::exit(1);
     }
 
-    // sniff the path provided by the first argument
-    match csv_sniffer::Sniffer::new().sniff_path(&args[1]) {
+    // sniff the path provided by the first argument, or a sample read from stdin if no path (or
+    // `-`) is provided
+    let mut sniffer = csv_sniffer::Sniffer::new();
+    let result = match args.get(1).map(String::as_str) {
+        None | Some("-") => sniffer.sniff_stream(io::stdin().lock()),
+        Some(path) => sniffer.sniff_path(path),
+    };
+    match result {
+        Ok(metadata) if rust_struct.is_some() => {
+            print!("{}", metadata.to_rust_struct("Record"));
+        }
         Ok(metadata) => {
             println!("{}", metadata);
         }
@@ -0,0 +1,166 @@
+/*!
+Generation of Rust record structs from the sniffed metadata (see
+[`Metadata::to_rust_struct`](../metadata/struct.Metadata.html#method.to_rust_struct)).
+*/
+use std::collections::HashSet;
+
+use crate::field_type::Type;
+use crate::metadata::Metadata;
+
+// Keywords that can't be field names (other than as raw identifiers).
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+    "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe",
+    "use", "where", "while", "abstract", "become", "box", "do", "final", "macro", "override",
+    "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+// The words of a name: its runs of ASCII letters and digits, also split where a lowercase letter
+// or digit is followed by an uppercase letter (e.g. `unitPrice`).
+fn words(name: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    for chr in name.chars() {
+        if !chr.is_ascii_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if chr.is_ascii_uppercase()
+            && word
+                .chars()
+                .last()
+                .is_some_and(|last| last.is_ascii_lowercase() || last.is_ascii_digit())
+        {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(chr);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// The (`snake_case`) field name for a field with the provided name, if it has any letters or
+/// digits.
+fn field_ident(name: &str) -> Option<String> {
+    let ident = words(name)
+        .iter()
+        .map(|word| word.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    if ident.is_empty() {
+        None
+    } else if ident.starts_with(|chr: char| chr.is_ascii_digit()) {
+        Some(format!("_{}", ident))
+    } else if KEYWORDS.contains(&ident.as_str()) {
+        // `crate`, `self` and `super` can't be raw identifiers
+        if ["crate", "self", "super"].contains(&ident.as_str()) {
+            Some(format!("{}_", ident))
+        } else {
+            Some(format!("r#{}", ident))
+        }
+    } else {
+        Some(ident)
+    }
+}
+
+/// The (`UpperCamelCase`) name of a struct with the provided name.
+fn struct_ident(name: &str) -> String {
+    let ident = words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| {
+                    first.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase()
+                })
+                .unwrap_or_default()
+        })
+        .collect::<String>();
+    if ident.is_empty() || ident.starts_with(|chr: char| chr.is_ascii_digit()) {
+        format!("Record{}", ident)
+    } else {
+        ident
+    }
+}
+
+/// The Rust type of a field of the provided type, whose values are plain booleans or numbers (see
+/// `Metadata::plain_values`) or not. Values that aren't plain numbers or booleans (including dates
+/// and times, currency amounts and percentages) are `String`s.
+fn rust_type(ty: Type, is_plain: bool) -> &'static str {
+    match ty {
+        // e.g. `yes` or `1,234`, which don't deserialize as booleans or numbers
+        Type::Unsigned
+        | Type::Signed
+        | Type::BigInt
+        | Type::Boolean
+        | Type::Float
+        | Type::Decimal { .. }
+            if !is_plain =>
+        {
+            "String"
+        }
+        Type::Unsigned => "u64",
+        Type::Signed => "i64",
+        Type::BigInt => "i128",
+        Type::Boolean => "bool",
+        Type::Float | Type::Decimal { .. } => "f64",
+        Type::Text
+        | Type::Currency { .. }
+        | Type::Percentage
+        | Type::DateTime
+        | Type::Date
+        | Type::Time
+        | Type::Null
+        | Type::Uuid
+        | Type::IpAddr
+        | Type::Json
+        | Type::NumericString
+        | Type::Hex
+        | Type::Binary { .. }
+        | Type::Categorical { .. } => "String",
+    }
+}
+
+/// The source code of a struct with the provided name and a field for each field of the provided
+/// metadata.
+pub(crate) fn rust_struct(metadata: &Metadata, name: &str) -> String {
+    let mut code = format!(
+        "#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]\n\
+         pub struct {} {{\n",
+        struct_ident(name)
+    );
+    let mut idents = HashSet::new();
+    for (i, &ty) in metadata.types.iter().enumerate() {
+        let field_name = metadata.field_name(i);
+        let base = field_ident(&field_name).unwrap_or_else(|| format!("field_{}", i + 1));
+        // names made valid may collide, so they're made unique with a suffix
+        let mut ident = base.clone();
+        let mut suffix = 1;
+        while !idents.insert(ident.clone()) {
+            suffix += 1;
+            ident = format!("{}_{}", base, suffix);
+        }
+        // serde derives field names from raw identifiers without their `r#` prefix
+        if ident.trim_start_matches("r#") != field_name && !metadata.field_names.is_empty() {
+            code.push_str(&format!("    #[serde(rename = {:?})]\n", field_name));
+        }
+        // null values can't be ruled out from a sample
+        let nullable = ty == Type::Null
+            || metadata.verification.is_none()
+            || metadata.nullable.get(i) != Some(&false);
+        let rust_type = rust_type(ty, metadata.plain_values.get(i) == Some(&true));
+        if nullable {
+            code.push_str(&format!("    pub {}: Option<{}>,\n", ident, rust_type));
+        } else {
+            code.push_str(&format!("    pub {}: {},\n", ident, rust_type));
+        }
+    }
+    code.push_str("}\n");
+    code
+}
@@ -0,0 +1,182 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::{
+    error::{Result, SnifferError},
+    metadata::Compression,
+};
+
+/// Detects the compression format (if any) from the magic bytes at the start of a file.
+pub(crate) fn detect_compression(magic: &[u8]) -> Compression {
+    if magic.starts_with(&[0x1F, 0x8B]) {
+        Compression::Gzip
+    } else if magic.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]) {
+        Compression::Zstd
+    } else if magic.starts_with(b"BZh") {
+        Compression::Bzip2
+    } else if magic.starts_with(&[0xFD, 0x37, 0x7A, 0x58, 0x5A, 0x00]) {
+        Compression::Xz
+    } else {
+        Compression::None
+    }
+}
+
+enum Decoder<R: Read> {
+    None(R),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::read::MultiGzDecoder<R>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::read::Decoder<'static, io::BufReader<R>>),
+    #[cfg(feature = "bzip2")]
+    Bzip2(bzip2::read::MultiBzDecoder<R>),
+    #[cfg(feature = "xz")]
+    Xz(xz2::read::XzDecoder<R>),
+}
+impl<R: Read> Decoder<R> {
+    fn new(reader: R, compression: Compression) -> Result<Decoder<R>> {
+        match compression {
+            Compression::None => Ok(Decoder::None(reader)),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => Ok(Decoder::Gzip(flate2::read::MultiGzDecoder::new(reader))),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Ok(Decoder::Zstd(zstd::stream::read::Decoder::new(reader)?)),
+            #[cfg(feature = "bzip2")]
+            Compression::Bzip2 => Ok(Decoder::Bzip2(bzip2::read::MultiBzDecoder::new(reader))),
+            #[cfg(feature = "xz")]
+            Compression::Xz => Ok(Decoder::Xz(xz2::read::XzDecoder::new_multi_decoder(reader))),
+            #[allow(unreachable_patterns)]
+            _ => Err(SnifferError::SniffingFailed(format!(
+                "{:?}-compressed input requires the `{}` feature",
+                compression,
+                compression.feature()
+            ))),
+        }
+    }
+    fn into_inner(self) -> R {
+        match self {
+            Decoder::None(reader) => reader,
+            #[cfg(feature = "gzip")]
+            Decoder::Gzip(decoder) => decoder.into_inner(),
+            #[cfg(feature = "zstd")]
+            Decoder::Zstd(decoder) => decoder.finish().into_inner(),
+            #[cfg(feature = "bzip2")]
+            Decoder::Bzip2(decoder) => decoder.into_inner(),
+            #[cfg(feature = "xz")]
+            Decoder::Xz(decoder) => decoder.into_inner(),
+        }
+    }
+}
+
+/// A reader that transparently decompresses its input. Seeking within compressed input is
+/// supported by decompressing from the start of the input (or from the current position, when
+/// seeking forward), so it's best kept to a minimum. Created by
+/// [`Dialect::open_path_decompressed`](struct.Dialect.html#method.open_path_decompressed).
+pub struct DecompressReader<R: Read> {
+    // always present; only taken while rewinding
+    decoder: Option<Decoder<R>>,
+    compression: Compression,
+    // position within (and total length of) the decompressed data
+    pos: u64,
+    len: Option<u64>,
+}
+impl DecompressReader<File> {
+    /// Opens the file at the provided path, detecting its compression format.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<DecompressReader<File>> {
+        DecompressReader::detect(File::open(path)?)
+    }
+}
+impl<R: Read> DecompressReader<R> {
+    /// Wraps the provided reader (positioned at the start of the input), decompressing it with the
+    /// provided compression format. Fails if support for the format isn't enabled.
+    pub fn new(reader: R, compression: Compression) -> Result<DecompressReader<R>> {
+        Ok(DecompressReader {
+            decoder: Some(Decoder::new(reader, compression)?),
+            compression,
+            pos: 0,
+            len: None,
+        })
+    }
+
+    /// The compression format of the underlying input.
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+}
+impl<R: Read + Seek> DecompressReader<R> {
+    /// Wraps the provided reader (positioned at the start of the input), detecting its compression
+    /// format.
+    pub fn detect(mut reader: R) -> Result<DecompressReader<R>> {
+        let mut magic = vec![];
+        (&mut reader).take(6).read_to_end(&mut magic)?;
+        reader.seek(SeekFrom::Start(0))?;
+        DecompressReader::new(reader, detect_compression(&magic))
+    }
+
+    // Restarts decompression from the start of the underlying input.
+    fn rewind_decoder(&mut self) -> io::Result<()> {
+        let mut reader = self.decoder.take().expect("decoder missing").into_inner();
+        reader.seek(SeekFrom::Start(0))?;
+        self.decoder = Some(
+            Decoder::new(reader, self.compression)
+                .map_err(|e| io::Error::new(io::ErrorKind::Unsupported, e.to_string()))?,
+        );
+        self.pos = 0;
+        Ok(())
+    }
+}
+impl<R: Read> Read for DecompressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n_read = match self.decoder.as_mut().expect("decoder missing") {
+            Decoder::None(reader) => reader.read(buf)?,
+            #[cfg(feature = "gzip")]
+            Decoder::Gzip(decoder) => decoder.read(buf)?,
+            #[cfg(feature = "zstd")]
+            Decoder::Zstd(decoder) => decoder.read(buf)?,
+            #[cfg(feature = "bzip2")]
+            Decoder::Bzip2(decoder) => decoder.read(buf)?,
+            #[cfg(feature = "xz")]
+            Decoder::Xz(decoder) => decoder.read(buf)?,
+        };
+        self.pos += n_read as u64;
+        Ok(n_read)
+    }
+}
+impl<R: Read + Seek> Seek for DecompressReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        if let Some(Decoder::None(reader)) = self.decoder.as_mut() {
+            self.pos = reader.seek(pos)?;
+            return Ok(self.pos);
+        }
+
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+            SeekFrom::End(offset) => {
+                let len = match self.len {
+                    Some(len) => len,
+                    None => {
+                        // decompress the rest of the input to find its length
+                        io::copy(self, &mut io::sink())?;
+                        self.len = Some(self.pos);
+                        self.pos
+                    }
+                };
+                len.checked_add_signed(offset)
+            }
+        }
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative position",
+            )
+        })?;
+
+        if target < self.pos {
+            self.rewind_decoder()?;
+        }
+        let n_skip = target - self.pos;
+        io::copy(&mut (&mut *self).take(n_skip), &mut io::sink())?;
+        Ok(self.pos)
+    }
+}
@@ -0,0 +1,174 @@
+/*!
+Export of the sniffed metadata as W3C [CSV on the Web](https://www.w3.org/TR/tabular-metadata/)
+(CSVW) metadata (see
+[`Metadata::to_csvw_metadata`](../metadata/struct.Metadata.html#method.to_csvw_metadata)).
+*/
+use serde_json::{json, Map, Value};
+
+use crate::field_type::{BinaryEncoding, SemanticType, Type};
+use crate::metadata::{Comment, Encoding, Metadata, Quote, Terminator, Trim};
+
+// Context of CSVW metadata documents.
+const CSVW_CONTEXT: &str = "http://www.w3.org/ns/csvw";
+
+/// The name of a column with the provided title: its characters other than ASCII letters, digits
+/// and underscores are percent-encoded (as the names of URI template variables), as is a leading
+/// underscore (reserved for the names of columns without titles).
+fn column_name(title: &str) -> String {
+    let mut name = String::new();
+    for (i, byte) in title.bytes().enumerate() {
+        if byte.is_ascii_alphanumeric() || (byte == b'_' && i > 0) {
+            name.push(char::from(byte));
+        } else {
+            name.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    name
+}
+
+/// The date and time format of CSVW (a UAX #35 pattern) equivalent to the provided
+/// `strftime`-style format, if any: formats of numeric years, months, days, hours, minutes and
+/// seconds.
+fn date_time_pattern(format: &str) -> Option<String> {
+    let mut pattern = String::new();
+    let mut chars = format.chars();
+    while let Some(chr) = chars.next() {
+        match chr {
+            '%' => pattern.push_str(match chars.next()? {
+                'Y' => "yyyy",
+                'm' => "MM",
+                'd' => "dd",
+                'H' => "HH",
+                'M' => "mm",
+                'S' => "ss",
+                _ => return None,
+            }),
+            // letters are pattern characters
+            chr if chr.is_ascii_alphabetic() && chr != 'T' => return None,
+            chr => pattern.push(chr),
+        }
+    }
+    Some(pattern)
+}
+
+/// The CSVW datatype of the field of the provided metadata with the provided index. Values that
+/// aren't plain numbers (e.g. amounts with currency symbols, or percentages) are strings.
+fn datatype(metadata: &Metadata, i: usize) -> Value {
+    let ty = metadata.types[i];
+    let format = metadata.formats.get(i).and_then(|format| format.as_deref());
+    let base = match ty {
+        Type::Unsigned => "nonNegativeInteger",
+        Type::Signed | Type::BigInt => "integer",
+        Type::Boolean => "boolean",
+        Type::Float => "double",
+        Type::Decimal { .. } => "decimal",
+        Type::DateTime => "datetime",
+        Type::Date => "date",
+        Type::Time => "time",
+        Type::Json => "json",
+        Type::Binary {
+            encoding: BinaryEncoding::Base64,
+            ..
+        } => "base64Binary",
+        Type::Text
+        | Type::Currency { .. }
+        | Type::Percentage
+        | Type::Null
+        | Type::Uuid
+        | Type::IpAddr
+        | Type::NumericString
+        | Type::Hex
+        | Type::Categorical { .. } => match metadata.semantic_types.get(i).copied().flatten() {
+            Some(SemanticType::Url) => "anyURI",
+            _ => "string",
+        },
+    };
+    let number_format = metadata.number_format;
+    let is_integer = matches!(ty, Type::Unsigned | Type::Signed | Type::BigInt);
+    let format = match ty {
+        Type::Unsigned | Type::Signed | Type::BigInt | Type::Float | Type::Decimal { .. } => {
+            let mut separators = Map::new();
+            if !is_integer && number_format.decimal_separator != '.' {
+                separators.insert("decimalChar".into(), json!(number_format.decimal_separator));
+            }
+            if let Some(grouping_separator) = number_format.grouping_separator {
+                separators.insert("groupChar".into(), json!(grouping_separator));
+            }
+            (!separators.is_empty()).then_some(Value::Object(separators))
+        }
+        // the ISO 8601 formats (with optional fractional seconds and offsets) are the default
+        Type::DateTime | Type::Date | Type::Time => format
+            .filter(|format| !format.contains("%.f") && !format.contains('z'))
+            .filter(|&format| format != "%Y-%m-%d")
+            .and_then(date_time_pattern)
+            .map(Value::String),
+        _ => None,
+    };
+    match format {
+        Some(format) => json!({"base": base, "format": format}),
+        None => json!(base),
+    }
+}
+
+/// The CSVW dialect description of the provided metadata.
+fn dialect(metadata: &Metadata) -> Value {
+    let dialect = &metadata.dialect;
+    let encoding = match metadata.encoding {
+        Encoding::Utf8 => "utf-8",
+        Encoding::Utf16Le => "utf-16le",
+        Encoding::Utf16Be => "utf-16be",
+        Encoding::Windows1252 => "windows-1252",
+    };
+    let terminator = match dialect.terminator {
+        Terminator::CRLF => "\r\n".to_string(),
+        Terminator::LF => "\n".to_string(),
+        Terminator::CR => "\r".to_string(),
+        Terminator::Any(chr) => char::from(chr).to_string(),
+    };
+    let quote = match dialect.quote {
+        Quote::Some(chr) => Some(char::from(chr)),
+        Quote::None => None,
+    };
+    let trim = match dialect.trim {
+        Trim::Fields | Trim::All => true,
+        Trim::None | Trim::Headers => false,
+    };
+    let mut description = json!({
+        "encoding": encoding,
+        "delimiter": char::from(dialect.delimiter),
+        "lineTerminators": [terminator],
+        "quoteChar": quote,
+        "doubleQuote": dialect.doublequote_escapes,
+    });
+    if let Comment::Enabled(chr) = dialect.comment {
+        description["commentPrefix"] = json!(char::from(chr));
+    }
+    description["header"] = json!(dialect.header.has_header_row);
+    description["skipRows"] = json!(dialect.header.num_preamble_rows);
+    description["skipBlankRows"] = json!(dialect.skip_blank_lines);
+    description["trim"] = json!(trim);
+    description
+}
+
+/// The CSVW metadata of the table at the provided URL, with the provided metadata.
+pub(crate) fn metadata(metadata: &Metadata, url: &str) -> Value {
+    let columns = (0..metadata.types.len())
+        .map(|i| {
+            let mut column = match metadata.field_names.get(i) {
+                Some(title) => json!({"name": column_name(title), "titles": title}),
+                None => json!({"name": metadata.field_name(i)}),
+            };
+            column["datatype"] = datatype(metadata, i);
+            if metadata.verification.is_some() && metadata.nullable.get(i) == Some(&false) {
+                column["required"] = json!(true);
+            }
+            column
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "@context": CSVW_CONTEXT,
+        "url": url,
+        "dialect": dialect(metadata),
+        "tableSchema": {"columns": columns},
+    })
+}
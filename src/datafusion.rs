@@ -0,0 +1,71 @@
+/*!
+Conversion of the sniffed metadata to [DataFusion](https://docs.rs/datafusion) CSV file formats and
+reading options (see
+[`Metadata::to_datafusion_csv_format`](../metadata/struct.Metadata.html#method.to_datafusion_csv_format)
+and
+[`Metadata::to_datafusion_read_options`](../metadata/struct.Metadata.html#method.to_datafusion_read_options)).
+*/
+use arrow_schema::Schema;
+use datafusion::datasource::file_format::csv::CsvFormat;
+use datafusion::datasource::file_format::file_compression_type::FileCompressionType;
+use datafusion::datasource::file_format::options::CsvReadOptions;
+
+use crate::metadata::{Comment, Compression, Escape, Metadata, Quote, Terminator};
+
+// The terminator, if other than CRLF (which DataFusion also takes for LF).
+fn terminator(terminator: Terminator) -> Option<u8> {
+    match terminator {
+        Terminator::CRLF | Terminator::LF => None,
+        Terminator::CR => Some(b'\r'),
+        Terminator::Any(chr) => Some(chr),
+    }
+}
+
+fn compression_type(compression: Compression) -> FileCompressionType {
+    match compression {
+        Compression::None => FileCompressionType::UNCOMPRESSED,
+        Compression::Gzip => FileCompressionType::GZIP,
+        Compression::Zstd => FileCompressionType::ZSTD,
+        Compression::Bzip2 => FileCompressionType::BZIP2,
+        Compression::Xz => FileCompressionType::XZ,
+    }
+}
+
+/// The DataFusion CSV file format matching the provided metadata.
+pub(crate) fn csv_format(metadata: &Metadata) -> CsvFormat {
+    let dialect = &metadata.dialect;
+    let mut format = CsvFormat::default()
+        .with_has_header(dialect.header.has_header_row)
+        .with_delimiter(dialect.delimiter)
+        .with_escape(dialect.escape.clone().into())
+        .with_terminator(terminator(dialect.terminator))
+        .with_comment(dialect.comment.clone().into())
+        .with_truncated_rows(dialect.flexible)
+        .with_file_compression_type(compression_type(metadata.compression));
+    if let Quote::Some(chr) = dialect.quote {
+        format = format.with_quote(chr);
+    }
+    format
+}
+
+/// The DataFusion CSV reading options matching the provided metadata, with the provided schema.
+pub(crate) fn read_options<'a>(metadata: &Metadata, schema: &'a Schema) -> CsvReadOptions<'a> {
+    let dialect = &metadata.dialect;
+    let mut options = CsvReadOptions::new()
+        .has_header(dialect.header.has_header_row)
+        .delimiter(dialect.delimiter)
+        .terminator(terminator(dialect.terminator))
+        .truncated_rows(dialect.flexible)
+        .file_compression_type(compression_type(metadata.compression))
+        .schema(schema);
+    if let Quote::Some(chr) = dialect.quote {
+        options = options.quote(chr);
+    }
+    if let Escape::Enabled(chr) = dialect.escape {
+        options = options.escape(chr);
+    }
+    if let Comment::Enabled(chr) = dialect.comment {
+        options = options.comment(chr);
+    }
+    options
+}
@@ -0,0 +1,273 @@
+/*!
+Matching of date and time values against `strftime`-style format strings.
+*/
+use crate::field_type::Type;
+use crate::metadata::DateOrder;
+
+/// Formats of the values recognized as [`Type::DateTime`](../enum.Type.html#variant.DateTime),
+/// from most to least preferred (a column's values can match several formats, e.g.
+/// `01/02/2020 10:00` is valid both month-first and day-first).
+pub(crate) const DATETIME_FORMATS: &[&str] = &[
+    // RFC 3339
+    "%Y-%m-%dT%H:%M:%S%.f%:z",
+    // ISO 8601
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f%:z",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%m/%d/%Y %H:%M:%S",
+    "%d/%m/%Y %H:%M:%S",
+    "%m/%d/%Y %H:%M",
+    "%d/%m/%Y %H:%M",
+];
+
+/// Formats of the values recognized as [`Type::Date`](../enum.Type.html#variant.Date), from most
+/// to least preferred.
+pub(crate) const DATE_FORMATS: &[&str] = &[
+    // ISO 8601
+    "%Y-%m-%d",
+    "%Y/%m/%d",
+    "%m/%d/%Y",
+    "%d/%m/%Y",
+    "%m-%d-%Y",
+    "%d-%m-%Y",
+    "%d.%m.%Y",
+    "%Y%m%d",
+    "%d %b %Y",
+    "%d-%b-%Y",
+    "%b %d, %Y",
+    "%d %B %Y",
+    "%B %d, %Y",
+];
+
+/// Formats of the values recognized as [`Type::Time`](../enum.Type.html#variant.Time) (times of
+/// day), from most to least preferred.
+pub(crate) const TIME_FORMATS: &[&str] = &[
+    "%H:%M:%S%.f",
+    "%H:%M",
+    "%I:%M:%S %p",
+    "%I:%M %p",
+    "%I:%M:%S%p",
+    "%I:%M%p",
+];
+
+// Formats of the times of day found within other values (e.g. date-times), by themselves.
+const TIME_TOKEN_FORMATS: &[&str] = &["%H:%M:%S%.f", "%H:%M"];
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Whether or not a value matches a format. Supports the `%Y` (four-digit year), `%m` (month),
+/// `%d` (day), `%b` (abbreviated month name), `%B` (month name), `%H` (hour), `%I` (hour on a
+/// 12-hour clock), `%p` (`AM` or `PM`), `%M` (minute), `%S` (second), `%.f` (optional fractional seconds),
+/// `%z` (`+hhmm` offset) and `%:z` (`+hh:mm` offset or `Z`) specifiers; any other character
+/// matches itself. Numbers next to other specifiers (e.g. in `%Y%m%d`) must have two digits (or
+/// four for years, between 1000 and 2999).
+pub(crate) fn matches_format(value: &str, format: &str) -> bool {
+    matches_format_bytes(value.as_bytes(), format)
+}
+
+fn matches_format_bytes(mut value: &[u8], format: &str) -> bool {
+    let mut format = format.as_bytes();
+    // whether or not the last format element was a specifier
+    let mut after_specifier = false;
+    while let Some((&chr, rest)) = format.split_first() {
+        if chr != b'%' {
+            match value.split_first() {
+                Some((&value_chr, value_rest)) if value_chr == chr => value = value_rest,
+                _ => return false,
+            }
+            format = rest;
+            after_specifier = false;
+            continue;
+        }
+        let spec_len = if rest.starts_with(b".f") || rest.starts_with(b":z") {
+            2
+        } else {
+            1
+        };
+        if rest.len() < spec_len {
+            return false;
+        }
+        let (spec, rest) = rest.split_at(spec_len);
+        let compact = after_specifier || rest.first() == Some(&b'%');
+        value = match match_specifier(value, spec, compact) {
+            Some(value_rest) => value_rest,
+            None => return false,
+        };
+        format = rest;
+        after_specifier = true;
+    }
+    value.is_empty()
+}
+
+// Matches a format specifier (without the `%`) at the start of a value, returning the rest of the
+// value. Numbers of `compact` specifiers (next to other specifiers, e.g. in `%Y%m%d`) have all of
+// their digits and plausible years, so that other numbers (e.g. IDs) don't match compact formats.
+fn match_specifier<'a>(value: &'a [u8], spec: &[u8], compact: bool) -> Option<&'a [u8]> {
+    let min_digits = if compact { 2 } else { 1 };
+    match spec {
+        b"Y" if compact => take_number(value, 4, 4, 1000..=2999),
+        b"Y" => take_number(value, 4, 4, 0..=9999),
+        b"m" => take_number(value, min_digits, 2, 1..=12),
+        b"d" => take_number(value, min_digits, 2, 1..=31),
+        b"b" => MONTH_NAMES
+            .iter()
+            .find_map(|name| take_name(value, &name[..3])),
+        b"B" => MONTH_NAMES.iter().find_map(|name| take_name(value, name)),
+        b"H" => take_number(value, min_digits, 2, 0..=23),
+        b"I" => take_number(value, min_digits, 2, 1..=12),
+        b"p" => take_name(value, "AM").or_else(|| take_name(value, "PM")),
+        b"M" => take_number(value, 2, 2, 0..=59),
+        // allowing for leap seconds
+        b"S" => take_number(value, 2, 2, 0..=60),
+        b".f" => match value.split_first() {
+            Some((b'.', rest)) => {
+                let n_digits = count_digits(rest);
+                if n_digits == 0 {
+                    None
+                } else {
+                    Some(&rest[n_digits..])
+                }
+            }
+            _ => Some(value),
+        },
+        b"z" => match value.split_first() {
+            Some((b'+' | b'-', rest)) => {
+                let rest = take_number(rest, 2, 2, 0..=23)?;
+                take_number(rest, 2, 2, 0..=59)
+            }
+            _ => None,
+        },
+        b":z" => match value.split_first() {
+            Some((b'Z' | b'z', rest)) => Some(rest),
+            Some((b'+' | b'-', rest)) => {
+                let rest = take_number(rest, 2, 2, 0..=23)?;
+                take_number(rest.strip_prefix(b":")?, 2, 2, 0..=59)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The type of the values of a format: `Type::DateTime` for formats with both date (`%Y`, `%m`,
+/// `%d`, `%b` or `%B`) and time (`%H` or `%I`) specifiers, `Type::Date` or `Type::Time` for formats
+/// with only one kind, and `None` for formats with neither.
+pub(crate) fn format_type(format: &str) -> Option<Type> {
+    let has_any = |specs: &[&str]| specs.iter().any(|spec| format.contains(spec));
+    match (
+        has_any(&["%Y", "%m", "%d", "%b", "%B"]),
+        has_any(&["%H", "%I"]),
+    ) {
+        (true, true) => Some(Type::DateTime),
+        (true, false) => Some(Type::Date),
+        (false, true) => Some(Type::Time),
+        (false, false) => None,
+    }
+}
+
+/// The order of the day and month in a format, or `None` for formats without both (or starting with
+/// the year, like ISO 8601 dates, which are never ambiguous).
+pub(crate) fn date_order(format: &str) -> Option<DateOrder> {
+    let day = format.find("%d")?;
+    let month = ["%m", "%b", "%B"]
+        .iter()
+        .filter_map(|spec| format.find(spec))
+        .min()?;
+    if format.find("%Y").is_some_and(|year| year < day.min(month)) {
+        None
+    } else if day < month {
+        Some(DateOrder::DayFirst)
+    } else {
+        Some(DateOrder::MonthFirst)
+    }
+}
+
+/// The format with its (numeric) day and month the other way around (e.g. `%d/%m/%Y` for
+/// `%m/%d/%Y`).
+pub(crate) fn swap_day_month(format: &str) -> String {
+    format
+        .replace("%d", "\0")
+        .replace("%m", "%d")
+        .replace('\0', "%m")
+}
+
+/// Counts the colons in a line that are part of times of day (e.g. `13:45` or `13:45:09`, by
+/// themselves or within date-times), which aren't field separators.
+pub(crate) fn count_time_colons(line: &[u8]) -> usize {
+    let is_time_chr = |chr: &u8| chr.is_ascii_digit() || *chr == b':' || *chr == b'.';
+    let mut n_colons = 0;
+    // end of the last token examined
+    let mut end = 0;
+    for pos in memchr::memchr_iter(b':', line) {
+        if pos < end {
+            continue;
+        }
+        let start = line[..pos]
+            .iter()
+            .rposition(|chr| !is_time_chr(chr))
+            .map_or(0, |i| i + 1);
+        end = line[pos..]
+            .iter()
+            .position(|chr| !is_time_chr(chr))
+            .map_or(line.len(), |i| pos + i);
+        let token = &line[start..end];
+        if TIME_TOKEN_FORMATS
+            .iter()
+            .any(|format| matches_format_bytes(token, format))
+        {
+            n_colons += memchr::memchr_iter(b':', token).count();
+        }
+    }
+    n_colons
+}
+
+// Takes a number (of as many digits as available, up to `max_digits`) at the start of a value,
+// returning the rest of the value if the number has at least `min_digits` digits and is within the
+// range.
+fn take_number(
+    value: &[u8],
+    min_digits: usize,
+    max_digits: usize,
+    range: std::ops::RangeInclusive<u32>,
+) -> Option<&[u8]> {
+    let n_digits = count_digits(value).min(max_digits);
+    if n_digits < min_digits {
+        return None;
+    }
+    let number = value[..n_digits]
+        .iter()
+        .fold(0, |acc, &digit| acc * 10 + u32::from(digit - b'0'));
+    if range.contains(&number) {
+        Some(&value[n_digits..])
+    } else {
+        None
+    }
+}
+
+// Takes a name (in any case) at the start of a value, returning the rest of the value.
+fn take_name<'a>(value: &'a [u8], name: &str) -> Option<&'a [u8]> {
+    if value.len() >= name.len() && value[..name.len()].eq_ignore_ascii_case(name.as_bytes()) {
+        Some(&value[name.len()..])
+    } else {
+        None
+    }
+}
+
+fn count_digits(value: &[u8]) -> usize {
+    value.iter().take_while(|chr| chr.is_ascii_digit()).count()
+}
@@ -0,0 +1,144 @@
+/*!
+Differences between the sniffed schemas of two files (see
+[`Metadata::diff`](../metadata/struct.Metadata.html#method.diff)).
+*/
+use std::fmt;
+
+use crate::field_type::Type;
+use crate::merge::matching_fields;
+use crate::metadata::Metadata;
+use crate::spec::settings;
+
+/// Differences between the schemas (fields, types and dialects) of two files, generated by
+/// [`Metadata::diff`](struct.Metadata.html#method.diff).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaDiff {
+    /// Names of the fields of the other file that this file doesn't have.
+    pub added: Vec<String>,
+    /// Names of the fields of this file that the other file doesn't have.
+    pub removed: Vec<String>,
+    /// Fields of both files whose types (or formats) differ.
+    pub type_changes: Vec<TypeChange>,
+    /// Dialect settings that differ.
+    pub dialect_changes: Vec<DialectChange>,
+}
+impl SchemaDiff {
+    /// Whether or not the schemas are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.type_changes.is_empty()
+            && self.dialect_changes.is_empty()
+    }
+}
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Schema diff:")?;
+        writeln!(f, "\tAdded fields: {}", self.added.join(", "))?;
+        writeln!(f, "\tRemoved fields: {}", self.removed.join(", "))?;
+        writeln!(f, "\tType changes:")?;
+        for change in &self.type_changes {
+            writeln!(f, "\t\t{}", change)?;
+        }
+        writeln!(f, "\tDialect changes:")?;
+        for change in &self.dialect_changes {
+            writeln!(f, "\t\t{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// A field whose type (or format) differs between two files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeChange {
+    /// Name of the field (in this file).
+    pub field: String,
+    /// Type of the field in this file.
+    pub from: Type,
+    /// Type of the field in the other file.
+    pub to: Type,
+    /// Format of the field in this file (see
+    /// [`Metadata::formats`](struct.Metadata.html#structfield.formats)).
+    pub from_format: Option<String>,
+    /// Format of the field in the other file.
+    pub to_format: Option<String>,
+}
+impl fmt::Display for TypeChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.from)?;
+        if let Some(ref format) = self.from_format {
+            write!(f, " ({})", format)?;
+        }
+        write!(f, " -> {}", self.to)?;
+        if let Some(ref format) = self.to_format {
+            write!(f, " ({})", format)?;
+        }
+        Ok(())
+    }
+}
+
+/// A dialect setting that differs between two files, with its key and values as in dialect
+/// specifications (see [`Dialect::from_spec`](struct.Dialect.html#method.from_spec)), e.g.
+/// `delim` from `,` to `;`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DialectChange {
+    /// Key of the setting.
+    pub setting: String,
+    /// Value of the setting for this file.
+    pub from: String,
+    /// Value of the setting for the other file.
+    pub to: String,
+}
+impl fmt::Display for DialectChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.setting, self.from, self.to)
+    }
+}
+
+/// The differences between the schema of the provided metadata and the other metadata.
+pub(crate) fn diff(metadata: &Metadata, other: &Metadata) -> SchemaDiff {
+    let matches = matching_fields(metadata, other);
+    let removed = matches
+        .iter()
+        .enumerate()
+        .filter(|(_, j)| j.is_none())
+        .map(|(i, _)| metadata.field_name(i))
+        .collect();
+    let added = (0..other.types.len())
+        .filter(|j| !matches.contains(&Some(*j)))
+        .map(|j| other.field_name(j))
+        .collect();
+    let type_changes = matches
+        .iter()
+        .enumerate()
+        .filter_map(|(i, j)| j.map(|j| (i, j)))
+        .filter_map(|(i, j)| {
+            let from_format = metadata.formats.get(i).cloned().flatten();
+            let to_format = other.formats.get(j).cloned().flatten();
+            let (from, to) = (metadata.types[i], other.types[j]);
+            (from != to || from_format != to_format).then(|| TypeChange {
+                field: metadata.field_name(i),
+                from,
+                to,
+                from_format,
+                to_format,
+            })
+        })
+        .collect();
+    let dialect_changes = settings(&metadata.dialect)
+        .into_iter()
+        .zip(settings(&other.dialect))
+        .filter(|((_, from), (_, to))| from != to)
+        .map(|((setting, from), (_, to))| DialectChange {
+            setting: setting.into(),
+            from,
+            to,
+        })
+        .collect();
+    SchemaDiff {
+        added,
+        removed,
+        type_changes,
+        dialect_changes,
+    }
+}
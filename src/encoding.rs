@@ -0,0 +1,184 @@
+use std::io::Read;
+
+use crate::{
+    error::Result,
+    metadata::{Bom, Encoding},
+    sample::detect_bom,
+    stream::SampleBuffer,
+};
+
+// Number of raw bytes read at a time while decoding a sample.
+const DECODE_CHUNK_BYTES: usize = 1 << 13;
+
+// Characters for bytes 0x80 through 0x9F in Windows-1252 (bytes that are undefined in Windows-1252
+// are mapped to the corresponding C1 control characters, as in Latin-1).
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+/// Detects the character encoding of a sample of raw bytes from the start of a file.
+pub(crate) fn detect_encoding(sample: &[u8]) -> Encoding {
+    match detect_bom(sample) {
+        Some(Bom::Utf8) => return Encoding::Utf8,
+        Some(Bom::Utf16Le) => return Encoding::Utf16Le,
+        Some(Bom::Utf16Be) => return Encoding::Utf16Be,
+        None => {}
+    }
+
+    // mostly-ASCII UTF-16 text has a zero byte in (nearly) every other position
+    let n_pairs = sample.len() / 2;
+    if n_pairs > 0 {
+        let n_zeros_even = sample.iter().step_by(2).filter(|&&chr| chr == 0).count();
+        let n_zeros_odd = sample
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .filter(|&&chr| chr == 0)
+            .count();
+        if 2 * n_zeros_odd > n_pairs && n_zeros_even == 0 {
+            return Encoding::Utf16Le;
+        }
+        if 2 * n_zeros_even > n_pairs && n_zeros_odd == 0 {
+            return Encoding::Utf16Be;
+        }
+    }
+
+    match std::str::from_utf8(sample) {
+        Ok(_) => Encoding::Utf8,
+        // the sample may end in the middle of a multi-byte character
+        Err(e) if e.error_len().is_none() => Encoding::Utf8,
+        Err(_) => Encoding::Windows1252,
+    }
+}
+
+/// Decodes raw bytes in the provided encoding, dropping the byte order mark (if any).
+pub(crate) fn decode(bytes: &[u8], encoding: Encoding) -> String {
+    let bytes = match detect_bom(bytes) {
+        Some(bom) if bom.encoding() == encoding => &bytes[bom.bytes().len()..],
+        _ => bytes,
+    };
+    decode_chars(bytes, encoding)
+}
+
+// Decodes raw bytes (with no byte order mark) in the provided encoding.
+fn decode_chars(bytes: &[u8], encoding: Encoding) -> String {
+    match encoding {
+        Encoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            let units = bytes.chunks_exact(2).map(|pair| match encoding {
+                Encoding::Utf16Le => u16::from_le_bytes([pair[0], pair[1]]),
+                _ => u16::from_be_bytes([pair[0], pair[1]]),
+            });
+            char::decode_utf16(units)
+                .map(|chr| chr.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect()
+        }
+        Encoding::Windows1252 => bytes
+            .iter()
+            .map(|&chr| match chr {
+                0x80..=0x9F => WINDOWS_1252_HIGH[usize::from(chr - 0x80)],
+                _ => char::from(chr),
+            })
+            .collect(),
+    }
+}
+
+/// The number of bytes the provided (decoded) text takes up in the provided encoding.
+pub(crate) fn encoded_len(text: &str, encoding: Encoding) -> usize {
+    match encoding {
+        Encoding::Utf8 => text.len(),
+        Encoding::Utf16Le | Encoding::Utf16Be => 2 * text.encode_utf16().count(),
+        Encoding::Windows1252 => text.chars().count(),
+    }
+}
+
+// The length of the longest prefix of the raw bytes that doesn't end in the middle of a character
+// in the provided encoding.
+fn whole_chars_len(bytes: &[u8], encoding: Encoding) -> usize {
+    match encoding {
+        Encoding::Utf8 => match std::str::from_utf8(bytes) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => bytes.len(),
+        },
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            let len = bytes.len() - bytes.len() % 2;
+            let last = match (encoding, &bytes[len.saturating_sub(2)..len]) {
+                (Encoding::Utf16Le, &[low, high]) => Some(u16::from_le_bytes([low, high])),
+                (_, &[high, low]) => Some(u16::from_be_bytes([high, low])),
+                _ => None,
+            };
+            // a high surrogate is completed by the next code unit
+            match last {
+                Some(0xD800..=0xDBFF) => len - 2,
+                _ => len,
+            }
+        }
+        Encoding::Windows1252 => bytes.len(),
+    }
+}
+
+/// A sample of text decoded from the start of a file in an encoding other than UTF-8.
+pub(crate) struct DecodedSample {
+    pub(crate) text: String,
+    /// The byte order mark dropped from the start of the file, if any.
+    pub(crate) bom: Option<Bom>,
+    /// The number of raw bytes the sample was decoded from (including the byte order mark).
+    pub(crate) n_raw_bytes: usize,
+    /// Whether or not the sample holds the whole file.
+    pub(crate) complete: bool,
+}
+impl DecodedSample {
+    /// Reads and decodes the start of the reader (positioned at the start of the file) a chunk at a
+    /// time, until the sample of decoded text is full. Only the raw bytes of the sample (and of the
+    /// partial character at its end, if any) are read.
+    pub(crate) fn take<R: Read>(
+        mut reader: R,
+        encoding: Encoding,
+        mut sample: SampleBuffer,
+    ) -> Result<DecodedSample> {
+        let mut chunk = vec![0; DECODE_CHUNK_BYTES];
+        // raw bytes read but not decoded yet (the start of the file, or a partial character)
+        let mut raw = vec![];
+        let mut bom = None;
+        let mut at_start = true;
+        let mut complete = false;
+        while !sample.is_full() {
+            let n_read = reader.read(&mut chunk)?;
+            let at_end = n_read == 0;
+            raw.extend_from_slice(&chunk[..n_read]);
+            if at_start {
+                if raw.len() < 3 && !at_end {
+                    continue;
+                }
+                bom = detect_bom(&raw).filter(|bom| bom.encoding() == encoding);
+                raw.drain(..bom.map_or(0, |bom| bom.bytes().len()));
+                at_start = false;
+            }
+            let end = if at_end {
+                raw.len()
+            } else {
+                whole_chars_len(&raw, encoding)
+            };
+            let text = decode_chars(&raw[..end], encoding);
+            raw.drain(..end);
+            let n_used = sample.push(text.as_bytes());
+            if at_end {
+                complete = n_used == text.len();
+                break;
+            }
+            if n_used < text.len() {
+                break;
+            }
+        }
+        let text = String::from_utf8_lossy(&sample.into_bytes()).into_owned();
+        Ok(DecodedSample {
+            n_raw_bytes: bom.map_or(0, |bom| bom.bytes().len()) + encoded_len(&text, encoding),
+            text,
+            bom,
+            complete,
+        })
+    }
+}
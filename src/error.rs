@@ -14,6 +14,22 @@ pub enum SnifferError {
     Csv(csv::Error),
     /// A CSV sniffing error
     SniffingFailed(String),
+    /// The file is a fixed-width (whitespace-aligned) table rather than a delimited one. Contains
+    /// the byte offset at which each column starts.
+    FixedWidth(Vec<usize>),
+    /// An HTTP error (from the ureq crate), when sniffing a remote file
+    #[cfg(feature = "http")]
+    Http(Box<ureq::Error>),
+    /// Invalid JSON metadata (see
+    /// [`Metadata::from_json`](../metadata/struct.Metadata.html#method.from_json))
+    #[cfg(feature = "serde")]
+    Json(String),
+    /// Invalid dialect specification (see
+    /// [`Dialect::from_spec`](../metadata/struct.Dialect.html#method.from_spec))
+    DialectSpec(String),
+    /// Sniffing was cancelled through a
+    /// [`CancellationToken`](../progress/struct.CancellationToken.html)
+    Cancelled,
 }
 
 /// Ease-of-use `Result` type with a `SnifferError`.
@@ -25,6 +41,17 @@ impl fmt::Display for SnifferError {
             SnifferError::Io(ref err) => write!(f, "IO error: {}", err),
             SnifferError::Csv(ref err) => write!(f, "CSV read error: {}", err),
             SnifferError::SniffingFailed(ref s) => write!(f, "Sniffing failed: {}", s),
+            SnifferError::FixedWidth(ref starts) => write!(
+                f,
+                "Fixed-width file detected: columns start at bytes {:?}",
+                starts
+            ),
+            #[cfg(feature = "http")]
+            SnifferError::Http(ref err) => write!(f, "HTTP error: {}", err),
+            #[cfg(feature = "serde")]
+            SnifferError::Json(ref s) => write!(f, "Invalid JSON metadata: {}", s),
+            SnifferError::DialectSpec(ref s) => write!(f, "Invalid dialect specification: {}", s),
+            SnifferError::Cancelled => write!(f, "Sniffing cancelled"),
         }
     }
 }
@@ -34,7 +61,14 @@ impl Error for SnifferError {
         match *self {
             SnifferError::Io(ref err) => Some(err),
             SnifferError::Csv(ref err) => Some(err),
-            SnifferError::SniffingFailed(_) => None,
+            #[cfg(feature = "http")]
+            SnifferError::Http(ref err) => Some(err),
+            SnifferError::SniffingFailed(_)
+            | SnifferError::FixedWidth(_)
+            | SnifferError::DialectSpec(_)
+            | SnifferError::Cancelled => None,
+            #[cfg(feature = "serde")]
+            SnifferError::Json(_) => None,
         }
     }
 }
@@ -49,3 +83,9 @@ impl From<csv::Error> for SnifferError {
         SnifferError::Csv(err)
     }
 }
+#[cfg(feature = "http")]
+impl From<ureq::Error> for SnifferError {
+    fn from(err: ureq::Error) -> SnifferError {
+        SnifferError::Http(Box::new(err))
+    }
+}
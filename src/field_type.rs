@@ -1,30 +1,95 @@
+use std::borrow::Cow;
+use std::collections::BTreeSet;
 use std::fmt;
+use std::mem;
+use std::net::IpAddr;
 
 use bitflags::bitflags;
 use csv::StringRecord;
 
+use crate::datetime::{
+    date_order, matches_format, swap_day_month, DATETIME_FORMATS, DATE_FORMATS, TIME_FORMATS,
+};
+use crate::metadata::{DateOrder, LengthStats, NumberFormat, NumericStats};
+use crate::sample::{ExampleValues, SplitMix64};
+use crate::sketch::HyperLogLog;
+
+/// Maximum number of custom formats of each type with formats (see `TypeOptions::custom_formats`).
+pub(crate) const MAX_CUSTOM_FORMATS: usize = 8;
+
+// Position of the first date-time format flag (one flag for each of `DATETIME_FORMATS`, followed
+// by one for each custom date-time format).
+const DATETIME_SHIFT: usize = 19;
+// Position of the first date format flag (one flag for each of `DATE_FORMATS`, followed by one for
+// each custom date format).
+const DATE_SHIFT: usize = DATETIME_SHIFT + DATETIME_FORMATS.len() + MAX_CUSTOM_FORMATS;
+// Position of the first time format flag (one flag for each of `TIME_FORMATS`, followed by one for
+// each custom time format).
+const TIME_SHIFT: usize = DATE_SHIFT + DATE_FORMATS.len() + MAX_CUSTOM_FORMATS;
+
+// The types whose values have formats, along with the position of their first format flag and
+// their built-in formats.
+const FORMATTED_TYPES: &[(Type, usize, &[&str])] = &[
+    (Type::DateTime, DATETIME_SHIFT, DATETIME_FORMATS),
+    (Type::Date, DATE_SHIFT, DATE_FORMATS),
+    (Type::Time, TIME_SHIFT, TIME_FORMATS),
+];
+
 bitflags! {
     /// Possible guesses for the field type. Implementged as a bitflag struct (see
     /// [`bitflags`](https://docs.rs/bitflags/)).
     #[derive(Default)]
-    pub(crate) struct TypeGuesses: u32 {
+    pub(crate) struct TypeGuesses: u128 {
         const BOOLEAN   = 0b00000001;
         const UNSIGNED  = 0b00000010;
         const SIGNED    = 0b00000100;
         const FLOAT     = 0b00001000;
         const TEXT      = 0b00010000;
+        const DECIMAL   = 0b00100000;
+        const CURRENCY  = 0b01000000;
+        const PERCENT   = 0b10000000;
+        const UUID      = 0b100000000;
+        const EMAIL     = 0b1000000000;
+        const URL       = 0b10000000000;
+        const IP_ADDR   = 0b100000000000;
+        const PHONE     = 0b1000000000000;
+        const JSON      = 0b10000000000000;
+        const BINARY    = 0b100000000000000;
+        const HEX       = 0b1000000000000000;
+        const DIGITS    = 0b10000000000000000;
+        const BIG_INT   = 0b100000000000000000;
+        const PLAIN     = 0b1000000000000000000;
+        const DATETIME  = ((1 << (DATETIME_FORMATS.len() + MAX_CUSTOM_FORMATS)) - 1)
+            << DATETIME_SHIFT;
+        const DATE      = ((1 << (DATE_FORMATS.len() + MAX_CUSTOM_FORMATS)) - 1) << DATE_SHIFT;
+        const TIME      = ((1 << (TIME_FORMATS.len() + MAX_CUSTOM_FORMATS)) - 1) << TIME_SHIFT;
     }
 }
 
 impl TypeGuesses {
     /// Compute the 'best-fitting' `Type` among the guesses of this struct. 'Best-fitting' in this
     /// case means the narrowest definition: `Type::Boolean` being the narrowest, and `Type::Text`
-    /// being the widest (since everything can be a text field).
-    pub(crate) fn best(&self) -> Type {
+    /// being the widest (since everything can be a text field). The details of the numeric values
+    /// are used for `Type::Decimal` and `Type::Currency`.
+    pub(crate) fn best(&self, details: NumberDetails) -> Type {
+        // if all values are null (they fit every type), the type is undetermined
+        if self.is_all() {
+            Type::Null
+        }
         // if all values are some sort of boolean (0 or 1, or 'true' and 'false'), guess boolean
-        if self.contains(TypeGuesses::BOOLEAN) {
+        else if self.contains(TypeGuesses::BOOLEAN) {
             Type::Boolean
         }
+        // if all values are dates of the same format, guess date (before checking for integers,
+        // so compact dates like 20210304 are dates)
+        else if self.intersects(TypeGuesses::DATE) {
+            Type::Date
+        }
+        // if all values are phone numbers (only detected with the `phone` feature), they aren't
+        // numeric, even if they parse as such
+        else if self.contains(TypeGuesses::PHONE) {
+            Type::Text
+        }
         // if all values are integer and > 0, guess unsigned
         else if self.contains(TypeGuesses::UNSIGNED) {
             Type::Unsigned
@@ -33,10 +98,68 @@ impl TypeGuesses {
         else if self.contains(TypeGuesses::SIGNED) {
             Type::Signed
         }
+        // if all values are integer, but some don't fit in 64 bits, guess big integer (rather
+        // than losing precision as floats)
+        else if self.contains(TypeGuesses::BIG_INT) {
+            Type::BigInt
+        }
+        // if all values are plain decimal numbers (and decimals are detected), guess decimal
+        else if self.contains(TypeGuesses::DECIMAL) {
+            Type::Decimal {
+                precision: (details.integer + details.fraction).max(1),
+                scale: details.fraction,
+            }
+        }
         // if all values are numeric, but non-integer, guess float
         else if self.contains(TypeGuesses::FLOAT) {
             Type::Float
         }
+        // if all values are digits (but not all numbers, since some have significant leading
+        // zeros), guess numeric string
+        else if self.contains(TypeGuesses::DIGITS) {
+            Type::NumericString
+        }
+        // if all values are amounts (and not all plain numbers), guess currency
+        else if self.contains(TypeGuesses::CURRENCY) {
+            Type::Currency {
+                symbol: details.symbol,
+            }
+        }
+        // if all values are percentages (and not all plain numbers), guess percentage
+        else if self.contains(TypeGuesses::PERCENT) {
+            Type::Percentage
+        }
+        // if all values are date-times of the same format, guess date-time
+        else if self.intersects(TypeGuesses::DATETIME) {
+            Type::DateTime
+        }
+        // if all values are times of day of the same format, guess time
+        else if self.intersects(TypeGuesses::TIME) {
+            Type::Time
+        }
+        // if all values are UUIDs, guess UUID
+        else if self.contains(TypeGuesses::UUID) {
+            Type::Uuid
+        }
+        // if all values are IP addresses, guess IP address
+        else if self.contains(TypeGuesses::IP_ADDR) {
+            Type::IpAddr
+        }
+        // if all values are JSON objects or arrays, guess JSON
+        else if self.contains(TypeGuesses::JSON) {
+            Type::Json
+        }
+        // if all values are hexadecimal (and not all plain integers), guess hexadecimal
+        else if self.contains(TypeGuesses::HEX) {
+            Type::Hex
+        }
+        // if all values are (long) base64 strings, guess binary
+        else if self.contains(TypeGuesses::BINARY) {
+            Type::Binary {
+                encoding: BinaryEncoding::Base64,
+                max_len: details.length,
+            }
+        }
         // doesn't fit anything else, it's a text field
         else {
             Type::Text
@@ -47,34 +170,947 @@ impl TypeGuesses {
     /// TypesGuesses::TEXT, then `allows` returns `false` (since self is more restrictive than
     /// other).
     pub(crate) fn allows(&self, other: &TypeGuesses) -> bool {
-        !(*self - *other).is_empty()
+        (*self - *other).is_empty()
+    }
+    /// The format of the values of the 'best-fitting' `Type` (see [`best`](#method.best)), if the
+    /// type has formats: the most preferred format that all values match, in the provided date
+    /// order if the values can be read either way.
+    pub(crate) fn format<'a>(&self, order: DateOrder, options: &'a TypeOptions) -> Option<&'a str> {
+        let (format, swapped) = self.formats(options)?;
+        match swapped {
+            Some(swapped) if date_order(format) != Some(order) => Some(swapped),
+            _ => Some(format),
+        }
+    }
+    /// The order of the day and month in the values of the 'best-fitting' `Type`, if the type has
+    /// formats with both, along with whether or not the values can be read either way.
+    pub(crate) fn date_order(&self, options: &TypeOptions) -> Option<(DateOrder, bool)> {
+        let (format, swapped) = self.formats(options)?;
+        Some((date_order(format)?, swapped.is_some()))
+    }
+    // The most preferred format that all values of the 'best-fitting' `Type` match (if the type
+    // has formats), along with the same format with the day and month the other way around, if
+    // all values match that one as well.
+    fn formats<'a>(&self, options: &'a TypeOptions) -> Option<(&'a str, Option<&'a str>)> {
+        let best = self.best(NumberDetails::default());
+        let &(_, shift, _) = FORMATTED_TYPES.iter().find(|&&(ty, ..)| ty == best)?;
+        let formats = options.formats(best).collect::<Vec<_>>();
+        let matches = |i: usize| self.contains(format_flag(shift, i));
+        let i = (0..formats.len()).find(|&i| matches(i))?;
+        let swapped = swap_day_month(formats[i]);
+        let swapped = (0..formats.len())
+            .find(|&j| j != i && formats[j] == swapped && matches(j))
+            .map(|j| formats[j]);
+        Some((formats[i], swapped))
+    }
+    /// The guesses consistent with a column of the provided type and format (for types with
+    /// formats): like `From<Type>`, but with only the flag of the format.
+    pub(crate) fn with_format(
+        ty: Type,
+        format: Option<&str>,
+        options: &TypeOptions,
+    ) -> TypeGuesses {
+        FORMATTED_TYPES
+            .iter()
+            .find(|&&(formatted, ..)| formatted == ty)
+            .and_then(|&(_, shift, _)| {
+                let i = options
+                    .formats(ty)
+                    .position(|known| Some(known) == format)?;
+                Some(format_flag(shift, i) | TypeGuesses::TEXT)
+            })
+            .unwrap_or_else(|| TypeGuesses::from(ty))
+    }
+    /// The semantic type of the values, if the 'best-fitting' `Type` is `Type::Text` (see
+    /// [`best`](#method.best)) and all values have one.
+    pub(crate) fn semantic_type(&self) -> Option<SemanticType> {
+        if self.best(NumberDetails::default()) != Type::Text {
+            None
+        } else if self.contains(TypeGuesses::EMAIL) {
+            Some(SemanticType::Email)
+        } else if self.contains(TypeGuesses::URL) {
+            Some(SemanticType::Url)
+        } else {
+            #[cfg(feature = "phone")]
+            if self.contains(TypeGuesses::PHONE) {
+                return Some(SemanticType::Phone);
+            }
+            None
+        }
+    }
+    /// The guesses of a column known to be of the provided type: these guesses narrowed to the
+    /// type, if it's still their 'best-fitting' type (keeping the formats that all values match,
+    /// for types with formats, and whether or not all values are plain); otherwise, the guesses
+    /// consistent with the type.
+    pub(crate) fn hinted(self, ty: Type) -> TypeGuesses {
+        let narrowed = self & (TypeGuesses::from(ty) | TypeGuesses::PLAIN);
+        let best = narrowed.best(NumberDetails::of_type(ty));
+        if std::mem::discriminant(&best) == std::mem::discriminant(&ty) {
+            narrowed
+        } else {
+            TypeGuesses::from(ty)
+        }
+    }
+    /// The flag of the provided format with the day and month the other way around (for types
+    /// with formats), if there's such a format; empty otherwise.
+    pub(crate) fn with_swapped_format(
+        ty: Type,
+        format: Option<&str>,
+        options: &TypeOptions,
+    ) -> TypeGuesses {
+        let Some(swapped) = format.map(swap_day_month) else {
+            return TypeGuesses::empty();
+        };
+        FORMATTED_TYPES
+            .iter()
+            .find(|&&(formatted, ..)| formatted == ty)
+            .and_then(|&(_, shift, _)| {
+                let i = options.formats(ty).position(|known| known == swapped)?;
+                Some(format_flag(shift, i))
+            })
+            .unwrap_or_else(TypeGuesses::empty)
     }
 }
 
-pub(crate) fn infer_types(s: &str) -> TypeGuesses {
-    if s.is_empty() {
-        // empty fields can be of any type; or rather, of no known type
+// The flag of the format at the provided index of the formats whose flags start at `shift`.
+fn format_flag(shift: usize, i: usize) -> TypeGuesses {
+    TypeGuesses::from_bits_truncate(1 << (shift + i))
+}
+
+impl From<Type> for TypeGuesses {
+    /// The guesses consistent with a column of the provided type: the type itself, along with the
+    /// wider types that every value of the type also fits.
+    fn from(ty: Type) -> TypeGuesses {
+        match ty {
+            Type::Boolean => TypeGuesses::BOOLEAN | TypeGuesses::TEXT,
+            Type::Unsigned => {
+                TypeGuesses::UNSIGNED
+                    | TypeGuesses::SIGNED
+                    | TypeGuesses::BIG_INT
+                    | TypeGuesses::FLOAT
+                    | TypeGuesses::CURRENCY
+                    | TypeGuesses::PERCENT
+                    | TypeGuesses::TEXT
+            }
+            Type::Signed => {
+                TypeGuesses::SIGNED
+                    | TypeGuesses::BIG_INT
+                    | TypeGuesses::FLOAT
+                    | TypeGuesses::CURRENCY
+                    | TypeGuesses::PERCENT
+                    | TypeGuesses::TEXT
+            }
+            Type::Decimal { .. } => {
+                TypeGuesses::DECIMAL
+                    | TypeGuesses::FLOAT
+                    | TypeGuesses::CURRENCY
+                    | TypeGuesses::PERCENT
+                    | TypeGuesses::TEXT
+            }
+            Type::Currency { .. } => TypeGuesses::CURRENCY | TypeGuesses::TEXT,
+            Type::Percentage => TypeGuesses::PERCENT | TypeGuesses::TEXT,
+            Type::Float => TypeGuesses::FLOAT | TypeGuesses::PERCENT | TypeGuesses::TEXT,
+            Type::DateTime => TypeGuesses::DATETIME | TypeGuesses::TEXT,
+            Type::Date => TypeGuesses::DATE | TypeGuesses::TEXT,
+            Type::Time => TypeGuesses::TIME | TypeGuesses::TEXT,
+            Type::Text => TypeGuesses::TEXT,
+            Type::Uuid => TypeGuesses::UUID | TypeGuesses::TEXT,
+            Type::IpAddr => TypeGuesses::IP_ADDR | TypeGuesses::TEXT,
+            Type::Json => TypeGuesses::JSON | TypeGuesses::TEXT,
+            Type::Binary { .. } => TypeGuesses::BINARY | TypeGuesses::TEXT,
+            Type::Hex => TypeGuesses::HEX | TypeGuesses::TEXT,
+            Type::NumericString => TypeGuesses::DIGITS | TypeGuesses::TEXT,
+            Type::BigInt => {
+                TypeGuesses::BIG_INT
+                    | TypeGuesses::FLOAT
+                    | TypeGuesses::CURRENCY
+                    | TypeGuesses::PERCENT
+                    | TypeGuesses::TEXT
+            }
+            Type::Categorical { .. } => TypeGuesses::TEXT,
+            Type::Null => TypeGuesses::all(),
+        }
+    }
+}
+
+/// Options for inferring the types of fields.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TypeOptions {
+    /// Tokens of `true` boolean values (matched case-insensitively).
+    pub(crate) true_tokens: Vec<String>,
+    /// Tokens of `false` boolean values (matched case-insensitively).
+    pub(crate) false_tokens: Vec<String>,
+    /// Whether or not to detect `Type::Decimal`.
+    pub(crate) decimals: bool,
+    /// Tokens of null values (matched exactly).
+    pub(crate) null_tokens: Vec<String>,
+    /// Separators used in numbers.
+    pub(crate) number_format: NumberFormat,
+    /// Order of the day and month in dates that can be read either way.
+    pub(crate) date_preference: DateOrder,
+    /// Additional formats of date-times, dates and times (less preferred than the built-in ones),
+    /// along with their types.
+    pub(crate) custom_formats: Vec<(Type, String)>,
+    /// Maximum number of levels of `Type::Categorical` columns (0 if not detected).
+    pub(crate) max_levels: usize,
+    /// Whether or not to detect semantic types.
+    pub(crate) semantic_types: bool,
+    /// Whether or not digits with leading zeros are strings rather than numbers.
+    pub(crate) leading_zeros: bool,
+    /// Share of the (non-null) values of a column that need to fit its type.
+    pub(crate) tolerance: f64,
+    /// Whether or not to accumulate statistics of numeric values, of the lengths of values and of
+    /// the numbers of distinct values.
+    pub(crate) statistics: bool,
+    /// Which values to keep as examples.
+    pub(crate) examples: ExampleValues,
+}
+impl Default for TypeOptions {
+    fn default() -> TypeOptions {
+        TypeOptions {
+            true_tokens: ["true", "yes", "y", "t"].map(String::from).to_vec(),
+            false_tokens: ["false", "no", "n", "f"].map(String::from).to_vec(),
+            decimals: false,
+            null_tokens: ["", "NULL", "NA", "\\N"].map(String::from).to_vec(),
+            number_format: NumberFormat::default(),
+            date_preference: DateOrder::MonthFirst,
+            custom_formats: vec![],
+            max_levels: 0,
+            semantic_types: false,
+            leading_zeros: false,
+            tolerance: 1.0,
+            statistics: false,
+            examples: ExampleValues::None,
+        }
+    }
+}
+impl TypeOptions {
+    fn is_boolean(&self, s: &str) -> bool {
+        self.true_tokens
+            .iter()
+            .chain(&self.false_tokens)
+            .any(|token| token.eq_ignore_ascii_case(s))
+    }
+    pub(crate) fn is_null(&self, s: &str) -> bool {
+        self.null_tokens.iter().any(|token| token == s)
+    }
+    /// The formats of the values of the provided type, from most to least preferred: the built-in
+    /// formats, followed by (at most `MAX_CUSTOM_FORMATS` of) the custom formats of the type. Empty
+    /// for types without formats.
+    pub(crate) fn formats(&self, ty: Type) -> impl Iterator<Item = &str> {
+        let builtin = FORMATTED_TYPES
+            .iter()
+            .find(|&&(formatted, ..)| formatted == ty)
+            .map_or(&[][..], |&(_, _, formats)| formats);
+        let custom = self
+            .custom_formats
+            .iter()
+            .filter(move |&&(custom_ty, _)| custom_ty == ty)
+            .map(|(_, format)| format.as_str())
+            .take(MAX_CUSTOM_FORMATS);
+        builtin.iter().copied().chain(custom)
+    }
+}
+
+pub(crate) fn infer_types(s: &str, options: &TypeOptions) -> TypeGuesses {
+    if options.is_null(s) {
+        // null fields can be of any type; or rather, of no known type
         return TypeGuesses::all();
     }
     let mut guesses = TypeGuesses::default();
     guesses |= TypeGuesses::TEXT;
-    if s.parse::<u64>().is_ok() {
+    let number = strip_grouping(s, &options.number_format);
+    // booleans and numbers that the standard library parses as they are (without grouping
+    // separators or a decimal comma) are plain
+    if s == "true" || s == "false" || (number == s && s.parse::<f64>().is_ok()) {
+        guesses |= TypeGuesses::PLAIN;
+    }
+    if number.parse::<u64>().is_ok() {
         guesses |= TypeGuesses::UNSIGNED;
     }
-    if s.parse::<i64>().is_ok() {
+    if number.parse::<i64>().is_ok() {
         guesses |= TypeGuesses::SIGNED;
     }
-    if s.parse::<bool>().is_ok() {
+    let digits = number.strip_prefix(['+', '-']).unwrap_or(&number);
+    if !digits.is_empty() && digits.bytes().all(|chr| chr.is_ascii_digit()) {
+        guesses |= TypeGuesses::BIG_INT;
+    }
+    if options.is_boolean(s) {
         guesses |= TypeGuesses::BOOLEAN;
     }
-    if s.parse::<f64>().is_ok() {
+    if parse_float(&number, &options.number_format).is_some() {
         guesses |= TypeGuesses::FLOAT;
     }
+    if let Some((_, is_plain)) = parse_amount(s, &options.number_format) {
+        guesses |= TypeGuesses::CURRENCY;
+        if options.decimals && is_plain {
+            guesses |= TypeGuesses::DECIMAL;
+        }
+    }
+    // plain numbers are percentages as well, so that columns mixing both are percentages
+    let number = s.strip_suffix('%').map_or(s, str::trim_end);
+    if parse_float(
+        &strip_grouping(number, &options.number_format),
+        &options.number_format,
+    )
+    .is_some()
+    {
+        guesses |= TypeGuesses::PERCENT;
+    }
+    if !s.is_empty() && s.bytes().all(|chr| chr.is_ascii_digit()) {
+        guesses |= TypeGuesses::DIGITS;
+        // leading zeros are significant (e.g. in ZIP codes), so the digits aren't a number
+        if options.leading_zeros && s.len() > 1 && s.starts_with('0') {
+            guesses &= !(TypeGuesses::UNSIGNED
+                | TypeGuesses::SIGNED
+                | TypeGuesses::BIG_INT
+                | TypeGuesses::FLOAT
+                | TypeGuesses::DECIMAL
+                | TypeGuesses::CURRENCY
+                | TypeGuesses::PERCENT);
+        }
+    }
+    if is_uuid(s) {
+        guesses |= TypeGuesses::UUID;
+    }
+    if s.parse::<IpAddr>().is_ok() {
+        guesses |= TypeGuesses::IP_ADDR;
+    }
+    if is_json(s) {
+        guesses |= TypeGuesses::JSON;
+    }
+    if base64_len(s).is_some() {
+        guesses |= TypeGuesses::BINARY;
+    }
+    if is_hex(s) {
+        guesses |= TypeGuesses::HEX;
+    }
+    if options.semantic_types && is_email(s) {
+        guesses |= TypeGuesses::EMAIL;
+    }
+    if options.semantic_types && is_url(s) {
+        guesses |= TypeGuesses::URL;
+    }
+    #[cfg(feature = "phone")]
+    if options.semantic_types && is_phone(s) {
+        guesses |= TypeGuesses::PHONE;
+    }
+    // every built-in format starts with a digit or a month name
+    let is_alphanumeric = s
+        .bytes()
+        .next()
+        .is_some_and(|chr| chr.is_ascii_alphanumeric());
+    for &(ty, shift, builtin) in FORMATTED_TYPES {
+        let skip = if is_alphanumeric { 0 } else { builtin.len() };
+        for (i, format) in options.formats(ty).enumerate().skip(skip) {
+            if matches_format(s, format) {
+                guesses |= format_flag(shift, i);
+            }
+        }
+    }
     guesses
 }
 
-pub(crate) fn infer_record_types(record: &StringRecord) -> Vec<TypeGuesses> {
-    record.iter().map(infer_types).collect()
+// Currency symbols recognized before (or after) amounts.
+const CURRENCY_SYMBOLS: &[char] = &[
+    '$', '€', '£', '¥', '₹', '₩', '₽', '¢', '₺', '₪', '฿', '₫', '₴', '₦', '₱',
+];
+
+/// Details of the numeric values of a field: the largest numbers of digits before and after the
+/// decimal point, and the currency symbol of the values (the first one seen, if any). Also keeps
+/// the largest decoded length of the field's binary values.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct NumberDetails {
+    /// Number of digits before the decimal point (not counting leading zeros).
+    pub(crate) integer: usize,
+    /// Number of digits after the decimal point.
+    pub(crate) fraction: usize,
+    /// Currency symbol.
+    pub(crate) symbol: Option<char>,
+    /// Decoded length (in bytes) of binary values.
+    pub(crate) length: usize,
+}
+impl NumberDetails {
+    /// The details of a numeric value (an amount, see `parse_amount`) or of a binary value (see
+    /// `base64_len`), or `None` for other values.
+    pub(crate) fn of(s: &str, format: &NumberFormat) -> Option<NumberDetails> {
+        parse_amount(s, format)
+            .map(|(details, _)| details)
+            .or_else(|| {
+                base64_len(s).map(|length| NumberDetails {
+                    length,
+                    ..NumberDetails::default()
+                })
+            })
+    }
+    /// The details of the values of a column of the provided type (none, unless the type is
+    /// `Type::Decimal`, `Type::Currency` or `Type::Binary`).
+    pub(crate) fn of_type(ty: Type) -> NumberDetails {
+        match ty {
+            Type::Decimal { precision, scale } => NumberDetails {
+                integer: precision - scale,
+                fraction: scale,
+                ..NumberDetails::default()
+            },
+            Type::Currency { symbol } => NumberDetails {
+                symbol,
+                ..NumberDetails::default()
+            },
+            Type::Binary { max_len, .. } => NumberDetails {
+                length: max_len,
+                ..NumberDetails::default()
+            },
+            _ => NumberDetails::default(),
+        }
+    }
+    /// Widens these details to fit the provided details as well.
+    pub(crate) fn widen(&mut self, other: NumberDetails) {
+        self.integer = self.integer.max(other.integer);
+        self.fraction = self.fraction.max(other.fraction);
+        self.symbol = self.symbol.or(other.symbol);
+        self.length = self.length.max(other.length);
+    }
+    /// Whether or not these digits fit within the digits of the provided details.
+    pub(crate) fn fits(&self, other: NumberDetails) -> bool {
+        self.integer <= other.integer && self.fraction <= other.fraction
+    }
+}
+
+/// Parses a value written as an amount: digits with an optional decimal separator (and no
+/// exponent), optionally with grouping separators (`,` by default: `1,234.56`), a currency symbol
+/// before or after the number (`$12`, `12 €`), a sign, or accounting-style parentheses for
+/// negative amounts (`(1,000.00)`). Returns the details of the amount, and whether or not it's a
+/// plain decimal number (with none of the above decorations but the sign and grouping separators),
+/// or `None` for other values.
+pub(crate) fn parse_amount(s: &str, format: &NumberFormat) -> Option<(NumberDetails, bool)> {
+    let mut is_plain = true;
+    let mut rest = match s
+        .strip_prefix('(')
+        .and_then(|inner| inner.strip_suffix(')'))
+    {
+        Some(inner) => {
+            is_plain = false;
+            inner
+        }
+        None => s,
+    };
+    rest = rest.strip_prefix(['+', '-']).unwrap_or(rest);
+    let mut symbol = None;
+    if let Some(chr) = rest
+        .chars()
+        .next()
+        .filter(|chr| CURRENCY_SYMBOLS.contains(chr))
+    {
+        symbol = Some(chr);
+        rest = rest[chr.len_utf8()..].trim_start();
+        // the sign can also follow the symbol
+        rest = rest.strip_prefix(['+', '-']).unwrap_or(rest);
+    } else if let Some(chr) = rest
+        .chars()
+        .next_back()
+        .filter(|chr| CURRENCY_SYMBOLS.contains(chr))
+    {
+        symbol = Some(chr);
+        rest = rest[..rest.len() - chr.len_utf8()].trim_end();
+    }
+    if symbol.is_some() {
+        is_plain = false;
+    }
+
+    let grouping = format.grouping_separator.unwrap_or(other_separator(format));
+    let (integer, fraction) = rest
+        .split_once(format.decimal_separator)
+        .unwrap_or((rest, ""));
+    let is_digits = |part: &str| part.bytes().all(|chr| chr.is_ascii_digit());
+    if integer.contains(grouping) {
+        let mut groups = integer.split(grouping);
+        // unwrap is safe: split always yields at least one item
+        let first = groups.next().unwrap();
+        if first.is_empty()
+            || first.len() > 3
+            || !is_digits(first)
+            || !groups.all(|group| group.len() == 3 && is_digits(group))
+        {
+            return None;
+        }
+    } else if !is_digits(integer) {
+        return None;
+    }
+    if (integer.is_empty() && fraction.is_empty()) || !is_digits(fraction) {
+        return None;
+    }
+    let n_integer_digits = integer
+        .bytes()
+        .filter(|chr| chr.is_ascii_digit())
+        .skip_while(|&chr| chr == b'0')
+        .count();
+    Some((
+        NumberDetails {
+            integer: n_integer_digits,
+            fraction: fraction.len(),
+            symbol,
+            length: 0,
+        },
+        is_plain,
+    ))
+}
+
+// Parses a floating-point number, with the decimal separator of the provided format.
+fn parse_float(s: &str, format: &NumberFormat) -> Option<f64> {
+    if format.decimal_separator == '.' {
+        s.parse().ok()
+    } else if s.contains('.') {
+        None
+    } else {
+        s.replacen(format.decimal_separator, ".", 1).parse().ok()
+    }
+}
+
+// Parses the value of a number (or a percentage, as written), with the separators of the provided
+// format.
+pub(crate) fn parse_number(s: &str, format: &NumberFormat) -> Option<f64> {
+    let s = s.strip_suffix('%').map_or(s, str::trim_end);
+    parse_float(&strip_grouping(s, format), format).filter(|number| number.is_finite())
+}
+
+// Whether or not a value is a UUID (GUID): 32 hexadecimal digits, either by themselves or in groups
+// of 8, 4, 4, 4 and 12 digits separated by dashes (optionally in braces).
+fn is_uuid(s: &str) -> bool {
+    let s = s
+        .strip_prefix('{')
+        .and_then(|inner| inner.strip_suffix('}'))
+        .unwrap_or(s);
+    let is_hex = |group: &str| group.bytes().all(|chr| chr.is_ascii_hexdigit());
+    match s.len() {
+        32 => is_hex(s),
+        36 => {
+            let groups = s.split('-').collect::<Vec<_>>();
+            groups.iter().map(|group| group.len()).eq([8, 4, 4, 4, 12])
+                && groups.iter().all(|group| is_hex(group))
+        }
+        _ => false,
+    }
+}
+
+// Whether or not a value is a JSON object or array: a lightweight check that it starts with `{` or
+// `[`, and that its braces and brackets (outside of strings) are balanced, with the first one only
+// closed at the end of the value.
+fn is_json(s: &str) -> bool {
+    let s = s.trim();
+    if !s.starts_with(['{', '[']) {
+        return false;
+    }
+    let mut open = vec![];
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, chr) in s.char_indices() {
+        if in_string {
+            match chr {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match chr {
+            '"' => in_string = true,
+            '{' | '[' => open.push(chr),
+            '}' | ']' => {
+                let expected = if chr == '}' { '{' } else { '[' };
+                if open.pop() != Some(expected) {
+                    return false;
+                }
+                if open.is_empty() {
+                    return i == s.len() - 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+// Minimum length of the hexadecimal values without a `0x` prefix (shorter values are more likely to
+// be words or codes).
+const MIN_BARE_HEX_LEN: usize = 8;
+
+// Whether or not a value is hexadecimal: hexadecimal digits with a `0x` prefix (e.g. `0x1A2B`), or
+// at least `MIN_BARE_HEX_LEN` of them (including at least one decimal digit) without one (e.g.
+// `8cC6B5992C0309c`).
+fn is_hex(s: &str) -> bool {
+    let is_hex_digits =
+        |digits: &str| !digits.is_empty() && digits.bytes().all(|chr| chr.is_ascii_hexdigit());
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(digits) => is_hex_digits(digits),
+        None => {
+            s.len() >= MIN_BARE_HEX_LEN
+                && is_hex_digits(s)
+                && s.bytes().any(|chr| chr.is_ascii_digit())
+        }
+    }
+}
+
+// Minimum length of the values considered base64-encoded binary (shorter values are more likely
+// to be words or identifiers).
+const MIN_BASE64_LEN: usize = 24;
+
+// The decoded length (in bytes) of a value, if it's a long base64 string: at least
+// `MIN_BASE64_LEN` characters of the standard base64 alphabet, padded to a multiple of 4, with both
+// upper- and lower-case letters (or the `+` and `/` characters, or padding), so that hexadecimal
+// digests and other identifiers aren't mistaken for base64.
+fn base64_len(s: &str) -> Option<usize> {
+    let data = s.trim_end_matches('=');
+    let padding = s.len() - data.len();
+    let is_base64_chr = |chr: u8| chr.is_ascii_alphanumeric() || chr == b'+' || chr == b'/';
+    let has = |pred: fn(&u8) -> bool| data.bytes().any(|chr| pred(&chr));
+    let is_mixed = padding > 0
+        || has(|&chr| chr == b'+' || chr == b'/')
+        || (has(u8::is_ascii_uppercase) && has(u8::is_ascii_lowercase));
+    (s.len() >= MIN_BASE64_LEN
+        && s.len().is_multiple_of(4)
+        && padding <= 2
+        && data.bytes().all(is_base64_chr)
+        && is_mixed)
+        .then(|| s.len() / 4 * 3 - padding)
+}
+
+// Whether or not a value is an email address: a local part (without spaces) and a domain name with
+// at least two labels (of letters, digits and hyphens), the last of which is at least two letters.
+fn is_email(s: &str) -> bool {
+    let Some((local, domain)) = s.split_once('@') else {
+        return false;
+    };
+    let is_label = |label: &str| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .bytes()
+                .all(|chr| chr.is_ascii_alphanumeric() || chr == b'-')
+    };
+    let labels = domain.split('.').collect::<Vec<_>>();
+    !local.is_empty()
+        && !local.contains(|chr: char| chr.is_whitespace() || chr == '@')
+        && labels.len() >= 2
+        && labels.iter().all(|label| is_label(label))
+        && labels[labels.len() - 1].len() >= 2
+        && labels[labels.len() - 1]
+            .bytes()
+            .all(|chr| chr.is_ascii_alphabetic())
+}
+
+// Whether or not a value is a URL: a scheme (starting with a letter), followed by `://`, a host and
+// optionally a path, query and fragment, without spaces.
+fn is_url(s: &str) -> bool {
+    let Some((scheme, rest)) = s.split_once("://") else {
+        return false;
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    scheme.starts_with(|chr: char| chr.is_ascii_alphabetic())
+        && scheme
+            .bytes()
+            .all(|chr| chr.is_ascii_alphanumeric() || b"+-.".contains(&chr))
+        && !host.is_empty()
+        && !rest.contains(char::is_whitespace)
+}
+
+// Whether or not a value is a phone number: 7 to 15 digits, optionally with a leading `+` and an
+// area code in parentheses, in groups separated by spaces, dashes or dots. Values that could also
+// be numbers with grouped thousands (e.g. `1 234 567`) or IP addresses aren't phone numbers.
+#[cfg(feature = "phone")]
+fn is_phone(s: &str) -> bool {
+    let is_digits =
+        |group: &str| !group.is_empty() && group.bytes().all(|chr| chr.is_ascii_digit());
+    let international = s.starts_with('+');
+    let rest = s.strip_prefix('+').unwrap_or(s);
+    let (area, number) = match rest.strip_prefix('(') {
+        Some(rest) => match rest.split_once(')') {
+            Some((area, number)) if is_digits(area) => (area, number.trim_start()),
+            _ => return false,
+        },
+        None => ("", rest),
+    };
+    let groups = number.split([' ', '-', '.']).collect::<Vec<_>>();
+    let n_digits = area.len() + groups.iter().map(|group| group.len()).sum::<usize>();
+    let grouped_thousands =
+        groups[0].len() <= 3 && groups[1..].iter().all(|group| group.len() == 3);
+    groups.iter().all(|group| is_digits(group))
+        && (7..=15).contains(&n_digits)
+        && (international || !area.is_empty() || (groups.len() >= 2 && !grouped_thousands))
+        && s.parse::<IpAddr>().is_err()
+}
+
+/// Counts the occurrences of a character (e.g. `:` or `/`) within the URLs of a line (which
+/// shouldn't be counted as potential delimiters).
+pub(crate) fn count_url_chars(line: &[u8], chr: u8) -> usize {
+    let is_scheme_chr = |chr: &u8| chr.is_ascii_alphanumeric() || b"+-.".contains(chr);
+    let is_url_chr =
+        |chr: &u8| chr.is_ascii_alphanumeric() || b"-._~:/?#[]@!$&()*+=%".contains(chr);
+    let mut n_chrs = 0;
+    // end of the last URL found
+    let mut end = 0;
+    for pos in memchr::memchr_iter(b':', line) {
+        if pos < end || !line[pos..].starts_with(b"://") {
+            continue;
+        }
+        let start = line[..pos]
+            .iter()
+            .rposition(|chr| !is_scheme_chr(chr))
+            .map_or(0, |i| i + 1);
+        let token_end = line[pos..]
+            .iter()
+            .position(|chr| !is_url_chr(chr))
+            .map_or(line.len(), |i| pos + i);
+        if std::str::from_utf8(&line[start..token_end]).is_ok_and(is_url) {
+            n_chrs += memchr::memchr_iter(chr, &line[start..token_end]).count();
+            end = token_end;
+        }
+    }
+    n_chrs
+}
+
+// Removes the grouping separators from a number (e.g. `1,234,567`), if they separate the groups of
+// thousands of its integer part; returns other values unchanged.
+fn strip_grouping<'a>(s: &'a str, format: &NumberFormat) -> Cow<'a, str> {
+    let grouping = format.grouping_separator.unwrap_or(other_separator(format));
+    if !s.contains(grouping) {
+        return Cow::Borrowed(s);
+    }
+    let (integer, fraction) = match s.split_once(format.decimal_separator) {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (s, None),
+    };
+    let digits = integer.strip_prefix(['+', '-']).unwrap_or(integer);
+    let mut groups = digits.split(grouping);
+    // unwrap is safe: split always yields at least one item
+    let first = groups.next().unwrap();
+    let is_digits = |group: &str| group.bytes().all(|chr| chr.is_ascii_digit());
+    if !(1..=3).contains(&first.len())
+        || !is_digits(first)
+        || !groups.all(|group| group.len() == 3 && is_digits(group))
+        || fraction.is_some_and(|fraction| fraction.contains(grouping))
+    {
+        return Cow::Borrowed(s);
+    }
+    let mut stripped = integer.replace(grouping, "");
+    if let Some(fraction) = fraction {
+        stripped.push(format.decimal_separator);
+        stripped.push_str(fraction);
+    }
+    Cow::Owned(stripped)
+}
+
+// The separator that isn't the decimal separator of the provided format, among `.` and `,`: the
+// grouping separator of amounts when the format doesn't specify one.
+fn other_separator(format: &NumberFormat) -> char {
+    if format.decimal_separator == ',' {
+        '.'
+    } else {
+        ','
+    }
+}
+
+// Maximum number of values of each type kept as examples (see `TypeCounts`).
+const MAX_EXAMPLES: usize = 10;
+
+/// Numbers of values of a field of each type: the 'best-fitting' type of each value by itself (see
+/// `TypeGuesses::best`), with the details of the numeric values of each type widened to fit them
+/// all. Also keeps the first values of each type, and the numbers of (non-null) values with each
+/// of the guess flags, and (if enabled) statistics of the numeric values of each type and of the
+/// lengths and distinct values of the (non-null) values, along with examples of them.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TypeCounts {
+    counts: Vec<TypeCount>,
+    n_values: usize,
+    flags: Vec<usize>,
+    lengths: Option<LengthStats>,
+    distinct: Option<HyperLogLog>,
+    values: Vec<String>,
+}
+#[derive(Debug, Clone)]
+struct TypeCount {
+    ty: Type,
+    // the guesses that all the values of the type fit
+    guesses: TypeGuesses,
+    details: NumberDetails,
+    count: usize,
+    examples: Vec<String>,
+    numbers: Option<NumericStats>,
+}
+impl TypeCounts {
+    /// Counts a value, from its guesses and details.
+    pub(crate) fn add(
+        &mut self,
+        value: &str,
+        guesses: TypeGuesses,
+        details: NumberDetails,
+        options: &TypeOptions,
+    ) {
+        let ty = guesses.best(details);
+        let number = if options.statistics && is_numeric(ty) {
+            parse_number(value, &options.number_format)
+        } else {
+            None
+        };
+        match self
+            .counts
+            .iter_mut()
+            .find(|known| mem::discriminant(&known.ty) == mem::discriminant(&ty))
+        {
+            Some(known) => {
+                known.details.widen(details);
+                known.ty = guesses.best(known.details);
+                known.guesses &= guesses;
+                known.count += 1;
+                if known.examples.len() < MAX_EXAMPLES {
+                    known.examples.push(value.to_string());
+                }
+                known.numbers = merge_stats(known.numbers, number.map(number_stats));
+            }
+            None => self.counts.push(TypeCount {
+                ty,
+                guesses,
+                details,
+                count: 1,
+                examples: vec![value.to_string()],
+                numbers: number.map(number_stats),
+            }),
+        }
+        // null values fit any type
+        if guesses != TypeGuesses::all() {
+            self.n_values += 1;
+            self.flags.resize(u128::BITS as usize, 0);
+            let mut bits = guesses.bits();
+            while bits != 0 {
+                self.flags[bits.trailing_zeros() as usize] += 1;
+                bits &= bits - 1;
+            }
+            if options.statistics {
+                self.lengths = Some(LengthStats::add(self.lengths, value));
+                self.distinct
+                    .get_or_insert_with(HyperLogLog::default)
+                    .add(value);
+            }
+            match options.examples {
+                ExampleValues::First(n) | ExampleValues::Random(n) if self.values.len() < n => {
+                    self.values.push(value.to_string());
+                }
+                ExampleValues::Random(_) => {
+                    // reservoir sampling, with a pseudo-random index for each value
+                    let i = SplitMix64::new(self.n_values as u64).next_u64() % self.n_values as u64;
+                    if let Some(example) = self.values.get_mut(i as usize) {
+                        *example = value.to_string();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    /// The numbers of values of each type, most common first.
+    pub(crate) fn counts(&self) -> Vec<(Type, usize)> {
+        let mut counts = self
+            .counts
+            .iter()
+            .map(|known| (known.ty, known.count))
+            .collect::<Vec<_>>();
+        counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+        counts
+    }
+    /// The number of null values.
+    pub(crate) fn nulls(&self) -> usize {
+        self.counts
+            .iter()
+            .filter(|known| known.ty == Type::Null)
+            .map(|known| known.count)
+            .sum()
+    }
+    /// The guesses that at least the provided share of the (non-null) values fit.
+    pub(crate) fn tolerant(&self, tolerance: f64) -> TypeGuesses {
+        if self.n_values == 0 {
+            return TypeGuesses::all();
+        }
+        let threshold = ((tolerance * self.n_values as f64).ceil() as usize).max(1);
+        self.flags
+            .iter()
+            .enumerate()
+            .filter(|&(_, &count)| count >= threshold)
+            .fold(TypeGuesses::empty(), |guesses, (bit, _)| {
+                guesses | TypeGuesses::from_bits_truncate(1 << bit)
+            })
+    }
+    /// The example values that don't fit a column with the provided guesses.
+    pub(crate) fn offending_values(&self, guesses: TypeGuesses) -> Vec<String> {
+        self.counts
+            .iter()
+            .filter(|known| !fits(known.guesses, guesses))
+            .flat_map(|known| known.examples.iter().cloned())
+            .collect()
+    }
+    /// The statistics of the numeric values that fit a column with the provided guesses.
+    pub(crate) fn statistics(&self, guesses: TypeGuesses) -> Option<NumericStats> {
+        self.counts
+            .iter()
+            .filter(|known| fits(known.guesses, guesses))
+            .fold(None, |stats, known| merge_stats(stats, known.numbers))
+    }
+}
+
+// Whether or not a type has numeric values with statistics.
+pub(crate) fn is_numeric(ty: Type) -> bool {
+    matches!(
+        ty,
+        Type::Unsigned
+            | Type::Signed
+            | Type::BigInt
+            | Type::Float
+            | Type::Decimal { .. }
+            | Type::Percentage
+    )
+}
+
+// The statistics of a single number.
+fn number_stats(number: f64) -> NumericStats {
+    NumericStats {
+        count: 1,
+        min: number,
+        max: number,
+        mean: number,
+    }
+}
+
+// Combines the statistics of two sets of numbers.
+pub(crate) fn merge_stats(
+    a: Option<NumericStats>,
+    b: Option<NumericStats>,
+) -> Option<NumericStats> {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let count = a.count + b.count;
+            Some(NumericStats {
+                count,
+                min: a.min.min(b.min),
+                max: a.max.max(b.max),
+                mean: a.mean + (b.mean - a.mean) * b.count as f64 / count as f64,
+            })
+        }
+        (a, b) => a.or(b),
+    }
+}
+
+// Whether or not values (with the first guesses) fit a column (with the second guesses), i.e. don't
+// change the 'best-fitting' type of the column.
+fn fits(value_guesses: TypeGuesses, guesses: TypeGuesses) -> bool {
+    let best = guesses.best(NumberDetails::default());
+    let narrowed = (value_guesses & guesses).best(NumberDetails::default());
+    mem::discriminant(&narrowed) == mem::discriminant(&best)
+}
+
+pub(crate) fn infer_record_types(record: &StringRecord, options: &TypeOptions) -> Vec<TypeGuesses> {
+    record
+        .iter()
+        .map(|field| infer_types(field, options))
+        .collect()
 }
 
 /// The valid field types for fields in a CSV record.
@@ -86,26 +1122,363 @@ pub enum Type {
     Signed,
     /// Text (any field can be a type)
     Text,
-    /// Boolean (true / false or 0 / 1)
+    /// Boolean (`true` / `false`, `yes` / `no`, `y` / `n` or `t` / `f`, in any case, or the tokens
+    /// specified with [`Sniffer::boolean_tokens`](struct.Sniffer.html#method.boolean_tokens))
     Boolean,
-    /// Floating-point
+    /// Floating-point (including scientific notation, e.g. `1.2e-5` or `3E+10`)
     Float,
+    /// Exact decimal number, with the maximum number of digits (`precision`) and of digits after
+    /// the decimal point (`scale`) seen (e.g. for SQL `NUMERIC(precision, scale)` columns). Only
+    /// detected if enabled with
+    /// [`Sniffer::detect_decimals`](struct.Sniffer.html#method.detect_decimals); otherwise, such
+    /// values are `Float`s.
+    Decimal {
+        /// Total number of significant digits.
+        precision: usize,
+        /// Number of digits after the decimal point.
+        scale: usize,
+    },
+    /// Amount of money, written with a currency symbol or accounting-style parentheses for
+    /// negative amounts (e.g. `$1,234.56`, `€12.00` or `(1,000.00)`)
+    Currency {
+        /// The currency symbol of the amounts (the first one seen), if any.
+        symbol: Option<char>,
+    },
+    /// Percentage, written as a number followed by `%` (e.g. `12.5%` or `-3 %`). The values are the
+    /// numbers before the `%` sign, so they need dividing by 100 to get the fractions.
+    Percentage,
+    /// Date and time (e.g. RFC 3339 or ISO 8601 timestamps), in the format reported in
+    /// [`Metadata::formats`](metadata/struct.Metadata.html#structfield.formats)
+    DateTime,
+    /// Date without a time of day (e.g. `2021-03-04`, `04/03/2021` or `March 4, 2021`), in the
+    /// format reported in [`Metadata::formats`](metadata/struct.Metadata.html#structfield.formats)
+    Date,
+    /// Time of day (e.g. `13:45`, `13:45:09` or `1:45 PM`), in the format reported in
+    /// [`Metadata::formats`](metadata/struct.Metadata.html#structfield.formats)
+    Time,
+    /// Undetermined type, for fields that are null (see
+    /// [`Sniffer::null_tokens`](struct.Sniffer.html#method.null_tokens)) in every record
+    Null,
+    /// UUID (GUID), with or without dashes (e.g. `123e4567-e89b-12d3-a456-426614174000`)
+    Uuid,
+    /// IP address, either IPv4 (e.g. `192.168.0.1`) or IPv6 (e.g. `2001:db8::1`)
+    IpAddr,
+    /// JSON object or array (e.g. `{"event": "click", "x": 10}` or `[1, 2, 3]`)
+    Json,
+    /// Integer, some of whose values don't fit in 64 bits (e.g. 20-digit identifiers or 128-bit
+    /// counters), so they need parsing as `i128` (or as arbitrary-precision integers) to avoid
+    /// losing precision
+    BigInt,
+    /// Digits that aren't a number, since some values have significant leading zeros (e.g. ZIP
+    /// codes like `01234`, or account numbers). Only detected if enabled with
+    /// [`Sniffer::preserve_leading_zeros`](struct.Sniffer.html#method.preserve_leading_zeros).
+    NumericString,
+    /// Hexadecimal integer or identifier, with or without a `0x` prefix (e.g. `0x1A2B` or
+    /// `8cC6B5992C0309c`)
+    Hex,
+    /// Binary data encoded as text (e.g. base64), such as images or other blobs
+    Binary {
+        /// Encoding of the data.
+        encoding: BinaryEncoding,
+        /// Largest decoded length of the values, in bytes.
+        max_len: usize,
+    },
+    /// Text with few distinct values (levels), listed in
+    /// [`Metadata::levels`](metadata/struct.Metadata.html#structfield.levels). Only detected if
+    /// enabled with
+    /// [`Sniffer::detect_categorical`](struct.Sniffer.html#method.detect_categorical).
+    Categorical {
+        /// Number of distinct values.
+        cardinality: usize,
+    },
 }
-pub(crate) fn get_best_types(guesses: Vec<TypeGuesses>) -> Vec<Type> {
-    guesses.iter().map(|guess| guess.best()).collect()
+pub(crate) fn get_best_types(guesses: &[TypeGuesses], details: &[NumberDetails]) -> Vec<Type> {
+    guesses
+        .iter()
+        .enumerate()
+        .map(|(i, guess)| guess.best(details.get(i).copied().unwrap_or_default()))
+        .collect()
+}
+/// The confidence in the type of a column (with the provided guesses), from the numbers of its
+/// values of each type (see `TypeCounts`): the lower bound of the 95% Wilson score interval of the
+/// share of its non-null values that support the type. The values that fit a type narrower than
+/// text support it, but text columns are only supported by the values that don't fit a narrower
+/// type by themselves.
+fn type_confidence(ty: Type, guesses: TypeGuesses, counts: &TypeCounts) -> f64 {
+    const Z: f64 = 1.96;
+    let is_text = |ty: &Type| matches!(ty, Type::Text | Type::Categorical { .. });
+    let (n_support, n) = counts
+        .counts
+        .iter()
+        .filter(|known| known.ty != Type::Null)
+        .fold((0, 0), |(n_support, n), known| {
+            if !fits(known.guesses, guesses) || (is_text(&ty) && !is_text(&known.ty)) {
+                (n_support, n + known.count)
+            } else {
+                (n_support + known.count, n + known.count)
+            }
+        });
+    if n == 0 {
+        return 0.0;
+    }
+    let (n, share) = (n as f64, n_support as f64 / n as f64);
+    let z2 = Z * Z;
+    let center = share + z2 / (2.0 * n);
+    let margin = Z * (share * (1.0 - share) / n + z2 / (4.0 * n * n)).sqrt();
+    ((center - margin) / (1.0 + z2 / n)).max(0.0)
+}
+pub(crate) fn get_type_confidence(
+    guesses: &[TypeGuesses],
+    types: &[Type],
+    counts: &[TypeCounts],
+) -> Vec<f64> {
+    types
+        .iter()
+        .zip(guesses)
+        .enumerate()
+        .map(|(i, (&ty, &guesses))| {
+            counts
+                .get(i)
+                .map_or(0.0, |counts| type_confidence(ty, guesses, counts))
+        })
+        .collect()
+}
+pub(crate) fn get_offending_values(
+    guesses: &[TypeGuesses],
+    counts: &[TypeCounts],
+) -> Vec<Vec<String>> {
+    guesses
+        .iter()
+        .enumerate()
+        .map(|(i, &guesses)| {
+            counts
+                .get(i)
+                .map_or(vec![], |counts| counts.offending_values(guesses))
+        })
+        .collect()
+}
+pub(crate) fn get_statistics(
+    guesses: &[TypeGuesses],
+    types: &[Type],
+    counts: &[TypeCounts],
+) -> Vec<Option<NumericStats>> {
+    types
+        .iter()
+        .zip(guesses)
+        .enumerate()
+        .map(|(i, (&ty, &guesses))| {
+            counts
+                .get(i)
+                .filter(|_| is_numeric(ty))
+                .and_then(|counts| counts.statistics(guesses))
+        })
+        .collect()
+}
+pub(crate) fn get_lengths(counts: &[TypeCounts]) -> Vec<Option<LengthStats>> {
+    counts.iter().map(|counts| counts.lengths).collect()
+}
+pub(crate) fn get_distinct_counts(
+    counts: &[TypeCounts],
+    options: &TypeOptions,
+) -> Vec<Option<usize>> {
+    counts
+        .iter()
+        .map(|counts| {
+            counts
+                .distinct
+                .as_ref()
+                .map(HyperLogLog::estimate)
+                .or(Some(0).filter(|_| options.statistics))
+        })
+        .collect()
+}
+pub(crate) fn get_examples(counts: &[TypeCounts]) -> Vec<Vec<String>> {
+    counts.iter().map(|counts| counts.values.clone()).collect()
+}
+pub(crate) fn get_semantic_types(guesses: &[TypeGuesses]) -> Vec<Option<SemanticType>> {
+    guesses.iter().map(TypeGuesses::semantic_type).collect()
+}
+pub(crate) fn get_formats(
+    guesses: &[TypeGuesses],
+    order: DateOrder,
+    options: &TypeOptions,
+) -> Vec<Option<String>> {
+    guesses
+        .iter()
+        .map(|guess| {
+            guess
+                .format(order, options)
+                .map(|format| format.to_string())
+        })
+        .collect()
+}
+/// Whether or not all values of each column are plain booleans or numbers (see `infer_types`).
+pub(crate) fn get_plain_values(guesses: &[TypeGuesses]) -> Vec<bool> {
+    guesses
+        .iter()
+        .map(|guess| guess.contains(TypeGuesses::PLAIN))
+        .collect()
+}
+/// The order of the day and month in the dates of the columns: the order of the first column whose
+/// values can only be read one way, or the preferred order if all of them can be read either way
+/// (in which case the order is ambiguous).
+pub(crate) fn get_date_order(
+    guesses: &[TypeGuesses],
+    options: &TypeOptions,
+) -> (Option<DateOrder>, bool) {
+    let orders = guesses
+        .iter()
+        .filter_map(|guess| guess.date_order(options))
+        .collect::<Vec<_>>();
+    match orders.iter().find(|&&(_, is_ambiguous)| !is_ambiguous) {
+        Some(&(order, _)) => (Some(order), false),
+        None if !orders.is_empty() => (Some(options.date_preference), true),
+        None => (None, false),
+    }
+}
+/// The distinct values of a column (as long as there are few of them), for detecting
+/// `Type::Categorical` columns.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Levels {
+    values: BTreeSet<String>,
+    n_values: usize,
+    // whether there are more distinct values than the maximum number of levels
+    overflowed: bool,
+}
+impl Levels {
+    /// Adds a (non-null) value, unless there are already more than the maximum number of distinct
+    /// values.
+    pub(crate) fn add(&mut self, value: &str, max_levels: usize) {
+        self.n_values += 1;
+        if self.overflowed || self.values.contains(value) {
+            return;
+        }
+        if self.values.len() < max_levels {
+            self.values.insert(value.to_string());
+        } else {
+            self.overflowed = true;
+            self.values.clear();
+        }
+    }
+    /// The levels of the column, if it's categorical: if it has at most the maximum number of
+    /// distinct values, and each of them appears at least twice on average.
+    pub(crate) fn categories(&self) -> Option<&BTreeSet<String>> {
+        let is_categorical =
+            !self.overflowed && !self.values.is_empty() && self.n_values >= 2 * self.values.len();
+        is_categorical.then_some(&self.values)
+    }
+}
+
+/// A column of a CSV file, identified by its index or by its name (its label in the header row).
+/// Used when calling [`type_hint`](struct.Sniffer.html#method.type_hint) on `Sniffer`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    /// The column at the provided (zero-based) index.
+    Index(usize),
+    /// The column with the provided label in the header row (if the file has a header row).
+    Name(String),
+}
+impl Column {
+    /// Whether or not this is the column at the provided index, among columns with the provided
+    /// names (empty if the file doesn't have a header row).
+    pub(crate) fn is(&self, index: usize, names: &[String]) -> bool {
+        match *self {
+            Column::Index(i) => i == index,
+            Column::Name(ref name) => names.get(index) == Some(name),
+        }
+    }
+}
+impl From<usize> for Column {
+    fn from(index: usize) -> Column {
+        Column::Index(index)
+    }
+}
+impl From<&str> for Column {
+    fn from(name: &str) -> Column {
+        Column::Name(name.to_string())
+    }
+}
+impl From<String> for Column {
+    fn from(name: String) -> Column {
+        Column::Name(name)
+    }
+}
+
+/// The meaning of the values of a text field, beyond their type. Only detected if enabled with
+/// [`Sniffer::detect_semantic_types`](struct.Sniffer.html#method.detect_semantic_types).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SemanticType {
+    /// Email addresses (e.g. `jane.doe@example.com`)
+    Email,
+    /// URLs (e.g. `https://www.example.com/about`)
+    Url,
+    /// Phone numbers (e.g. `+49 170 1234567` or `(555) 123-4567`). Only available with the `phone`
+    /// feature.
+    #[cfg(feature = "phone")]
+    Phone,
+}
+impl From<SemanticType> for TypeGuesses {
+    fn from(semantic_type: SemanticType) -> TypeGuesses {
+        match semantic_type {
+            SemanticType::Email => TypeGuesses::EMAIL,
+            SemanticType::Url => TypeGuesses::URL,
+            #[cfg(feature = "phone")]
+            SemanticType::Phone => TypeGuesses::PHONE,
+        }
+    }
 }
+impl fmt::Display for SemanticType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SemanticType::Email => write!(f, "Email"),
+            SemanticType::Url => write!(f, "Url"),
+            #[cfg(feature = "phone")]
+            SemanticType::Phone => write!(f, "Phone"),
+        }
+    }
+}
+
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match *self {
-                Type::Unsigned => "Unsigned",
-                Type::Signed => "Signed",
-                Type::Text => "Text",
-                Type::Boolean => "Boolean",
-                Type::Float => "Float",
-            }
-        )
+        match *self {
+            Type::Unsigned => write!(f, "Unsigned"),
+            Type::Signed => write!(f, "Signed"),
+            Type::Text => write!(f, "Text"),
+            Type::Boolean => write!(f, "Boolean"),
+            Type::Float => write!(f, "Float"),
+            Type::Decimal { precision, scale } => write!(f, "Decimal({}, {})", precision, scale),
+            Type::Currency {
+                symbol: Some(symbol),
+            } => write!(f, "Currency({})", symbol),
+            Type::Currency { symbol: None } => write!(f, "Currency"),
+            Type::Percentage => write!(f, "Percentage"),
+            Type::DateTime => write!(f, "DateTime"),
+            Type::Date => write!(f, "Date"),
+            Type::Time => write!(f, "Time"),
+            Type::Null => write!(f, "Null"),
+            Type::Uuid => write!(f, "Uuid"),
+            Type::IpAddr => write!(f, "IpAddr"),
+            Type::Json => write!(f, "Json"),
+            Type::Hex => write!(f, "Hex"),
+            Type::NumericString => write!(f, "NumericString"),
+            Type::BigInt => write!(f, "BigInt"),
+            Type::Binary { encoding, max_len } => write!(f, "Binary({}, {})", encoding, max_len),
+            Type::Categorical { cardinality } => write!(f, "Categorical({})", cardinality),
+        }
+    }
+}
+
+/// The text encodings of binary data, for fields of type
+/// [`Type::Binary`](enum.Type.html#variant.Binary).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryEncoding {
+    /// Base64, with the standard alphabet and padding (e.g. `iVBORw0KGgoAAAANSUhEUgAA`)
+    Base64,
+}
+impl fmt::Display for BinaryEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BinaryEncoding::Base64 => write!(f, "Base64"),
+        }
     }
 }
@@ -0,0 +1,151 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Read};
+
+use crate::sample::is_blank;
+
+/// Options used when opening a reader with
+/// [`Dialect::open_reader_with`](struct.Dialect.html#method.open_reader_with).
+#[derive(Debug, Clone, Default)]
+pub struct ReadOptions {
+    /// Whether or not to drop the trailing empty fields (detected by the sniffer) from each record.
+    pub drop_trailing_empty_fields: bool,
+    /// Whether or not to stop reading before the footer rows (detected by the sniffer).
+    pub skip_footer_rows: bool,
+}
+
+/// A reader that filters the lines of an underlying reader according to a set of
+/// [`ReadOptions`](struct.ReadOptions.html). Created by
+/// [`Dialect::open_reader_with`](struct.Dialect.html#method.open_reader_with).
+#[derive(Debug)]
+pub struct FilteredReader<R> {
+    reader: BufReader<R>,
+    terminator: u8,
+    delimiter: u8,
+    comment: Option<u8>,
+    // number of trailing delimiters to remove from each line
+    n_drop: usize,
+    skip_blank_lines: bool,
+    // number of content (non-blank, non-comment) lines at the end of the input to hold back
+    n_footer_rows: usize,
+    // lines read from the underlying reader but not yet released, along with whether or not each
+    // is a content line
+    pending: VecDeque<(Vec<u8>, bool)>,
+    n_pending_content: usize,
+    is_done: bool,
+    line: Vec<u8>,
+    pos: usize,
+}
+impl<R: Read> FilteredReader<R> {
+    pub(crate) fn new(reader: R, terminator: u8, delimiter: u8) -> FilteredReader<R> {
+        FilteredReader {
+            reader: BufReader::new(reader),
+            terminator,
+            delimiter,
+            comment: None,
+            n_drop: 0,
+            skip_blank_lines: false,
+            n_footer_rows: 0,
+            pending: VecDeque::new(),
+            n_pending_content: 0,
+            is_done: false,
+            line: vec![],
+            pos: 0,
+        }
+    }
+    pub(crate) fn comment(mut self, comment: Option<u8>) -> FilteredReader<R> {
+        self.comment = comment;
+        self
+    }
+    pub(crate) fn drop_trailing_fields(mut self, n_drop: usize) -> FilteredReader<R> {
+        self.n_drop = n_drop;
+        self
+    }
+    pub(crate) fn skip_blank_lines(mut self, skip_blank_lines: bool) -> FilteredReader<R> {
+        self.skip_blank_lines = skip_blank_lines;
+        self
+    }
+    pub(crate) fn skip_footer_rows(mut self, n_footer_rows: usize) -> FilteredReader<R> {
+        self.n_footer_rows = n_footer_rows;
+        self
+    }
+
+    // Reads the next line that isn't held back as a potential footer row. Returns `None` at the
+    // end of the underlying reader (discarding the footer rows).
+    fn next_released_line(&mut self) -> io::Result<Option<Vec<u8>>> {
+        loop {
+            // the front line can be released once enough content lines follow it to fill the
+            // footer
+            if let Some(&(_, is_content)) = self.pending.front() {
+                if self.n_pending_content - usize::from(is_content) >= self.n_footer_rows {
+                    let (line, is_content) = self.pending.pop_front().unwrap();
+                    self.n_pending_content -= usize::from(is_content);
+                    return Ok(Some(line));
+                }
+            }
+            if self.is_done {
+                return Ok(None);
+            }
+            let mut line = vec![];
+            if self.reader.read_until(self.terminator, &mut line)? == 0 {
+                // whatever is still pending is the footer
+                self.is_done = true;
+                self.pending.clear();
+                self.n_pending_content = 0;
+                continue;
+            }
+            let is_blank_line = std::str::from_utf8(&line).is_ok_and(is_blank);
+            let is_comment = self.comment.is_some() && line.first() == self.comment.as_ref();
+            let is_content = !is_blank_line && !is_comment;
+            self.n_pending_content += usize::from(is_content);
+            self.pending.push_back((line, is_content));
+        }
+    }
+
+    // Reads the next line into the line buffer, applying the filters. Returns false at the end of
+    // the underlying reader.
+    fn fill_line(&mut self) -> io::Result<bool> {
+        self.pos = 0;
+        loop {
+            self.line = match self.next_released_line()? {
+                Some(line) => line,
+                None => {
+                    self.line.clear();
+                    return Ok(false);
+                }
+            };
+            let is_blank_line = std::str::from_utf8(&self.line).is_ok_and(is_blank);
+            if !(self.skip_blank_lines && is_blank_line) {
+                break;
+            }
+        }
+
+        if self.n_drop > 0 {
+            let mut body_end = self.line.len();
+            if self.line.last() == Some(&self.terminator) {
+                body_end -= 1;
+                if self.terminator == b'\n' && body_end > 0 && self.line[body_end - 1] == b'\r' {
+                    body_end -= 1;
+                }
+            }
+            if body_end >= self.n_drop
+                && self.line[body_end - self.n_drop..body_end]
+                    .iter()
+                    .all(|&chr| chr == self.delimiter)
+            {
+                self.line.drain(body_end - self.n_drop..body_end);
+            }
+        }
+        Ok(true)
+    }
+}
+impl<R: Read> Read for FilteredReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.line.len() && !self.fill_line()? {
+            return Ok(0);
+        }
+        let n = buf.len().min(self.line.len() - self.pos);
+        buf[..n].copy_from_slice(&self.line[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
@@ -0,0 +1,42 @@
+// Characters that, if present in every line, indicate a delimited file rather than a fixed-width
+// one.
+const COMMON_DELIMITERS: &[u8] = b",;\t|";
+
+/// Examines sample lines for whitespace-aligned columns. Returns the byte offsets at which each
+/// column starts, or `None` if the lines don't look like a fixed-width table.
+pub(crate) fn fixed_width_columns<S: AsRef<str>>(lines: &[S]) -> Option<Vec<usize>> {
+    let lines = lines
+        .iter()
+        .map(|line| line.as_ref().as_bytes())
+        .filter(|line| !line.iter().all(|&chr| chr == b' '))
+        .collect::<Vec<_>>();
+    if lines.len() < 2 {
+        return None;
+    }
+    if COMMON_DELIMITERS
+        .iter()
+        .any(|&delim| lines.iter().all(|line| line.contains(&delim)))
+    {
+        return None;
+    }
+
+    // a 'gutter' is a byte position that is blank in every line (lines that end early count as
+    // blank past their end)
+    let max_len = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+    let gutter = (0..max_len)
+        .map(|pos| {
+            lines
+                .iter()
+                .all(|line| line.get(pos).is_none_or(|&chr| chr == b' '))
+        })
+        .collect::<Vec<_>>();
+
+    // columns start wherever a non-gutter position follows a gutter (or the start of the line)
+    let column_starts = (0..max_len)
+        .filter(|&pos| !gutter[pos] && (pos == 0 || gutter[pos - 1]))
+        .collect::<Vec<_>>();
+    if column_starts.len() < 2 {
+        return None;
+    }
+    Some(column_starts)
+}
@@ -0,0 +1,155 @@
+/*!
+Export of the sniffed metadata as a [Frictionless Data](https://specs.frictionlessdata.io) tabular
+data resource, with a CSV dialect and a Table Schema (see
+[`Metadata::to_frictionless_resource`](../metadata/struct.Metadata.html#method.to_frictionless_resource)).
+*/
+use serde_json::{json, Map, Value};
+
+use crate::field_type::{SemanticType, Type};
+use crate::metadata::{Comment, Encoding, Escape, Metadata, Quote, Terminator, Trim};
+
+// Version of the CSV Dialect specification of the generated dialects.
+const CSV_DIALECT_VERSION: &str = "1.2";
+
+/// The name of a resource at the provided path: the file name without its extensions, in lower
+/// case, with characters other than letters, digits, `-`, `_` and `.` replaced with `-`.
+fn resource_name(path: &str) -> String {
+    let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    let stem = file_name.split('.').next().unwrap_or(file_name);
+    stem.chars()
+        .map(|chr| match chr.to_ascii_lowercase() {
+            chr @ ('a'..='z' | '0'..='9' | '-' | '_' | '.') => chr,
+            _ => '-',
+        })
+        .collect()
+}
+
+/// The CSV Dialect of the provided metadata.
+fn dialect(metadata: &Metadata) -> Value {
+    let dialect = &metadata.dialect;
+    let terminator = match dialect.terminator {
+        Terminator::CRLF => "\r\n".to_string(),
+        Terminator::LF => "\n".to_string(),
+        Terminator::CR => "\r".to_string(),
+        Terminator::Any(chr) => char::from(chr).to_string(),
+    };
+    let mut csv_dialect = json!({
+        "csvddfVersion": CSV_DIALECT_VERSION,
+        "delimiter": char::from(dialect.delimiter),
+        "lineTerminator": terminator,
+    });
+    // the specification has no way of disabling quoting
+    if let Quote::Some(chr) = dialect.quote {
+        csv_dialect["quoteChar"] = json!(char::from(chr));
+    }
+    csv_dialect["doubleQuote"] = json!(dialect.doublequote_escapes);
+    if let Escape::Enabled(chr) = dialect.escape {
+        csv_dialect["escapeChar"] = json!(char::from(chr));
+    }
+    if let Comment::Enabled(chr) = dialect.comment {
+        csv_dialect["commentChar"] = json!(char::from(chr));
+    }
+    csv_dialect["header"] = json!(dialect.header.has_header_row);
+    csv_dialect["skipInitialSpace"] = json!(matches!(dialect.trim, Trim::Fields | Trim::All));
+    csv_dialect
+}
+
+/// The Table Schema field of the field of the provided metadata with the provided index.
+fn field(metadata: &Metadata, i: usize) -> Value {
+    let ty = metadata.types[i];
+    let format = metadata.formats.get(i).and_then(|format| format.as_deref());
+    let mut schema_field = json!({"name": metadata.field_name(i)});
+    let (name, field_format) = match ty {
+        Type::Unsigned | Type::Signed | Type::BigInt => ("integer", None),
+        Type::Boolean => ("boolean", None),
+        Type::Float | Type::Decimal { .. } | Type::Currency { .. } | Type::Percentage => {
+            ("number", None)
+        }
+        // other formats are guessed by implementations
+        Type::DateTime => (
+            "datetime",
+            Some(match format {
+                Some("%Y-%m-%dT%H:%M:%S%.f%:z") => "default",
+                _ => "any",
+            }),
+        ),
+        Type::Date => (
+            "date",
+            Some(match format {
+                Some("%Y-%m-%d") => "default",
+                _ => "any",
+            }),
+        ),
+        Type::Time => ("time", Some("any")),
+        Type::Uuid => ("string", Some("uuid")),
+        Type::Null => ("any", None),
+        Type::Text
+        | Type::IpAddr
+        | Type::Json
+        | Type::NumericString
+        | Type::Hex
+        | Type::Binary { .. }
+        | Type::Categorical { .. } => (
+            "string",
+            match metadata.semantic_types.get(i).copied().flatten() {
+                Some(SemanticType::Email) => Some("email"),
+                Some(SemanticType::Url) => Some("uri"),
+                _ => None,
+            },
+        ),
+    };
+    schema_field["type"] = json!(name);
+    if let Some(field_format) = field_format {
+        schema_field["format"] = json!(field_format);
+    }
+    if matches!(name, "integer" | "number") {
+        if name == "number" && metadata.number_format.decimal_separator != '.' {
+            schema_field["decimalChar"] = json!(metadata.number_format.decimal_separator);
+        }
+        if let Some(grouping_separator) = metadata.number_format.grouping_separator {
+            schema_field["groupChar"] = json!(grouping_separator);
+        }
+        // currency symbols and percent signs are stripped from numbers that aren't bare
+        if matches!(ty, Type::Currency { .. } | Type::Percentage) {
+            schema_field["bareNumber"] = json!(false);
+        }
+    }
+
+    let mut constraints = Map::new();
+    if metadata.verification.is_some() && metadata.nullable.get(i) == Some(&false) {
+        constraints.insert("required".into(), json!(true));
+    }
+    if ty == Type::Unsigned {
+        constraints.insert("minimum".into(), json!(0));
+    }
+    if let Some(levels) = metadata.levels.get(i).filter(|levels| !levels.is_empty()) {
+        constraints.insert("enum".into(), json!(levels));
+    }
+    if !constraints.is_empty() {
+        schema_field["constraints"] = Value::Object(constraints);
+    }
+    schema_field
+}
+
+/// The tabular data resource of the file at the provided path, with the provided metadata.
+pub(crate) fn resource(metadata: &Metadata, path: &str) -> Value {
+    let encoding = match metadata.encoding {
+        Encoding::Utf8 => "utf-8",
+        Encoding::Utf16Le => "utf-16le",
+        Encoding::Utf16Be => "utf-16be",
+        Encoding::Windows1252 => "windows-1252",
+    };
+    let fields = (0..metadata.types.len())
+        .map(|i| field(metadata, i))
+        .collect::<Vec<_>>();
+    json!({
+        "name": resource_name(path),
+        "path": path,
+        "profile": "tabular-data-resource",
+        "format": "csv",
+        "mediatype": "text/csv",
+        "encoding": encoding,
+        "dialect": dialect(metadata),
+        "schema": {"fields": fields},
+    })
+}
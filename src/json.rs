@@ -0,0 +1,592 @@
+/*!
+JSON (de)serialization of [`Metadata`](../metadata/struct.Metadata.html) (see
+[`Metadata::to_json`](../metadata/struct.Metadata.html#method.to_json)), with
+[`serde_json`](https://docs.rs/serde_json): conversions of the metadata types to and from JSON
+values, written out by hand so that the shape of the JSON doesn't follow the layout of the types.
+The `serde` implementations of `Metadata` and `Dialect` go through the same conversions.
+*/
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::error::SnifferError;
+use crate::field_type::{BinaryEncoding, SemanticType, Type};
+use crate::metadata::*;
+
+/// Version of the JSON shape of the metadata, written as the `version` key.
+const JSON_VERSION: u64 = 1;
+
+/// Serializes metadata to JSON.
+pub(crate) fn to_json(metadata: &Metadata) -> String {
+    ToJson::to_json(metadata).to_string()
+}
+
+/// Deserializes metadata from JSON.
+pub(crate) fn from_json(json: &str) -> crate::error::Result<Metadata> {
+    serde_json::from_str(json)
+        .map_err(|err| err.to_string())
+        .and_then(|json| <Metadata as FromJson>::from_json(&json))
+        .map_err(SnifferError::Json)
+}
+
+// Access to the keys of JSON objects.
+trait Object {
+    // The value of a (required) key of an object, converted.
+    fn field<T: FromJson>(&self, key: &str) -> Result<T, String>;
+    // The value of an optional key of an object, converted (the default if missing).
+    fn field_or_default<T: FromJson + Default>(&self, key: &str) -> Result<T, String>;
+}
+impl Object for Value {
+    fn field<T: FromJson>(&self, key: &str) -> Result<T, String> {
+        match self {
+            Value::Object(_) => match self.get(key) {
+                Some(value) => T::from_json(value).map_err(|err| format!("{}: {}", key, err)),
+                None => Err(format!("missing key '{}'", key)),
+            },
+            _ => Err("expected an object".into()),
+        }
+    }
+    fn field_or_default<T: FromJson + Default>(&self, key: &str) -> Result<T, String> {
+        match self.get(key) {
+            Some(_) => self.field(key),
+            None => Ok(T::default()),
+        }
+    }
+}
+
+/// Conversion into a JSON value.
+pub(crate) trait ToJson {
+    fn to_json(&self) -> Value;
+}
+/// Conversion from a JSON value.
+pub(crate) trait FromJson: Sized {
+    fn from_json(json: &Value) -> Result<Self, String>;
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+impl FromJson for bool {
+    fn from_json(json: &Value) -> Result<bool, String> {
+        json.as_bool().ok_or_else(|| "expected a boolean".into())
+    }
+}
+macro_rules! integer_json {
+    ($($ty:ty),*) => {$(
+        impl ToJson for $ty {
+            fn to_json(&self) -> Value {
+                Value::from(*self)
+            }
+        }
+        impl FromJson for $ty {
+            fn from_json(json: &Value) -> Result<$ty, String> {
+                json.as_u64()
+                    .and_then(|value| <$ty>::try_from(value).ok())
+                    .ok_or_else(|| format!("expected an integer, found {}", json))
+            }
+        }
+    )*};
+}
+integer_json!(usize, u64);
+impl ToJson for f64 {
+    fn to_json(&self) -> Value {
+        // non-finite numbers are written as null
+        Value::from(*self)
+    }
+}
+impl FromJson for f64 {
+    fn from_json(json: &Value) -> Result<f64, String> {
+        match json {
+            Value::Null => Ok(f64::NAN),
+            json => json.as_f64().ok_or_else(|| "expected a number".into()),
+        }
+    }
+}
+impl ToJson for String {
+    fn to_json(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+impl FromJson for String {
+    fn from_json(json: &Value) -> Result<String, String> {
+        json.as_str()
+            .map(String::from)
+            .ok_or_else(|| "expected a string".into())
+    }
+}
+impl ToJson for char {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+impl FromJson for char {
+    fn from_json(json: &Value) -> Result<char, String> {
+        let text = String::from_json(json)?;
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(chr), None) => Ok(chr),
+            _ => Err("expected a single character".into()),
+        }
+    }
+}
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Value {
+        self.as_ref().map_or(Value::Null, ToJson::to_json)
+    }
+}
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(json: &Value) -> Result<Option<T>, String> {
+        match json {
+            Value::Null => Ok(None),
+            json => T::from_json(json).map(Some),
+        }
+    }
+}
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(json: &Value) -> Result<Vec<T>, String> {
+        match json {
+            Value::Array(values) => values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| T::from_json(value).map_err(|err| format!("[{}]: {}", i, err)))
+                .collect(),
+            _ => Err("expected an array".into()),
+        }
+    }
+}
+
+// Bytes (e.g. delimiters) are written as single-character strings (of the character with the same
+// code).
+fn byte_to_json(byte: u8) -> Value {
+    char::from(byte).to_json()
+}
+fn byte_from_json(json: &Value) -> Result<u8, String> {
+    u8::try_from(char::from_json(json)?).map_err(|_| "expected a single-byte character".into())
+}
+
+// Enums without data are written as the names of their variants.
+macro_rules! unit_enum_json {
+    ($ty:ident { $($(#[$attr:meta])* $variant:ident),* $(,)? }) => {
+        impl ToJson for $ty {
+            fn to_json(&self) -> Value {
+                Value::String(match self {
+                    $($(#[$attr])* $ty::$variant => stringify!($variant),)*
+                }.to_string())
+            }
+        }
+        impl FromJson for $ty {
+            fn from_json(json: &Value) -> Result<$ty, String> {
+                match String::from_json(json)?.as_str() {
+                    $($(#[$attr])* stringify!($variant) => Ok($ty::$variant),)*
+                    other => Err(format!("unknown {} '{}'", stringify!($ty), other)),
+                }
+            }
+        }
+    };
+}
+unit_enum_json!(QuoteStyle {
+    Always,
+    Necessary,
+    NonNumeric
+});
+unit_enum_json!(Trim {
+    None,
+    Headers,
+    Fields,
+    All
+});
+unit_enum_json!(Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Windows1252
+});
+unit_enum_json!(Bom {
+    Utf8,
+    Utf16Le,
+    Utf16Be
+});
+unit_enum_json!(Compression {
+    None,
+    Gzip,
+    Zstd,
+    Bzip2,
+    Xz
+});
+unit_enum_json!(DateOrder {
+    DayFirst,
+    MonthFirst
+});
+unit_enum_json!(BinaryEncoding { Base64 });
+unit_enum_json!(SemanticType {
+    Email,
+    Url,
+    #[cfg(feature = "phone")]
+    Phone,
+});
+
+// Structs are written as objects with a key for each field.
+macro_rules! struct_json {
+    ($ty:ident { $($field:ident),* $(,)? }) => {
+        impl ToJson for $ty {
+            fn to_json(&self) -> Value {
+                Value::Object(Map::from_iter([
+                    $((stringify!($field).to_string(), self.$field.to_json()),)*
+                ]))
+            }
+        }
+        impl FromJson for $ty {
+            fn from_json(json: &Value) -> Result<$ty, String> {
+                Ok($ty {
+                    $($field: json.field(stringify!($field))?,)*
+                })
+            }
+        }
+    };
+}
+struct_json!(Header {
+    has_header_row,
+    num_preamble_rows,
+    num_footer_rows
+});
+struct_json!(Confidence {
+    delimiter,
+    header,
+    quote
+});
+struct_json!(NumberFormat {
+    decimal_separator,
+    grouping_separator
+});
+struct_json!(Verification {
+    num_records,
+    num_field_count_violations,
+    num_type_violations
+});
+struct_json!(NumericStats {
+    count,
+    min,
+    max,
+    mean
+});
+struct_json!(LengthStats {
+    count,
+    min_bytes,
+    max_bytes,
+    mean_bytes,
+    min_chars,
+    max_chars,
+    mean_chars
+});
+
+impl ToJson for Dialect {
+    fn to_json(&self) -> Value {
+        let quote = match self.quote {
+            Quote::Some(chr) => byte_to_json(chr),
+            Quote::None => Value::Null,
+        };
+        let terminator = match self.terminator {
+            Terminator::CRLF => Value::String("CRLF".into()),
+            Terminator::LF => Value::String("LF".into()),
+            Terminator::CR => Value::String("CR".into()),
+            Terminator::Any(chr) => byte_to_json(chr),
+        };
+        let escape = match self.escape {
+            Escape::Enabled(chr) => byte_to_json(chr),
+            Escape::Disabled => Value::Null,
+        };
+        let comment = match self.comment {
+            Comment::Enabled(chr) => byte_to_json(chr),
+            Comment::Disabled => Value::Null,
+        };
+        Value::Object(Map::from_iter([
+            ("delimiter".into(), byte_to_json(self.delimiter)),
+            ("header".into(), self.header.to_json()),
+            ("quote".into(), quote),
+            ("flexible".into(), self.flexible.to_json()),
+            ("terminator".into(), terminator),
+            ("escape".into(), escape),
+            ("comment".into(), comment),
+            (
+                "doublequote_escapes".into(),
+                self.doublequote_escapes.to_json(),
+            ),
+            ("quote_style".into(), self.quote_style.to_json()),
+            (
+                "num_trailing_empty_fields".into(),
+                self.num_trailing_empty_fields.to_json(),
+            ),
+            ("trim".into(), self.trim.to_json()),
+            ("skip_blank_lines".into(), self.skip_blank_lines.to_json()),
+            ("encoding".into(), self.encoding.to_json()),
+        ]))
+    }
+}
+impl FromJson for Dialect {
+    fn from_json(json: &Value) -> Result<Dialect, String> {
+        let byte = |key: &str| match json.get(key) {
+            Some(Value::Null) => Ok(None),
+            Some(value) => byte_from_json(value)
+                .map(Some)
+                .map_err(|err| format!("{}: {}", key, err)),
+            None => Err(format!("missing key '{}'", key)),
+        };
+        let terminator = match json.get("terminator") {
+            Some(Value::String(name)) if name == "CRLF" => Terminator::CRLF,
+            Some(Value::String(name)) if name == "LF" => Terminator::LF,
+            Some(Value::String(name)) if name == "CR" => Terminator::CR,
+            _ => Terminator::Any(byte("terminator")?.ok_or("terminator: expected a string")?),
+        };
+        Ok(Dialect {
+            delimiter: byte("delimiter")?.ok_or("delimiter: expected a string")?,
+            header: json.field("header")?,
+            quote: byte("quote")?.map_or(Quote::None, Quote::Some),
+            flexible: json.field("flexible")?,
+            terminator,
+            escape: byte("escape")?.map_or(Escape::Disabled, Escape::Enabled),
+            comment: byte("comment")?.map_or(Comment::Disabled, Comment::Enabled),
+            doublequote_escapes: json.field("doublequote_escapes")?,
+            quote_style: json.field("quote_style")?,
+            num_trailing_empty_fields: json.field_or_default("num_trailing_empty_fields")?,
+            trim: json.field("trim")?,
+            skip_blank_lines: json.field_or_default("skip_blank_lines")?,
+            encoding: json.field("encoding")?,
+        })
+    }
+}
+
+// The keys of a type's object: its name, and the details of the types with them.
+fn type_entries(ty: &Type) -> Map<String, Value> {
+    let name = match ty {
+        Type::Unsigned => "Unsigned",
+        Type::Signed => "Signed",
+        Type::Text => "Text",
+        Type::Boolean => "Boolean",
+        Type::Float => "Float",
+        Type::Decimal { .. } => "Decimal",
+        Type::Currency { .. } => "Currency",
+        Type::Percentage => "Percentage",
+        Type::DateTime => "DateTime",
+        Type::Date => "Date",
+        Type::Time => "Time",
+        Type::Null => "Null",
+        Type::Uuid => "Uuid",
+        Type::IpAddr => "IpAddr",
+        Type::Json => "Json",
+        Type::BigInt => "BigInt",
+        Type::NumericString => "NumericString",
+        Type::Hex => "Hex",
+        Type::Binary { .. } => "Binary",
+        Type::Categorical { .. } => "Categorical",
+    };
+    let mut entries = Map::new();
+    entries.insert("type".into(), Value::String(name.into()));
+    match *ty {
+        Type::Decimal { precision, scale } => {
+            entries.insert("precision".into(), precision.to_json());
+            entries.insert("scale".into(), scale.to_json());
+        }
+        Type::Currency { symbol } => {
+            entries.insert("symbol".into(), symbol.to_json());
+        }
+        Type::Binary { encoding, max_len } => {
+            entries.insert("encoding".into(), encoding.to_json());
+            entries.insert("max_len".into(), max_len.to_json());
+        }
+        Type::Categorical { cardinality } => {
+            entries.insert("cardinality".into(), cardinality.to_json());
+        }
+        _ => {}
+    }
+    entries
+}
+impl ToJson for Type {
+    fn to_json(&self) -> Value {
+        Value::Object(type_entries(self))
+    }
+}
+impl FromJson for Type {
+    fn from_json(json: &Value) -> Result<Type, String> {
+        Ok(match json.field::<String>("type")?.as_str() {
+            "Unsigned" => Type::Unsigned,
+            "Signed" => Type::Signed,
+            "Text" => Type::Text,
+            "Boolean" => Type::Boolean,
+            "Float" => Type::Float,
+            "Decimal" => Type::Decimal {
+                precision: json.field("precision")?,
+                scale: json.field("scale")?,
+            },
+            "Currency" => Type::Currency {
+                symbol: json.field("symbol")?,
+            },
+            "Percentage" => Type::Percentage,
+            "DateTime" => Type::DateTime,
+            "Date" => Type::Date,
+            "Time" => Type::Time,
+            "Null" => Type::Null,
+            "Uuid" => Type::Uuid,
+            "IpAddr" => Type::IpAddr,
+            "Json" => Type::Json,
+            "BigInt" => Type::BigInt,
+            "NumericString" => Type::NumericString,
+            "Hex" => Type::Hex,
+            "Binary" => Type::Binary {
+                encoding: json.field("encoding")?,
+                max_len: json.field("max_len")?,
+            },
+            "Categorical" => Type::Categorical {
+                cardinality: json.field("cardinality")?,
+            },
+            other => return Err(format!("unknown type '{}'", other)),
+        })
+    }
+}
+
+// The numbers of values of each type are written as the objects of the types, with a `count` key.
+struct TypeCount((Type, usize));
+impl ToJson for TypeCount {
+    fn to_json(&self) -> Value {
+        let (ty, count) = &self.0;
+        let mut entries = type_entries(ty);
+        entries.insert("count".into(), count.to_json());
+        Value::Object(entries)
+    }
+}
+impl FromJson for TypeCount {
+    fn from_json(json: &Value) -> Result<TypeCount, String> {
+        Ok(TypeCount((Type::from_json(json)?, json.field("count")?)))
+    }
+}
+
+impl ToJson for Metadata {
+    fn to_json(&self) -> Value {
+        let type_counts = self
+            .type_counts
+            .iter()
+            .map(|counts| counts.iter().copied().map(TypeCount).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        Value::Object(Map::from_iter([
+            ("version".into(), JSON_VERSION.to_json()),
+            ("dialect".into(), self.dialect.to_json()),
+            ("num_fields".into(), self.num_fields.to_json()),
+            (
+                "num_effective_fields".into(),
+                self.num_effective_fields.to_json(),
+            ),
+            ("num_blank_lines".into(), self.num_blank_lines.to_json()),
+            (
+                "estimated_num_records".into(),
+                self.estimated_num_records.to_json(),
+            ),
+            ("mean_record_len".into(), self.mean_record_len.to_json()),
+            ("max_record_len".into(), self.max_record_len.to_json()),
+            ("types".into(), self.types.to_json()),
+            ("formats".into(), self.formats.to_json()),
+            ("plain_values".into(), self.plain_values.to_json()),
+            ("nullable".into(), self.nullable.to_json()),
+            ("null_counts".into(), self.null_counts.to_json()),
+            ("type_counts".into(), type_counts.to_json()),
+            ("type_confidence".into(), self.type_confidence.to_json()),
+            ("offending_values".into(), self.offending_values.to_json()),
+            ("statistics".into(), self.statistics.to_json()),
+            ("increasing".into(), self.increasing.to_json()),
+            ("primary_keys".into(), self.primary_keys.to_json()),
+            ("lengths".into(), self.lengths.to_json()),
+            ("distinct_counts".into(), self.distinct_counts.to_json()),
+            ("examples".into(), self.examples.to_json()),
+            ("semantic_types".into(), self.semantic_types.to_json()),
+            ("levels".into(), self.levels.to_json()),
+            ("number_format".into(), self.number_format.to_json()),
+            ("date_order".into(), self.date_order.to_json()),
+            (
+                "date_order_ambiguous".into(),
+                self.date_order_ambiguous.to_json(),
+            ),
+            ("field_names".into(), self.field_names.to_json()),
+            ("preamble".into(), self.preamble.to_json()),
+            ("data_offset".into(), self.data_offset.to_json()),
+            ("bom".into(), self.bom.to_json()),
+            ("encoding".into(), self.encoding.to_json()),
+            ("compression".into(), self.compression.to_json()),
+            ("confidence".into(), self.confidence.to_json()),
+            ("verification".into(), self.verification.to_json()),
+        ]))
+    }
+}
+impl FromJson for Metadata {
+    fn from_json(json: &Value) -> Result<Metadata, String> {
+        let version = json.field::<u64>("version")?;
+        if version > JSON_VERSION {
+            return Err(format!("unsupported version {}", version));
+        }
+        let type_counts = json
+            .field_or_default::<Vec<Vec<TypeCount>>>("type_counts")?
+            .into_iter()
+            .map(|counts| counts.into_iter().map(|count| count.0).collect())
+            .collect();
+        Ok(Metadata {
+            dialect: json.field("dialect")?,
+            num_fields: json.field("num_fields")?,
+            num_effective_fields: json.field("num_effective_fields")?,
+            num_blank_lines: json.field_or_default("num_blank_lines")?,
+            estimated_num_records: json.field_or_default("estimated_num_records")?,
+            mean_record_len: json.field_or_default("mean_record_len")?,
+            max_record_len: json.field_or_default("max_record_len")?,
+            types: json.field("types")?,
+            formats: json.field_or_default("formats")?,
+            plain_values: json.field_or_default("plain_values")?,
+            nullable: json.field_or_default("nullable")?,
+            null_counts: json.field_or_default("null_counts")?,
+            type_counts,
+            type_confidence: json.field_or_default("type_confidence")?,
+            offending_values: json.field_or_default("offending_values")?,
+            statistics: json.field_or_default("statistics")?,
+            increasing: json.field_or_default("increasing")?,
+            primary_keys: json.field_or_default("primary_keys")?,
+            lengths: json.field_or_default("lengths")?,
+            distinct_counts: json.field_or_default("distinct_counts")?,
+            examples: json.field_or_default("examples")?,
+            semantic_types: json.field_or_default("semantic_types")?,
+            levels: json.field_or_default("levels")?,
+            number_format: json.field_or_default("number_format")?,
+            date_order: json.field_or_default("date_order")?,
+            date_order_ambiguous: json.field_or_default("date_order_ambiguous")?,
+            field_names: json.field_or_default("field_names")?,
+            preamble: json.field_or_default("preamble")?,
+            data_offset: json.field_or_default("data_offset")?,
+            bom: json.field_or_default("bom")?,
+            encoding: json.field("encoding")?,
+            compression: json.field("compression")?,
+            confidence: json.field("confidence")?,
+            verification: json.field_or_default("verification")?,
+        })
+    }
+}
+
+// `Metadata` and `Dialect` (de)serialize as their JSON values, so that any `serde` format gets the
+// same shape as `Metadata::to_json`.
+macro_rules! serde_via_json {
+    ($ty:ident) => {
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                ToJson::to_json(self).serialize(serializer)
+            }
+        }
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<$ty, D::Error> {
+                <$ty as FromJson>::from_json(&Value::deserialize(deserializer)?)
+                    .map_err(de::Error::custom)
+            }
+        }
+    };
+}
+serde_via_json!(Metadata);
+serde_via_json!(Dialect);
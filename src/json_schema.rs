@@ -0,0 +1,97 @@
+/*!
+Generation of a [JSON Schema](https://json-schema.org) describing the records of a CSV file (see
+[`Metadata::to_json_schema`](../metadata/struct.Metadata.html#method.to_json_schema)).
+*/
+use serde_json::{json, Map, Value};
+
+use crate::field_type::{SemanticType, Type};
+use crate::metadata::Metadata;
+
+// Version of JSON Schema of the generated schemas.
+const JSON_SCHEMA_DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+// The `format` of string values with the provided type, date-time format and semantic type, if
+// JSON Schema has one for them (date-times in RFC 3339 and dates in ISO 8601, UUIDs, email
+// addresses and URLs).
+fn string_format(
+    ty: Type,
+    format: Option<&str>,
+    semantic_type: Option<SemanticType>,
+) -> Option<&'static str> {
+    match (ty, format, semantic_type) {
+        (Type::DateTime, Some("%Y-%m-%dT%H:%M:%S%.f%:z"), _) => Some("date-time"),
+        (Type::Date, Some("%Y-%m-%d"), _) => Some("date"),
+        (Type::Uuid, _, _) => Some("uuid"),
+        (_, _, Some(SemanticType::Email)) => Some("email"),
+        (_, _, Some(SemanticType::Url)) => Some("uri"),
+        _ => None,
+    }
+}
+
+/// The JSON Schema of the values of the field of the provided metadata with the provided index.
+/// Values that aren't plain numbers (e.g. amounts with currency symbols, or percentages) are
+/// strings.
+fn property(metadata: &Metadata, i: usize) -> Value {
+    let ty = metadata.types[i];
+    let format = metadata.formats.get(i).and_then(|format| format.as_deref());
+    let semantic_type = metadata.semantic_types.get(i).copied().flatten();
+    // null values can't be ruled out from a sample
+    let nullable = metadata.verification.is_none() || metadata.nullable.get(i) != Some(&false);
+
+    let name = match ty {
+        Type::Unsigned | Type::Signed | Type::BigInt => "integer",
+        Type::Boolean => "boolean",
+        Type::Float | Type::Decimal { .. } => "number",
+        Type::Null => "null",
+        Type::Text
+        | Type::Currency { .. }
+        | Type::Percentage
+        | Type::DateTime
+        | Type::Date
+        | Type::Time
+        | Type::Uuid
+        | Type::IpAddr
+        | Type::Json
+        | Type::NumericString
+        | Type::Hex
+        | Type::Binary { .. }
+        | Type::Categorical { .. } => "string",
+    };
+    let mut property = json!({
+        "type": if nullable && ty != Type::Null {
+            json!([name, "null"])
+        } else {
+            json!(name)
+        },
+    });
+    if ty == Type::Unsigned {
+        property["minimum"] = json!(0);
+    }
+    if let Some(format) = string_format(ty, format, semantic_type) {
+        property["format"] = json!(format);
+    }
+    if let Some(levels) = metadata.levels.get(i).filter(|levels| !levels.is_empty()) {
+        let mut values = levels.iter().map(|level| json!(level)).collect::<Vec<_>>();
+        if nullable {
+            values.push(Value::Null);
+        }
+        property["enum"] = Value::Array(values);
+    }
+    property
+}
+
+/// The JSON Schema of the records of the provided metadata, as objects with a property for each
+/// field.
+pub(crate) fn schema(metadata: &Metadata) -> Value {
+    let properties = (0..metadata.types.len())
+        .map(|i| (metadata.field_name(i), property(metadata, i)))
+        .collect::<Map<_, _>>();
+    let required = properties.keys().collect::<Vec<_>>();
+    json!({
+        "$schema": JSON_SCHEMA_DRAFT,
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
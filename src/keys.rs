@@ -0,0 +1,69 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+use crate::field_type::TypeOptions;
+
+/// Maximum number of records whose values are kept (hashed) to detect primary keys.
+pub(crate) const MAX_KEY_RECORDS: usize = 1 << 14;
+
+/// Hashes of the values of a record (`None` for null values).
+pub(crate) type RecordHashes = Vec<Option<u64>>;
+
+/// Hashes the values of a record.
+pub(crate) fn hash_record<'a, I>(record: I, options: &TypeOptions) -> RecordHashes
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    record
+        .into_iter()
+        .map(|value| (!options.is_null(value)).then(|| hash(&value)))
+        .collect()
+}
+
+fn hash<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The columns (among the provided number of them) whose values are all non-null and distinct
+/// among the records; if there aren't any, the pairs of columns whose combined values are. Each
+/// key lists the indices of its columns, in order. A single record doesn't have any keys.
+pub(crate) fn primary_keys(records: &[RecordHashes], n_fields: usize) -> Vec<Vec<usize>> {
+    if records.len() < 2 {
+        return vec![];
+    }
+    let non_null = (0..n_fields)
+        .filter(|&i| {
+            records
+                .iter()
+                .all(|record| record.get(i).copied().flatten().is_some())
+        })
+        .collect::<Vec<_>>();
+    let is_unique = |columns: &[usize]| {
+        let mut seen = HashSet::with_capacity(records.len());
+        records.iter().all(|record| {
+            let values = columns.iter().map(|&i| record[i]).collect::<Vec<_>>();
+            seen.insert(hash(&values))
+        })
+    };
+
+    let singles = non_null
+        .iter()
+        .filter(|&&i| is_unique(&[i]))
+        .map(|&i| vec![i])
+        .collect::<Vec<_>>();
+    if !singles.is_empty() {
+        return singles;
+    }
+    let mut pairs = vec![];
+    for (n, &i) in non_null.iter().enumerate() {
+        for &j in &non_null[n + 1..] {
+            if is_unique(&[i, j]) {
+                pairs.push(vec![i, j]);
+            }
+        }
+    }
+    pairs
+}
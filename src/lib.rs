@@ -16,14 +16,26 @@ underlying CSV input.
 
 This sniffer detects the following metadata about a CSV file:
 
+* Encoding -- character encoding of the file (UTF-8, UTF-16, or Windows-1252), along with any byte
+  order mark
 * Delimiter -- byte character between fields in a record
 * Number of preamble rows -- number of rows in a CSV file before the data starts (occasionally used
-in data files to introduce the data)
+  in data files to introduce the data)
+* Number of footer rows -- number of rows at the end of a CSV file that aren't part of the data
+  (e.g. summary rows)
 * Has a header row? -- whether or not the first row of the data file provdes column headers
 * Quote -- byte character (either ", ', or `) used to quote fields, or that the file has no quotes
 * Flexible -- whether or not records are all of the same length
 * Delimiter count -- maximum number of delimiters in each row (and therefore number of fields in
-each row)
+  each row)
+* Terminator -- how records are terminated (CRLF, LF, CR, or another byte)
+* Escape -- escape character used within quoted fields, if any
+* Comment -- character that starts lines to be ignored, if any
+* Double quote escapes -- whether quotes within quoted fields are escaped by doubling them
+* Quote style -- whether all fields, only non-numeric fields, or only fields that need it are quoted
+* Trailing empty fields -- number of trailing fields that are empty in every record
+* Trim -- whether fields are padded with whitespace that should be trimmed
+* Blank lines -- whether (and how many) blank lines appear between data rows
 * Types -- the inferred data type of each field in the data table
 
 See [`Metadata`](metadata/struct.Metadata.html) for full information about what the sniffer returns.
@@ -43,6 +55,39 @@ and this to your crate root:
 extern crate csv_sniffer;
 ```
 
+# Features
+
+* `encoding` -- adds [`Dialect::open_reader_transcoded`](metadata/struct.Dialect.html#method.open_reader_transcoded)
+  (and `open_path_transcoded`), which transcode files in other encodings to UTF-8 (using
+  [`encoding_rs`](https://docs.rs/encoding_rs)).
+* `gzip` -- decompresses gzip-compressed files (e.g. `.csv.gz`) while sniffing and when opening
+  them with [`Dialect::open_path_decompressed`](metadata/struct.Dialect.html#method.open_path_decompressed)
+  (using [`flate2`](https://docs.rs/flate2)).
+* `zstd`, `bzip2`, `xz` -- likewise decompress zstd-, bzip2- and xz-compressed files (using
+  [`zstd`](https://docs.rs/zstd), [`bzip2`](https://docs.rs/bzip2) and
+  [`xz2`](https://docs.rs/xz2)).
+* `tokio` -- adds [`Sniffer::sniff_async`](struct.Sniffer.html#method.sniff_async) (and
+  `sniff_path_async`), which sniff files on a [`tokio`](https://docs.rs/tokio) runtime without
+  blocking it.
+* `futures-io` -- adds [`Sniffer::sniff_futures`](struct.Sniffer.html#method.sniff_futures), which
+  does the same for [`futures-io`](https://docs.rs/futures-io) readers (e.g. on `async-std` or
+  `smol`).
+* `http` -- adds [`Sniffer::sniff_url`](struct.Sniffer.html#method.sniff_url), which sniffs a
+  remote file while only downloading the sample (using [`ureq`](https://docs.rs/ureq)).
+* `mmap` -- adds [`Sniffer::sniff_path_mmap`](struct.Sniffer.html#method.sniff_path_mmap), which
+  sniffs a local file by memory-mapping it (using [`memmap2`](https://docs.rs/memmap2)).
+* `phone` -- adds [`SemanticType::Phone`](enum.SemanticType.html#variant.Phone), for columns of
+  phone numbers (e.g. `+49 170 1234567` or `(555) 123-4567`), which are never inferred as numeric.
+* `serde` -- adds the JSON (de)serialization of the metadata
+  ([`Metadata::to_json`](metadata/struct.Metadata.html#method.to_json) and
+  [`Metadata::from_json`](metadata/struct.Metadata.html#method.from_json), and `Serialize` and
+  `Deserialize` implementations of `Metadata` and `Dialect`), and the JSON exports of the
+  metadata: a [JSON Schema](metadata/struct.Metadata.html#method.to_json_schema) and an
+  [Avro schema](metadata/struct.Metadata.html#method.to_avro_schema) of the records, a
+  [Frictionless data resource](metadata/struct.Metadata.html#method.to_frictionless_resource)
+  and [CSVW metadata](metadata/struct.Metadata.html#method.to_csvw_metadata) (using
+  [`serde_json`](https://docs.rs/serde_json)).
+
 # Example
 
 This example shows how to write a simple command-line tool for discovering the metadata of a CSV
@@ -79,21 +124,69 @@ run as:
 $ cargo run -- tests/data/library-visitors.csv
 ```
 
+The binary also sniffs a sample read from standard input when given `-` (or no argument), so it can
+be used in pipelines:
+
+```ignore
+$ zcat big.csv.gz | cargo run -- -
+```
+
+With `--rust-struct`, it prints a Rust struct for the records of the file instead (see
+[`Metadata::to_rust_struct`](metadata/struct.Metadata.html#method.to_rust_struct)):
+
+```ignore
+$ cargo run -- --rust-struct tests/data/library-visitors.csv
+```
+
 */
 
 #![warn(missing_docs)]
 
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "serde")]
+mod avro;
 pub(crate) mod chain;
+mod codegen;
+mod compression;
+#[cfg(feature = "serde")]
+mod csvw;
+#[cfg(feature = "datafusion")]
+mod datafusion;
+mod datetime;
+mod diff;
+mod encoding;
 pub mod error;
+mod filter;
+mod fixed_width;
+#[cfg(feature = "serde")]
+mod frictionless;
+#[cfg(feature = "serde")]
+mod json;
+#[cfg(feature = "serde")]
+mod json_schema;
+mod keys;
+mod merge;
 pub mod metadata;
+#[cfg(feature = "parquet")]
+mod parquet;
+#[cfg(feature = "polars")]
+mod polars;
+pub mod progress;
 
 mod sniffer;
-pub use sniffer::Sniffer;
+pub use sniffer::{HeaderHint, Sniffer};
 
 mod sample;
-pub use sample::SampleSize;
+pub use sample::{ExampleValues, SampleSize, Sampling};
 
 pub(crate) mod field_type;
-pub use field_type::Type;
+pub use field_type::{BinaryEncoding, Column, SemanticType, Type};
 
+mod sketch;
 mod snip;
+mod spec;
+mod sql;
+
+mod stream;
+mod validate;
@@ -0,0 +1,340 @@
+/*!
+Merging of the metadata of several files (e.g. parts of the same dataset) into a single schema
+that fits all of them (see [`Metadata::merge`](../metadata/struct.Metadata.html#method.merge)).
+*/
+use std::collections::BTreeSet;
+
+use crate::field_type::{is_numeric, merge_stats, SemanticType, Type};
+use crate::metadata::{Confidence, LengthStats, Metadata, NumericStats, Verification};
+
+// Everything known about a single field.
+struct Field {
+    name: Option<String>,
+    ty: Type,
+    format: Option<String>,
+    plain_values: bool,
+    nullable: bool,
+    null_count: usize,
+    type_counts: Vec<(Type, usize)>,
+    type_confidence: f64,
+    offending_values: Vec<String>,
+    statistics: Option<NumericStats>,
+    increasing: bool,
+    lengths: Option<LengthStats>,
+    distinct_count: Option<usize>,
+    examples: Vec<String>,
+    semantic_type: Option<SemanticType>,
+    levels: Vec<String>,
+}
+
+fn field(metadata: &Metadata, i: usize) -> Field {
+    Field {
+        name: metadata.field_names.get(i).cloned(),
+        ty: metadata.types[i],
+        format: metadata.formats.get(i).cloned().flatten(),
+        plain_values: metadata.plain_values.get(i).copied().unwrap_or(false),
+        nullable: metadata.nullable.get(i).copied().unwrap_or(true),
+        null_count: metadata.null_counts.get(i).copied().unwrap_or(0),
+        type_counts: metadata.type_counts.get(i).cloned().unwrap_or_default(),
+        type_confidence: metadata.type_confidence.get(i).copied().unwrap_or(0.0),
+        offending_values: metadata
+            .offending_values
+            .get(i)
+            .cloned()
+            .unwrap_or_default(),
+        statistics: metadata.statistics.get(i).copied().flatten(),
+        increasing: metadata.increasing.get(i).copied().unwrap_or(false),
+        lengths: metadata.lengths.get(i).copied().flatten(),
+        distinct_count: metadata.distinct_counts.get(i).copied().flatten(),
+        examples: metadata.examples.get(i).cloned().unwrap_or_default(),
+        semantic_type: metadata.semantic_types.get(i).copied().flatten(),
+        levels: metadata.levels.get(i).cloned().unwrap_or_default(),
+    }
+}
+
+/// The narrowest type that fits the values of both provided types: integers widen to signed
+/// integers, then to `BigInt`s, and numbers of different kinds to `Float`s; other types that
+/// differ (including date-times with different formats, see `merge_field`) widen to `Text`.
+fn widen(a: Type, b: Type) -> Type {
+    match (a, b) {
+        _ if a == b => a,
+        (Type::Null, ty) | (ty, Type::Null) => ty,
+        (Type::Unsigned | Type::Signed, Type::Unsigned | Type::Signed) => Type::Signed,
+        (
+            Type::Unsigned | Type::Signed | Type::BigInt,
+            Type::Unsigned | Type::Signed | Type::BigInt,
+        ) => Type::BigInt,
+        // leading zeros are significant in either file
+        (Type::NumericString, Type::Unsigned) | (Type::Unsigned, Type::NumericString) => {
+            Type::NumericString
+        }
+        (
+            Type::Decimal {
+                precision: a_precision,
+                scale: a_scale,
+            },
+            Type::Decimal {
+                precision: b_precision,
+                scale: b_scale,
+            },
+        ) => {
+            let scale = a_scale.max(b_scale);
+            Type::Decimal {
+                precision: (a_precision - a_scale).max(b_precision - b_scale) + scale,
+                scale,
+            }
+        }
+        (
+            Type::Unsigned | Type::Signed | Type::BigInt | Type::Float | Type::Decimal { .. },
+            Type::Unsigned | Type::Signed | Type::BigInt | Type::Float | Type::Decimal { .. },
+        ) => Type::Float,
+        (Type::Currency { symbol: a_symbol }, Type::Currency { symbol: b_symbol })
+            if a_symbol.is_none() || b_symbol.is_none() || a_symbol == b_symbol =>
+        {
+            Type::Currency {
+                symbol: a_symbol.or(b_symbol),
+            }
+        }
+        (
+            Type::Binary {
+                encoding: a_encoding,
+                max_len: a_max_len,
+            },
+            Type::Binary {
+                encoding: b_encoding,
+                max_len: b_max_len,
+            },
+        ) if a_encoding == b_encoding => Type::Binary {
+            encoding: a_encoding,
+            max_len: a_max_len.max(b_max_len),
+        },
+        // the cardinality of the union of the levels is counted by `merge_field`
+        (Type::Categorical { .. }, Type::Categorical { .. }) => a,
+        _ => Type::Text,
+    }
+}
+
+// Combines the statistics of the lengths of two sets of values.
+fn merge_lengths(a: Option<LengthStats>, b: Option<LengthStats>) -> Option<LengthStats> {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let count = a.count + b.count;
+            let weight = b.count as f64 / count as f64;
+            Some(LengthStats {
+                count,
+                min_bytes: a.min_bytes.min(b.min_bytes),
+                max_bytes: a.max_bytes.max(b.max_bytes),
+                mean_bytes: a.mean_bytes + (b.mean_bytes - a.mean_bytes) * weight,
+                min_chars: a.min_chars.min(b.min_chars),
+                max_chars: a.max_chars.max(b.max_chars),
+                mean_chars: a.mean_chars + (b.mean_chars - a.mean_chars) * weight,
+            })
+        }
+        (a, b) => a.or(b),
+    }
+}
+
+// The values of both lists, without duplicates, up to the longer length of the two lists.
+fn merge_values(a: &[String], b: &[String]) -> Vec<String> {
+    let mut values = a.to_vec();
+    for value in b {
+        if values.len() < a.len().max(b.len()) && !values.contains(value) {
+            values.push(value.clone());
+        }
+    }
+    values
+}
+
+fn merge_field(a: &Field, b: &Field) -> Field {
+    let is_temporal = matches!(a.ty, Type::DateTime | Type::Date | Type::Time);
+    let (mut ty, format) = match (a.ty, b.ty) {
+        (Type::Null, _) => (b.ty, b.format.clone()),
+        (_, Type::Null) => (a.ty, a.format.clone()),
+        // values written in a different format don't fit the other file's format
+        _ if a.ty == b.ty && is_temporal && a.format != b.format => (Type::Text, None),
+        _ => (widen(a.ty, b.ty), a.format.clone().filter(|_| a.ty == b.ty)),
+    };
+    let levels = if let Type::Categorical { .. } = ty {
+        let levels = a
+            .levels
+            .iter()
+            .chain(&b.levels)
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        ty = Type::Categorical {
+            cardinality: levels.len(),
+        };
+        levels.into_iter().collect()
+    } else {
+        vec![]
+    };
+    let mut type_counts = a.type_counts.clone();
+    for &(count_ty, count) in &b.type_counts {
+        match type_counts.iter_mut().find(|(ty, _)| *ty == count_ty) {
+            Some((_, total)) => *total += count,
+            None => type_counts.push((count_ty, count)),
+        }
+    }
+    type_counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+    Field {
+        name: a.name.clone().or_else(|| b.name.clone()),
+        ty,
+        format,
+        plain_values: a.plain_values && b.plain_values,
+        nullable: a.nullable || b.nullable,
+        null_count: a.null_count + b.null_count,
+        type_counts,
+        type_confidence: a.type_confidence.min(b.type_confidence),
+        offending_values: merge_values(&a.offending_values, &b.offending_values),
+        statistics: if is_numeric(ty) {
+            merge_stats(a.statistics, b.statistics)
+        } else {
+            None
+        },
+        // the files may be in any order
+        increasing: false,
+        lengths: merge_lengths(a.lengths, b.lengths),
+        // distinct values can't be counted across files from the counts of each file
+        distinct_count: None,
+        examples: merge_values(&a.examples, &b.examples),
+        semantic_type: a
+            .semantic_type
+            .filter(|_| a.semantic_type == b.semantic_type),
+        levels,
+    }
+}
+
+// The index of the field of the other metadata matching each field of the metadata, if any: by
+// name if both files have header rows, and by position otherwise.
+pub(crate) fn matching_fields(metadata: &Metadata, other: &Metadata) -> Vec<Option<usize>> {
+    let num_fields = metadata.types.len();
+    if metadata.field_names.is_empty() || other.field_names.is_empty() {
+        return (0..num_fields)
+            .map(|i| (i < other.types.len()).then_some(i))
+            .collect();
+    }
+    let mut matches: Vec<Option<usize>> = vec![];
+    for i in 0..num_fields {
+        let name = metadata.field_names.get(i);
+        // duplicate names match in order
+        let j = (0..other.types.len())
+            .find(|j| other.field_names.get(*j) == name && !matches.contains(&Some(*j)));
+        matches.push(j);
+    }
+    matches
+}
+
+/// The metadata of the provided metadata's file and the other metadata's file taken together.
+pub(crate) fn merge(metadata: &Metadata, other: &Metadata) -> Metadata {
+    let matches = matching_fields(metadata, other);
+    let mut fields = matches
+        .iter()
+        .enumerate()
+        .map(|(i, j)| match *j {
+            Some(j) => merge_field(&field(metadata, i), &field(other, j)),
+            // fields missing from a file are null in its records
+            None => Field {
+                nullable: true,
+                increasing: false,
+                ..field(metadata, i)
+            },
+        })
+        .collect::<Vec<_>>();
+    let unmatched = (0..other.types.len()).filter(|j| !matches.contains(&Some(*j)));
+    fields.extend(unmatched.map(|j| Field {
+        name: Some(other.field_name(j)),
+        nullable: true,
+        increasing: false,
+        ..field(other, j)
+    }));
+
+    // keys of both files (over the same fields) are still keys, as long as the files don't share
+    // records
+    let primary_keys = metadata
+        .primary_keys
+        .iter()
+        .filter(|key| {
+            let other_key = key.iter().map(|&i| matches[i]).collect::<Option<Vec<_>>>();
+            other_key.is_some_and(|other_key| other.primary_keys.contains(&other_key))
+        })
+        .cloned()
+        .collect();
+    let estimated_num_records = metadata
+        .estimated_num_records
+        .zip(other.estimated_num_records)
+        .map(|(a, b)| a + b);
+    let mean_record_len = match (metadata.estimated_num_records, other.estimated_num_records) {
+        (Some(a), Some(b)) if a + b > 0 => {
+            (metadata.mean_record_len * a as f64 + other.mean_record_len * b as f64)
+                / (a + b) as f64
+        }
+        _ => (metadata.mean_record_len + other.mean_record_len) / 2.0,
+    };
+    let num_effective_fields = fields.len();
+    let field_names = if metadata.field_names.is_empty() {
+        vec![]
+    } else {
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                field
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("column_{}", i + 1))
+            })
+            .collect()
+    };
+    Metadata {
+        num_fields: num_effective_fields + metadata.dialect.num_trailing_empty_fields,
+        num_effective_fields,
+        num_blank_lines: metadata.num_blank_lines + other.num_blank_lines,
+        estimated_num_records,
+        mean_record_len,
+        max_record_len: metadata.max_record_len.max(other.max_record_len),
+        types: fields.iter().map(|field| field.ty).collect(),
+        formats: fields.iter().map(|field| field.format.clone()).collect(),
+        plain_values: fields.iter().map(|field| field.plain_values).collect(),
+        nullable: fields.iter().map(|field| field.nullable).collect(),
+        null_counts: fields.iter().map(|field| field.null_count).collect(),
+        type_counts: fields
+            .iter()
+            .map(|field| field.type_counts.clone())
+            .collect(),
+        type_confidence: fields.iter().map(|field| field.type_confidence).collect(),
+        offending_values: fields
+            .iter()
+            .map(|field| field.offending_values.clone())
+            .collect(),
+        statistics: fields.iter().map(|field| field.statistics).collect(),
+        increasing: fields.iter().map(|field| field.increasing).collect(),
+        primary_keys,
+        lengths: fields.iter().map(|field| field.lengths).collect(),
+        distinct_counts: fields.iter().map(|field| field.distinct_count).collect(),
+        examples: fields.iter().map(|field| field.examples.clone()).collect(),
+        semantic_types: fields.iter().map(|field| field.semantic_type).collect(),
+        levels: fields.iter().map(|field| field.levels.clone()).collect(),
+        date_order: metadata.date_order.or(other.date_order),
+        date_order_ambiguous: metadata.date_order_ambiguous && other.date_order_ambiguous,
+        field_names,
+        confidence: Confidence {
+            delimiter: metadata
+                .confidence
+                .delimiter
+                .min(other.confidence.delimiter),
+            header: metadata.confidence.header.min(other.confidence.header),
+            quote: metadata.confidence.quote.min(other.confidence.quote),
+        },
+        verification: metadata
+            .verification
+            .as_ref()
+            .zip(other.verification.as_ref())
+            .map(|(a, b)| Verification {
+                num_records: a.num_records + b.num_records,
+                num_field_count_violations: a.num_field_count_violations
+                    + b.num_field_count_violations,
+                num_type_violations: a.num_type_violations + b.num_type_violations,
+            }),
+        ..metadata.clone()
+    }
+}
@@ -3,12 +3,29 @@ CSV metadata types.
 */
 use std::fmt;
 use std::fs::File;
-use std::io::{Read, Seek};
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::ops::Range;
 use std::path::Path;
+use std::str::FromStr;
 
-use csv::{Reader, ReaderBuilder};
+use csv::{Reader, ReaderBuilder, WriterBuilder};
+#[cfg(feature = "encoding")]
+use encoding_rs_io::{DecodeReaderBytes, DecodeReaderBytesBuilder};
+#[cfg(feature = "encoding")]
+use std::io::{BufRead, BufReader};
 
-use crate::{error::Result, field_type::Type, snip::snip_preamble};
+use crate::{
+    error::{Result, SnifferError},
+    field_type::{SemanticType, Type},
+    snip::snip_preamble,
+};
+
+pub use crate::compression::DecompressReader;
+pub use crate::diff::{DialectChange, SchemaDiff, TypeChange};
+pub use crate::filter::{FilteredReader, ReadOptions};
+pub use crate::sql::SqlDialect;
+pub use crate::stream::StreamReader;
+pub use crate::validate::{Validation, Violation};
 
 /// Primary CSV metadata. Generated by
 /// [`Sniffer::sniff_path`](../struct.Sniffer.html#method.sniff_path) or
@@ -20,22 +37,512 @@ pub struct Metadata {
     pub dialect: Dialect,
     /// (Maximum) number of fields per record.
     pub num_fields: usize,
+    /// Number of fields per record, not counting trailing fields that are empty in every record
+    /// (see [`Dialect::num_trailing_empty_fields`](struct.Dialect.html#structfield.num_trailing_empty_fields)).
+    pub num_effective_fields: usize,
+    /// Number of blank lines found among the data rows (in the sample).
+    pub num_blank_lines: usize,
+    /// Estimated number of data records (not counting the header row or footer rows) in the whole
+    /// file, from the length of the file and the average length of the records at the start of
+    /// the data; exact if the sample covers the whole file (e.g. with `SampleSize::All`) or if
+    /// verified (see [`Sniffer::verify`](../struct.Sniffer.html#method.verify)). `None`
+    /// if the length of the file isn't known (e.g. when sniffing part of a stream).
+    pub estimated_num_records: Option<usize>,
+    /// Mean length in bytes (including the terminator) of the data records at the head of the
+    /// sample (or in the whole file, if verified).
+    pub mean_record_len: f64,
+    /// Maximum length in bytes (including the terminator) of the data records at the head of the
+    /// sample (or in the whole file, if verified), e.g. to flag files with pathologically long
+    /// lines before parsing them.
+    pub max_record_len: usize,
     /// Inferred field types.
     pub types: Vec<Type>,
+    /// Detected format of each field's values (a `strftime`-style format string, e.g.
+    /// `%Y-%m-%dT%H:%M:%S%.f%:z`), for fields whose type has formats (`Type::DateTime`); `None`
+    /// for other fields.
+    pub formats: Vec<Option<String>>,
+    /// Whether or not the (non-null) values of each field are all plain booleans or numbers, the
+    /// way e.g. Rust's `FromStr` implementations parse them: `true` or `false`, or numbers without
+    /// grouping separators and with a `.` decimal separator (if any). Only meaningful for boolean
+    /// and numeric fields.
+    pub plain_values: Vec<bool>,
+    /// Whether or not each field contains null values (see
+    /// [`Sniffer::null_tokens`](../struct.Sniffer.html#method.null_tokens)) in the sample (or in the
+    /// whole file, if verified).
+    pub nullable: Vec<bool>,
+    /// Number of null values of each field in the sample (or in the whole file, if verified), out
+    /// of the total number of values counted in
+    /// [`type_counts`](#structfield.type_counts).
+    pub null_counts: Vec<usize>,
+    /// Numbers of values of each field fitting each type (taking each value by itself, so that
+    /// e.g. a column of integers with a few stray words has mostly `Type::Unsigned` values and a
+    /// few `Type::Text` ones), most common first, in the sample (or in the whole file, if
+    /// verified). Null values are counted as `Type::Null`.
+    pub type_counts: Vec<Vec<(Type, usize)>>,
+    /// Confidence in each field's type, between 0.0 and 1.0, growing with the number of (non-null)
+    /// values it was inferred from and with the share of them that support it: all of them for
+    /// types narrower than `Type::Text`, but only the values that are text by themselves (see
+    /// [`type_counts`](#structfield.type_counts)) for text fields, so that a text field of mostly
+    /// numbers has low confidence. Fields of only null values have a confidence of 0.0.
+    pub type_confidence: Vec<f64>,
+    /// Values of each field that don't fit its type (up to 10 values of each of their own types),
+    /// which are only tolerated with
+    /// [`Sniffer::type_tolerance`](../struct.Sniffer.html#method.type_tolerance) (or with
+    /// [type hints](../struct.Sniffer.html#method.type_hint)); empty for other fields.
+    pub offending_values: Vec<Vec<String>>,
+    /// [`NumericStats`](struct.NumericStats.html) of each numeric field (if enabled, see
+    /// [`Sniffer::compute_statistics`](../struct.Sniffer.html#method.compute_statistics)), in the
+    /// sample (or in the whole file, if verified); `None` for other fields.
+    pub statistics: Vec<Option<NumericStats>>,
+    /// Whether or not the values of each numeric field (see
+    /// [`NumericStats`](struct.NumericStats.html)) are strictly increasing at the head of the
+    /// sample (or in the whole file, if verified), e.g. row numbers or sequential IDs, making the
+    /// field a candidate for a natural ordering (or partitioning) key. Fields with null values or
+    /// with fewer than two values aren't.
+    pub increasing: Vec<bool>,
+    /// Plausible primary keys (if detected, see
+    /// [`Sniffer::detect_primary_keys`](../struct.Sniffer.html#method.detect_primary_keys)): the
+    /// fields whose values are non-null and distinct at the head of the sample, or (if there
+    /// aren't any) the pairs of fields whose combined values are. Each key lists the indices of
+    /// its fields.
+    pub primary_keys: Vec<Vec<usize>>,
+    /// [`LengthStats`](struct.LengthStats.html) of the values of each field (if enabled, see
+    /// [`Sniffer::compute_statistics`](../struct.Sniffer.html#method.compute_statistics)), in the
+    /// sample (or in the whole file, if verified); `None` for fields of only null values.
+    pub lengths: Vec<Option<LengthStats>>,
+    /// Estimated number of distinct (non-null) values of each field (if enabled, see
+    /// [`Sniffer::compute_statistics`](../struct.Sniffer.html#method.compute_statistics)), in the
+    /// sample (or in the whole file, if verified), from a HyperLogLog sketch: exact for small
+    /// numbers of values, and within a few percent otherwise. `None` if not enabled.
+    pub distinct_counts: Vec<Option<usize>>,
+    /// Example (non-null) values of each field (if enabled, see
+    /// [`Sniffer::example_values`](../struct.Sniffer.html#method.example_values)), in the sample
+    /// (or in the whole file, if verified), e.g. for previews next to the inferred types.
+    pub examples: Vec<Vec<String>>,
+    /// Detected [`SemanticType`](../enum.SemanticType.html) of each field (if enabled, see
+    /// [`Sniffer::detect_semantic_types`](../struct.Sniffer.html#method.detect_semantic_types));
+    /// `None` for fields without one.
+    pub semantic_types: Vec<Option<SemanticType>>,
+    /// Distinct values (sorted) of each
+    /// [`Type::Categorical`](../enum.Type.html#variant.Categorical) field; empty for other fields.
+    pub levels: Vec<Vec<String>>,
+    /// [`NumberFormat`](struct.NumberFormat.html) of the numeric fields.
+    pub number_format: NumberFormat,
+    /// [`DateOrder`](enum.DateOrder.html) of the dates (and date-times) with both a day and a
+    /// month, if any (other than year-first dates, like `2021-03-04`).
+    pub date_order: Option<DateOrder>,
+    /// Whether or not every date could be read either day-first or month-first, in which case the
+    /// `date_order` is the one preferred (see
+    /// [`Sniffer::date_preference`](../struct.Sniffer.html#method.date_preference)).
+    pub date_order_ambiguous: bool,
+    /// Field names (the labels in the header row). Empty if the file doesn't have a header row.
+    pub field_names: Vec<String>,
+    /// Contents of the preamble rows (the rows before the header row or first data row), without
+    /// their terminators.
+    pub preamble: Vec<String>,
+    /// Byte offset at which the header row (or first data row, if there is no header row) begins.
+    pub data_offset: u64,
+    /// [`Bom`](enum.Bom.html) (byte order mark) found at the start of the file, if any.
+    pub bom: Option<Bom>,
+    /// Character [`Encoding`](enum.Encoding.html) of the file. Files that aren't UTF-8 are
+    /// transcoded to UTF-8 while sniffing; `data_offset` still refers to the original bytes.
+    pub encoding: Encoding,
+    /// [`Compression`](enum.Compression.html) format of the file. Compressed files are
+    /// decompressed while sniffing; `data_offset` refers to the decompressed bytes.
+    pub compression: Compression,
+    /// [`Confidence`](struct.Confidence.html) in the detected dialect details.
+    pub confidence: Confidence,
+    /// Results of the full-file [`Verification`](struct.Verification.html) pass, if enabled (see
+    /// [`Sniffer::verify`](../struct.Sniffer.html#method.verify)).
+    pub verification: Option<Verification>,
 }
 impl fmt::Display for Metadata {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "Metadata")?;
         writeln!(f, "========")?;
         writeln!(f, "{}", self.dialect)?;
+        write!(f, "{}", self.confidence)?;
         writeln!(f, "Number of fields: {}", self.num_fields)?;
+        writeln!(
+            f,
+            "Number of effective fields: {}",
+            self.num_effective_fields
+        )?;
+        writeln!(f, "Number of blank lines: {}", self.num_blank_lines)?;
+        writeln!(f, "Data offset: {}", self.data_offset)?;
+        writeln!(f, "Byte order mark: {:?}", self.bom)?;
+        writeln!(f, "Encoding: {:?}", self.encoding)?;
+        writeln!(f, "Compression: {:?}", self.compression)?;
+        write!(f, "{}", self.number_format)?;
+        if let Some(date_order) = self.date_order {
+            if self.date_order_ambiguous {
+                writeln!(f, "Date order: {:?} (ambiguous)", date_order)?;
+            } else {
+                writeln!(f, "Date order: {:?}", date_order)?;
+            }
+        }
+        if let Some(ref verification) = self.verification {
+            write!(f, "{}", verification)?;
+        }
+        if !self.preamble.is_empty() {
+            writeln!(f, "Preamble:")?;
+            for line in &self.preamble {
+                writeln!(f, "\t{}", line)?;
+            }
+        }
         writeln!(f, "Types:")?;
         for (i, ty) in self.types.iter().enumerate() {
-            writeln!(f, "\t{}: {}", i, ty)?;
+            match self.field_names.get(i) {
+                Some(name) => write!(f, "\t{} ({}): {}", i, name, ty)?,
+                None => write!(f, "\t{}: {}", i, ty)?,
+            }
+            if let Some(Some(format)) = self.formats.get(i) {
+                write!(f, " ({})", format)?;
+            }
+            if let Some(Some(semantic_type)) = self.semantic_types.get(i) {
+                write!(f, " ({})", semantic_type)?;
+            }
+            if self.nullable.get(i) == Some(&true) {
+                write!(f, " (nullable)")?;
+            }
+            if let Some(levels) = self.levels.get(i).filter(|levels| !levels.is_empty()) {
+                write!(f, " [{}]", levels.join(", "))?;
+            }
+            writeln!(f)?;
         }
         Ok(())
     }
 }
+impl Metadata {
+    /// Serializes this `Metadata` to JSON, in a documented shape that is kept stable across
+    /// versions of this crate. The shape is written out by hand rather than derived from the
+    /// layout of the types, and the `serde` implementations of `Metadata` and
+    /// [`Dialect`](struct.Dialect.html) use it as well:
+    ///
+    /// * an object with a `"version"` key (currently `1`, increased only on incompatible changes)
+    ///   and a key for each field of `Metadata`, named after it (new fields add new keys);
+    /// * the `dialect` is an object with a key for each field of
+    ///   [`Dialect`](struct.Dialect.html); bytes (the delimiter, quote, escape and comment
+    ///   characters) are single-character strings, or `null` if disabled, and the terminator is
+    ///   `"CRLF"`, `"LF"`, `"CR"` or a single-character string;
+    /// * structs (e.g. [`Header`](struct.Header.html) or
+    ///   [`NumericStats`](struct.NumericStats.html)) are objects with a key for each field;
+    /// * enums without data (e.g. [`Encoding`](enum.Encoding.html)) are the names of their
+    ///   variants, e.g. `"Utf8"`, and `None` is `null`;
+    /// * each [`Type`](../enum.Type.html) is an object with its name as `"type"` and its details
+    ///   as further keys, e.g. `{"type":"Decimal","precision":5,"scale":2}`, and each entry of
+    ///   `type_counts` is such an object with an additional `"count"` key;
+    /// * non-finite numbers (e.g. the mean of no values) are `null`.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        crate::json::to_json(self)
+    }
+
+    /// Deserializes `Metadata` from JSON in the shape written by
+    /// [`to_json`](#method.to_json). Unknown keys are ignored, and missing keys (e.g. written by
+    /// an older version) take default values, except for `version`, `dialect`, `num_fields`,
+    /// `num_effective_fields`, `types`, `encoding`, `compression` and `confidence`. Fails with
+    /// `SnifferError::Json` if the JSON is invalid, doesn't have this shape, or is of a newer
+    /// version.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Metadata> {
+        crate::json::from_json(json)
+    }
+
+    /// Merges the metadata of another file (e.g. another part of the same dataset) into a single
+    /// schema that fits the records of both files. Fields are matched by name if both files have a
+    /// header row, and by position otherwise; fields found in only one of the files are kept (the
+    /// other file's at the end), and are nullable.
+    ///
+    /// Types of matched fields are widened to fit the values of both: unsigned integers to signed
+    /// integers, integers to `Type::BigInt`, and numbers of different kinds (e.g. integers and
+    /// floats) to `Type::Float`; date-times written in different formats, and other types that
+    /// differ, become `Type::Text`. Fields are nullable if nullable in either file, and the
+    /// counts, statistics, lengths, examples and levels of the fields are combined, but distinct
+    /// counts can't be (so they're `None`), and fields aren't `increasing` across files.
+    ///
+    /// The dialect and the other details of the file itself (e.g. the preamble, data offset and
+    /// encoding) are this file's. See also
+    /// [`Sniffer::sniff_paths`](../struct.Sniffer.html#method.sniff_paths).
+    pub fn merge(&self, other: &Metadata) -> Metadata {
+        crate::merge::merge(self, other)
+    }
+
+    /// Compares this file's schema with another file's (e.g. the previous and latest deliveries
+    /// of a recurring export), and returns the [`SchemaDiff`](struct.SchemaDiff.html): the fields
+    /// added and removed, the fields whose types (or formats) changed, and the dialect settings
+    /// that changed. Fields are matched as by [`merge`](#method.merge): by name if both files
+    /// have a header row, and by position otherwise.
+    pub fn diff(&self, other: &Metadata) -> SchemaDiff {
+        crate::diff::diff(self, other)
+    }
+
+    /// Generates a [JSON Schema](https://json-schema.org) (draft 2020-12) describing the records
+    /// of the file as objects with a (required) property for each field, named after
+    /// `field_names`, or `column_1`, `column_2`, etc. if the file doesn't have a header row.
+    ///
+    /// Integers are `integer`s (with a `minimum` of 0 if unsigned), floats and decimals `number`s,
+    /// booleans `boolean`s, and other fields (including currency amounts and percentages)
+    /// `string`s, with a `format` for RFC 3339 date-times, ISO 8601 dates, UUIDs, and email
+    /// addresses and URLs (see
+    /// [`Sniffer::detect_semantic_types`](../struct.Sniffer.html#method.detect_semantic_types)),
+    /// and an `enum` of the levels of categorical fields. Properties also allow `null` unless
+    /// verified not to have null values (see
+    /// [`Sniffer::verify`](../struct.Sniffer.html#method.verify)).
+    #[cfg(feature = "serde")]
+    pub fn to_json_schema(&self) -> String {
+        crate::json_schema::schema(self).to_string()
+    }
+
+    /// Generates an [Avro](https://avro.apache.org) record schema (as JSON) with the provided name
+    /// (which may include a namespace, e.g. `com.example.Visit`) and a field for each field,
+    /// named after `field_names`, or `column_1`, `column_2`, etc. if the file doesn't have a
+    /// header row. Names are made valid Avro names by replacing other characters than ASCII
+    /// letters, digits and underscores with underscores (fields whose names are changed keep
+    /// their original names as `doc`).
+    ///
+    /// Integers are `long`s (`decimal`s if beyond 64 bits), floats `double`s, decimals `decimal`s
+    /// (of `bytes`), dates `date`s, times `time-millis`, date-times `timestamp-millis` (or
+    /// `local-timestamp-millis` if they don't have offsets), UUIDs `uuid`s, and other fields
+    /// (including currency amounts and percentages) `string`s. Fields are unions with `null`
+    /// (defaulting to `null`) unless verified not to have null values (see
+    /// [`Sniffer::verify`](../struct.Sniffer.html#method.verify)).
+    #[cfg(feature = "serde")]
+    pub fn to_avro_schema(&self, name: &str) -> String {
+        crate::avro::schema(self, name).to_string()
+    }
+
+    /// Exports this `Metadata` as a [Frictionless Data](https://specs.frictionlessdata.io) tabular
+    /// data resource (as JSON, e.g. for the `resources` of a `datapackage.json`) for the file at
+    /// the provided path, named after the file. The resource has:
+    ///
+    /// * a CSV `dialect`, with the delimiter, terminator, quote, escape and comment characters,
+    ///   whether quotes are doubled, whether there is a header row, and whether leading spaces are
+    ///   skipped (preamble and footer rows can't be described);
+    /// * a Table Schema with a field for each field, named after `field_names`, or `column_1`,
+    ///   `column_2`, etc. if the file doesn't have a header row. Integers are `integer`s (with a
+    ///   `minimum` of 0 if unsigned), floats, decimals, currency amounts and percentages `number`s
+    ///   (with the decimal and grouping separators of the
+    ///   [`NumberFormat`](struct.NumberFormat.html)), dates, times and date-times `date`s, `time`s
+    ///   and `datetime`s, and other fields `string`s (with the `uuid`, `email` or `uri` format, if
+    ///   detected). Categorical fields have an `enum` of their levels, and fields are `required`
+    ///   if verified not to have null values (see
+    ///   [`Sniffer::verify`](../struct.Sniffer.html#method.verify)).
+    #[cfg(feature = "serde")]
+    pub fn to_frictionless_resource(&self, path: &str) -> String {
+        crate::frictionless::resource(self, path).to_string()
+    }
+
+    /// Exports this `Metadata` as W3C [CSV on the Web](https://www.w3.org/TR/tabular-metadata/)
+    /// (CSVW) metadata (as JSON, e.g. for a `-metadata.json` sidecar file) of the table at the
+    /// provided URL. The metadata has:
+    ///
+    /// * a `dialect`, with the encoding, delimiter, terminator, quote and comment characters,
+    ///   whether quotes are doubled, whether there is a header row, the number of preamble rows to
+    ///   skip, and whether blank rows are skipped and values trimmed (footer rows can't be
+    ///   described);
+    /// * a `tableSchema` with a column for each field, titled after `field_names` (with their
+    ///   characters other than ASCII letters, digits and underscores percent-encoded in their
+    ///   names), or named `column_1`, `column_2`, etc. if the file doesn't have a header row.
+    ///   Integers are `integer`s (`nonNegativeInteger`s if unsigned), floats `double`s, decimals
+    ///   `decimal`s, dates, times and date-times `date`s, `time`s and `datetime`s (with the
+    ///   detected format, unless ISO 8601), JSON `json`, base64 `base64Binary`, URLs `anyURI`s,
+    ///   and other fields (including currency amounts and percentages) `string`s. Numbers have the
+    ///   separators of the [`NumberFormat`](struct.NumberFormat.html) (unless the default), and
+    ///   columns are `required` if verified not to have null values (see
+    ///   [`Sniffer::verify`](../struct.Sniffer.html#method.verify)).
+    #[cfg(feature = "serde")]
+    pub fn to_csvw_metadata(&self, url: &str) -> String {
+        crate::csvw::metadata(self, url).to_string()
+    }
+
+    /// Generates the source code of a Rust struct with the provided name (converted to
+    /// `UpperCamelCase`) for the records of the file, with `serde` derives, ready to use with
+    /// [`csv::Reader::deserialize`](https://docs.rs/csv/latest/csv/struct.Reader.html#method.deserialize)
+    /// (e.g. with a reader opened with [`Dialect::open_path`](struct.Dialect.html#method.open_path)).
+    /// The struct has a public field for each field, named after `field_names` (converted to
+    /// `snake_case`, and renamed for `serde` if they change), or `column_1`, `column_2`, etc. if
+    /// the file doesn't have a header row.
+    ///
+    /// Integers are `u64`s or `i64`s (`i128`s if beyond 64 bits), floats and decimals `f64`s,
+    /// booleans `bool`s, and other fields (including dates and times, currency amounts and
+    /// percentages) `String`s. Booleans and numbers that Rust doesn't parse as they are (see
+    /// [`plain_values`](#structfield.plain_values), e.g. `yes` or `1,234`) are `String`s as well.
+    /// Fields are `Option`s unless verified not to have null values (see
+    /// [`Sniffer::verify`](../struct.Sniffer.html#method.verify)), with empty values read as
+    /// `None` (other null tokens, like `NA`, don't deserialize in non-`String` fields).
+    pub fn to_rust_struct(&self, name: &str) -> String {
+        crate::codegen::rust_struct(self, name)
+    }
+
+    /// Converts the inferred field types to an Arrow
+    /// [`Schema`](https://docs.rs/arrow-schema/latest/arrow_schema/struct.Schema.html) (the
+    /// `arrow::datatypes::Schema` of the [`arrow`](https://docs.rs/arrow) crate), e.g. for reading
+    /// the file with `arrow-csv`. Fields are named after `field_names`, or `column_1`,
+    /// `column_2`, etc. if the file doesn't have a header row.
+    ///
+    /// Integers are `UInt64` or `Int64` (`Decimal256` if beyond 64 bits), floats `Float64`,
+    /// decimals `Decimal128` (or `Decimal256`), dates `Date32`, times `Time64`, date-times
+    /// nanosecond `Timestamp`s (in UTC if they have offsets), categorical fields dictionaries of
+    /// strings, and other fields (including currency amounts and percentages, which `arrow-csv`
+    /// doesn't parse as numbers) `Utf8`. Booleans, numbers, dates and times that `arrow-csv`
+    /// doesn't parse are `Utf8` as well: booleans other than `true` and `false` (e.g. `yes`),
+    /// numbers with grouping separators or a decimal comma (see
+    /// [`plain_values`](#structfield.plain_values)), and dates and date-times other than ISO 8601
+    /// ones (e.g. `03/04/2021`). Fields are nullable unless verified not to be (see
+    /// [`Sniffer::verify`](../struct.Sniffer.html#method.verify)).
+    #[cfg(feature = "arrow")]
+    pub fn to_arrow_schema(&self) -> arrow_schema::Schema {
+        crate::arrow::schema(self)
+    }
+
+    /// Converts the inferred field types to a Parquet
+    /// [`SchemaDescriptor`](https://docs.rs/parquet/latest/parquet/schema/types/struct.SchemaDescriptor.html),
+    /// as the `parquet::arrow` writers (e.g. `ArrowWriter`) convert the Arrow schema (see
+    /// [`to_arrow_schema`](#method.to_arrow_schema)) of the records they write: integers are
+    /// `INT64` (annotated as unsigned if they are), floats `DOUBLE`, decimals `DECIMAL`s (of
+    /// `INT32`, `INT64` or `FIXED_LEN_BYTE_ARRAY`, by precision), dates `DATE`s, times and
+    /// date-times `TIME` and `TIMESTAMP` (in nanoseconds), and other fields (including the
+    /// booleans, numbers, dates and times that are `Utf8` in the Arrow schema) `STRING`s. Fields
+    /// are optional unless verified not to have null values (see
+    /// [`Sniffer::verify`](../struct.Sniffer.html#method.verify)).
+    ///
+    /// A sniffed CSV file can thus be converted to Parquet by reading it with `arrow-csv` with the
+    /// Arrow schema, and writing the batches with an `ArrowWriter` created with the same schema.
+    #[cfg(feature = "parquet")]
+    pub fn to_parquet_schema(&self) -> parquet::schema::types::SchemaDescriptor {
+        crate::parquet::schema(self)
+    }
+
+    /// Converts the inferred field types to a Polars
+    /// [`Schema`](https://docs.rs/polars/latest/polars/prelude/type.Schema.html). Fields are
+    /// named after `field_names`, or `column_1`, `column_2`, etc. if the file doesn't have a
+    /// header row.
+    ///
+    /// Integers are `UInt64` or `Int64` (`Int128` if beyond 64 bits), floats `Float64`, decimals
+    /// `Decimal` (or `Float64`, beyond the precision of Polars decimals), dates `Date`, times
+    /// `Time`, date-times microsecond `Datetime`s (in UTC if they have offsets), and other fields
+    /// (including currency amounts and percentages, which Polars doesn't parse as numbers)
+    /// `String`.
+    #[cfg(feature = "polars")]
+    pub fn to_polars_schema(&self) -> ::polars::prelude::Schema {
+        crate::polars::schema(self)
+    }
+
+    /// Converts this `Metadata` to Polars
+    /// [`CsvReadOptions`](https://docs.rs/polars/latest/polars/prelude/struct.CsvReadOptions.html)
+    /// for reading the file: the separator, quote character, terminator and comment character of
+    /// the dialect, whether or not there is a header row, the number of preamble rows to skip,
+    /// the decimal separator, and the schema (see [`to_polars_schema`](#method.to_polars_schema)).
+    /// Footer rows aren't skipped, and Polars expects UTF-8 files (see
+    /// [`Encoding`](enum.Encoding.html)).
+    #[cfg(feature = "polars")]
+    pub fn to_polars_read_options(&self) -> ::polars::prelude::CsvReadOptions {
+        crate::polars::read_options(self)
+    }
+
+    /// Converts this `Metadata` to a DataFusion
+    /// [`CsvFormat`](https://docs.rs/datafusion/latest/datafusion/datasource/file_format/csv/struct.CsvFormat.html)
+    /// (e.g. for a `ListingTable`): the delimiter, quote, escape, terminator and comment
+    /// characters of the dialect, whether or not there is a header row or records of varying
+    /// lengths, and the [`Compression`](enum.Compression.html). DataFusion can't skip preamble
+    /// rows (other than comment lines) or footer rows, nor disable quoting.
+    #[cfg(feature = "datafusion")]
+    pub fn to_datafusion_csv_format(&self) -> datafusion::datasource::file_format::csv::CsvFormat {
+        crate::datafusion::csv_format(self)
+    }
+
+    /// Converts this `Metadata` to DataFusion
+    /// [`CsvReadOptions`](https://docs.rs/datafusion/latest/datafusion/prelude/struct.CsvReadOptions.html)
+    /// with the provided schema (see [`to_arrow_schema`](#method.to_arrow_schema)), e.g. for
+    /// registering the file as a table:
+    ///
+    /// ```ignore
+    /// let schema = metadata.to_arrow_schema();
+    /// ctx.register_csv("table", path, metadata.to_datafusion_read_options(&schema)).await?;
+    /// ```
+    ///
+    /// The same dialect details as in [`to_datafusion_csv_format`](#method.to_datafusion_csv_format)
+    /// are set. DataFusion reads CSV files with `arrow-csv`, so the fields of the Arrow schema
+    /// only have the types of the values it parses.
+    #[cfg(feature = "datafusion")]
+    pub fn to_datafusion_read_options<'a>(
+        &self,
+        schema: &'a arrow_schema::Schema,
+    ) -> datafusion::datasource::file_format::options::CsvReadOptions<'a> {
+        crate::datafusion::read_options(self, schema)
+    }
+
+    /// Generates a SQL `CREATE TABLE` statement for a table with the provided name (quoted as a
+    /// single identifier) in the provided [`SqlDialect`](enum.SqlDialect.html), with a column for
+    /// each field. Columns are named after `field_names`, or `column_1`, `column_2`, etc. if the
+    /// file doesn't have a header row.
+    ///
+    /// Integers are `BIGINT` (`BIGINT UNSIGNED` in MySQL, `INTEGER` in SQLite, and `NUMERIC(39, 0)`
+    /// if beyond 64 bits), floats `DOUBLE PRECISION`, decimals `NUMERIC(precision, scale)`, and
+    /// dates, times and date-times `DATE`, `TIME` and `TIMESTAMP` (`WITH TIME ZONE` if they have
+    /// offsets; `DATETIME` in MySQL, and `TEXT` in SQLite). PostgreSQL columns of UUIDs, IP
+    /// addresses and JSON are `UUID`, `INET` and `JSONB`. Other columns (including currency
+    /// amounts and percentages) are `VARCHAR(n)` sized to their longest value if the lengths of
+    /// the values were computed (see
+    /// [`Sniffer::compute_statistics`](../struct.Sniffer.html#method.compute_statistics)), or
+    /// `TEXT` (`CLOB` in standard SQL) otherwise. Columns are `NOT NULL` only if verified not to
+    /// have null values (see [`Sniffer::verify`](../struct.Sniffer.html#method.verify)).
+    pub fn to_sql_ddl(&self, dialect: SqlDialect, table: &str) -> String {
+        crate::sql::create_table(self, dialect, table)
+    }
+
+    /// The name of a field: its label in the header row, or `column_<n>` (counting from 1) if the
+    /// file doesn't have a header row.
+    pub(crate) fn field_name(&self, i: usize) -> String {
+        self.field_names
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| format!("column_{}", i + 1))
+    }
+}
+
+/// A table found within a CSV file by
+/// [`Sniffer::sniff_tables`](../struct.Sniffer.html#method.sniff_tables).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    /// Byte range of the table within the file.
+    pub range: Range<u64>,
+    /// Metadata of the table. Its `data_offset` is relative to the start of the file.
+    pub metadata: Metadata,
+}
+impl Table {
+    /// Create a `Reader` (from the [`csv`](https://docs.rs/csv) crate) for this table from the
+    /// reader of the whole file. The table's contents are read into memory. Fails if unable to
+    /// read from the reader.
+    pub fn open_reader<R: Read + Seek>(&self, mut rdr: R) -> Result<Reader<Cursor<Vec<u8>>>> {
+        rdr.seek(SeekFrom::Start(self.range.start))?;
+        let mut contents = vec![];
+        rdr.take(self.range.end - self.range.start)
+            .read_to_end(&mut contents)?;
+        self.metadata.dialect.open_reader(Cursor::new(contents))
+    }
+}
+
+/// Confidence scores for the detected dialect details. Each score ranges from 0.0 (a guess) to 1.0
+/// (no competing alternative was found, or the detail was specified on the `Sniffer`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Confidence {
+    /// Confidence in the detected delimiter.
+    pub delimiter: f64,
+    /// Confidence in the detected presence (or absence) of a header row.
+    pub header: f64,
+    /// Confidence in the detected quote character (or lack thereof).
+    pub quote: f64,
+}
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Confidence:")?;
+        writeln!(f, "\tDelimiter: {:.3}", self.delimiter)?;
+        writeln!(f, "\tHeader: {:.3}", self.header)?;
+        writeln!(f, "\tQuote: {:.3}", self.quote)
+    }
+}
 
 /// Dialect-level metadata. This type encapsulates the details to be used to derive a
 /// `ReaderBuilder` object (in the [`csv`](https://docs.rs/csv) crate).
@@ -49,6 +556,31 @@ pub struct Dialect {
     pub quote: Quote,
     /// Whether or not the number of fields in a record is allowed to change.
     pub flexible: bool,
+    /// Record terminator.
+    pub terminator: Terminator,
+    /// [`Escape`](enum.Escape.html) character used within quoted fields (if any).
+    pub escape: Escape,
+    /// [`Comment`](enum.Comment.html) character that starts lines to be ignored (if any).
+    pub comment: Comment,
+    /// Whether two quote characters in a row within a quoted field are interpreted as an escaped
+    /// quote.
+    pub doublequote_escapes: bool,
+    /// [`QuoteStyle`](enum.QuoteStyle.html) describing which fields are quoted.
+    pub quote_style: QuoteStyle,
+    /// Number of trailing fields that are empty in every record (for example, from a run of
+    /// delimiters at the end of each line).
+    pub num_trailing_empty_fields: usize,
+    /// [`Trim`](enum.Trim.html) setting describing which fields are padded with whitespace.
+    pub trim: Trim,
+    /// Whether or not blank lines (empty or containing only whitespace) are skipped. Empty lines
+    /// are always skipped by the [`csv`](https://docs.rs/csv) reader; lines containing only
+    /// whitespace are skipped by readers opened with
+    /// [`open_reader_with`](#method.open_reader_with).
+    pub skip_blank_lines: bool,
+    /// Character [`Encoding`](enum.Encoding.html) of the file. Readers opened with
+    /// [`open_reader`](#method.open_reader) expect UTF-8; with the `encoding` feature enabled,
+    /// [`open_reader_transcoded`](#method.open_reader_transcoded) handles any detected encoding.
+    pub encoding: Encoding,
 }
 impl PartialEq for Dialect {
     fn eq(&self, other: &Dialect) -> bool {
@@ -56,6 +588,15 @@ impl PartialEq for Dialect {
             && self.header == other.header
             && self.quote == other.quote
             && self.flexible == other.flexible
+            && self.terminator == other.terminator
+            && self.escape == other.escape
+            && self.comment == other.comment
+            && self.doublequote_escapes == other.doublequote_escapes
+            && self.quote_style == other.quote_style
+            && self.num_trailing_empty_fields == other.num_trailing_empty_fields
+            && self.trim == other.trim
+            && self.skip_blank_lines == other.skip_blank_lines
+            && self.encoding == other.encoding
     }
 }
 impl fmt::Debug for Dialect {
@@ -65,6 +606,15 @@ impl fmt::Debug for Dialect {
             .field("header", &self.header)
             .field("quote", &self.quote)
             .field("flexible", &self.flexible)
+            .field("terminator", &self.terminator)
+            .field("escape", &self.escape)
+            .field("comment", &self.comment)
+            .field("doublequote_escapes", &self.doublequote_escapes)
+            .field("quote_style", &self.quote_style)
+            .field("num_trailing_empty_fields", &self.num_trailing_empty_fields)
+            .field("trim", &self.trim)
+            .field("skip_blank_lines", &self.skip_blank_lines)
+            .field("encoding", &self.encoding)
             .finish()
     }
 }
@@ -78,6 +628,11 @@ impl fmt::Display for Dialect {
             "\tNumber of preamble rows: {}",
             self.header.num_preamble_rows
         )?;
+        writeln!(
+            f,
+            "\tNumber of footer rows: {}",
+            self.header.num_footer_rows
+        )?;
         writeln!(
             f,
             "\tQuote character: {}",
@@ -86,30 +641,225 @@ impl fmt::Display for Dialect {
                 Quote::None => "none".into(),
             }
         )?;
-        writeln!(f, "\tFlexible: {}", self.flexible)
+        writeln!(f, "\tFlexible: {}", self.flexible)?;
+        writeln!(f, "\tTerminator: {:?}", self.terminator)?;
+        writeln!(f, "\tEscape: {:?}", self.escape)?;
+        writeln!(f, "\tComment: {:?}", self.comment)?;
+        writeln!(f, "\tDouble quote escapes: {}", self.doublequote_escapes)?;
+        writeln!(f, "\tQuote style: {:?}", self.quote_style)?;
+        writeln!(
+            f,
+            "\tNumber of trailing empty fields: {}",
+            self.num_trailing_empty_fields
+        )?;
+        writeln!(f, "\tTrim: {:?}", self.trim)?;
+        writeln!(f, "\tSkip blank lines: {}", self.skip_blank_lines)?;
+        writeln!(f, "\tEncoding: {:?}", self.encoding)
+    }
+}
+impl FromStr for Dialect {
+    type Err = SnifferError;
+
+    fn from_str(spec: &str) -> Result<Dialect> {
+        Dialect::from_spec(spec)
     }
 }
 impl Dialect {
+    /// The dialect of CSV files written by Excel (like Python's `csv.excel`): comma-delimited, with
+    /// a header row, double quotes (doubled within quoted fields) around the fields that need
+    /// them, and CRLF terminators.
+    pub fn excel() -> Dialect {
+        Dialect::preset(b',', Terminator::CRLF, QuoteStyle::Necessary)
+    }
+
+    /// The dialect of CSV files usually written on Unix (like Python's `csv.unix_dialect`): like
+    /// [`excel`](#method.excel), but with every field quoted and LF terminators.
+    pub fn unix() -> Dialect {
+        Dialect::preset(b',', Terminator::LF, QuoteStyle::Always)
+    }
+
+    /// The dialect of tab-delimited files written by Excel (like Python's `csv.excel_tab`): like
+    /// [`excel`](#method.excel), but tab-delimited.
+    pub fn tsv() -> Dialect {
+        Dialect::preset(b'\t', Terminator::CRLF, QuoteStyle::Necessary)
+    }
+
+    /// The dialect of [RFC 4180](https://www.rfc-editor.org/rfc/rfc4180): an alias of
+    /// [`excel`](#method.excel), which already has a fixed number of fields, double quotes doubled
+    /// within quoted fields, CRLF terminators, and doesn't skip blank lines.
+    pub fn rfc4180_strict() -> Dialect {
+        Dialect::excel()
+    }
+
+    /// Parse a compact textual specification of a dialect: `;`-separated `key=value` pairs, such
+    /// as `delim=;;quote=";header=yes;preamble=2`. Each value starts with the character following
+    /// its `=` (so `delim=;` sets a semicolon delimiter) and ends at the next `;`. Settings that
+    /// aren't specified are taken from [`excel`](#method.excel).
+    ///
+    /// The keys and their values are:
+    /// * `delim`, `quote`, `escape` and `comment`: a single ASCII character, or `tab` (and `none`
+    ///   to disable quoting, escapes or comments),
+    /// * `header`, `doublequote`, `flexible` and `skip_blank`: `yes` or `no` (or `true` or
+    ///   `false`),
+    /// * `preamble`, `footer` and `trailing_empty`: a number of rows (or fields),
+    /// * `terminator`: `crlf`, `lf`, `cr`, or a single character,
+    /// * `quote_style`: `always`, `necessary` or `nonnumeric`,
+    /// * `trim`: `none`, `headers`, `fields` or `all`,
+    /// * `encoding`: `utf-8`, `utf-16le`, `utf-16be` or `windows-1252`.
+    ///
+    /// Unknown keys and invalid values are errors. `Dialect` also implements `FromStr` with this
+    /// syntax, and [`to_spec`](#method.to_spec) formats a dialect in it.
+    pub fn from_spec(spec: &str) -> Result<Dialect> {
+        crate::spec::parse(spec)
+    }
+
+    /// Format this dialect as a specification that [`from_spec`](#method.from_spec) parses back
+    /// into it: its delimiter, quote character, header row and number of preamble rows, followed
+    /// by the settings that differ from [`excel`](#method.excel).
+    pub fn to_spec(&self) -> String {
+        crate::spec::format(self)
+    }
+
+    // A dialect with a header row and double quotes (doubled within quoted fields), without
+    // escapes, comments, trimming or skipping blank lines.
+    fn preset(delimiter: u8, terminator: Terminator, quote_style: QuoteStyle) -> Dialect {
+        Dialect {
+            delimiter,
+            header: Header {
+                has_header_row: true,
+                num_preamble_rows: 0,
+                num_footer_rows: 0,
+            },
+            quote: Quote::Some(b'"'),
+            flexible: false,
+            terminator,
+            escape: Escape::Disabled,
+            comment: Comment::Disabled,
+            doublequote_escapes: true,
+            quote_style,
+            num_trailing_empty_fields: 0,
+            trim: Trim::None,
+            skip_blank_lines: false,
+            encoding: Encoding::Utf8,
+        }
+    }
+
     /// Use this `Dialect` to open a file specified by provided path. Returns a `Reader` (from the
     /// [`csv`](https://docs.rs/csv) crate). Fails on file opening or reading errors.
     pub fn open_path<P: AsRef<Path>>(&self, path: P) -> Result<Reader<File>> {
         self.open_reader(File::open(path)?)
     }
 
+    /// Use this `Dialect` to open a file specified by provided path, decompressing it if it's
+    /// compressed (see [`DecompressReader`](struct.DecompressReader.html), which can also be
+    /// passed to the other `open_reader` methods). Returns a `Reader` (from the
+    /// [`csv`](https://docs.rs/csv) crate). Fails on file opening, decompression or reading errors.
+    pub fn open_path_decompressed<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<Reader<DecompressReader<File>>> {
+        self.open_reader(DecompressReader::open(path)?)
+    }
+
     /// Use this `Dialect` to create a `Reader` (from the [`csv`](https://docs.rs/csv) crate) using
     /// the provided reader. Fails if unable to read from the reader.
     pub fn open_reader<R: Read + Seek>(&self, mut rdr: R) -> Result<Reader<R>> {
-        snip_preamble(&mut rdr, self.header.num_preamble_rows)?;
+        snip_preamble(
+            &mut rdr,
+            self.header.num_preamble_rows,
+            self.terminator.byte(),
+        )?;
         let bldr: ReaderBuilder = self.clone().into();
         Ok(bldr.from_reader(rdr))
     }
+
+    /// Use this `Dialect` to open a file specified by provided path, transcoding its contents from
+    /// the dialect's [`Encoding`](enum.Encoding.html) to UTF-8. Fails on file opening or reading
+    /// errors.
+    #[cfg(feature = "encoding")]
+    pub fn open_path_transcoded<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<Reader<TranscodingReader<File>>> {
+        self.open_reader_transcoded(File::open(path)?)
+    }
+
+    /// Use this `Dialect` to create a `Reader` (from the [`csv`](https://docs.rs/csv) crate) using
+    /// the provided reader, transcoding its contents from the dialect's
+    /// [`Encoding`](enum.Encoding.html) to UTF-8. Fails if unable to read from the reader.
+    #[cfg(feature = "encoding")]
+    pub fn open_reader_transcoded<R: Read>(&self, rdr: R) -> Result<Reader<TranscodingReader<R>>> {
+        let mut decoded = BufReader::new(
+            DecodeReaderBytesBuilder::new()
+                .encoding(Some(self.encoding.into()))
+                .bom_override(true)
+                .build(rdr),
+        );
+        // skip the preamble rows (in the transcoded text, since the terminator might not be a
+        // single byte in the original encoding)
+        let mut line = vec![];
+        for _ in 0..self.header.num_preamble_rows {
+            line.clear();
+            if decoded.read_until(self.terminator.byte(), &mut line)? == 0 {
+                break;
+            }
+        }
+        let bldr: ReaderBuilder = self.clone().into();
+        Ok(bldr.from_reader(decoded))
+    }
+
+    /// Use this `Dialect` to open a file specified by provided path, applying the provided
+    /// [`ReadOptions`](struct.ReadOptions.html). Fails on file opening or reading errors.
+    pub fn open_path_with<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &ReadOptions,
+    ) -> Result<Reader<FilteredReader<File>>> {
+        self.open_reader_with(File::open(path)?, options)
+    }
+
+    /// Use this `Dialect` to create a `Reader` (from the [`csv`](https://docs.rs/csv) crate) using
+    /// the provided reader, applying the provided [`ReadOptions`](struct.ReadOptions.html) (for
+    /// example, to drop trailing empty fields or stop before the footer rows). Blank lines are
+    /// skipped if `skip_blank_lines` is set. Fails if unable to read from the reader.
+    pub fn open_reader_with<R: Read + Seek>(
+        &self,
+        mut rdr: R,
+        options: &ReadOptions,
+    ) -> Result<Reader<FilteredReader<R>>> {
+        snip_preamble(
+            &mut rdr,
+            self.header.num_preamble_rows,
+            self.terminator.byte(),
+        )?;
+        let bldr: ReaderBuilder = self.clone().into();
+        let filtered = FilteredReader::new(rdr, self.terminator.byte(), self.delimiter)
+            .comment(self.comment.clone().into())
+            .skip_blank_lines(self.skip_blank_lines);
+        let filtered = if options.drop_trailing_empty_fields {
+            filtered.drop_trailing_fields(self.num_trailing_empty_fields)
+        } else {
+            filtered
+        };
+        let filtered = if options.skip_footer_rows {
+            filtered.skip_footer_rows(self.header.num_footer_rows)
+        } else {
+            filtered
+        };
+        Ok(bldr.from_reader(filtered))
+    }
 }
 impl From<Dialect> for ReaderBuilder {
     fn from(dialect: Dialect) -> ReaderBuilder {
         let mut bldr = ReaderBuilder::new();
         bldr.delimiter(dialect.delimiter)
             .has_headers(dialect.header.has_header_row)
-            .flexible(dialect.flexible);
+            .flexible(dialect.flexible)
+            .terminator(dialect.terminator.into())
+            .escape(dialect.escape.into())
+            .comment(dialect.comment.into())
+            .double_quote(dialect.doublequote_escapes)
+            .trim(dialect.trim.into());
 
         match dialect.quote {
             Quote::Some(character) => {
@@ -124,6 +874,39 @@ impl From<Dialect> for ReaderBuilder {
         bldr
     }
 }
+/// A `WriterBuilder` writing records in this dialect (its delimiter, quote and escape characters,
+/// quoting style and terminator), e.g. to rewrite a file after transforming its records. Comment
+/// lines, preamble rows and footer rows aren't written.
+impl From<Dialect> for WriterBuilder {
+    fn from(dialect: Dialect) -> WriterBuilder {
+        let mut bldr = WriterBuilder::new();
+        bldr.delimiter(dialect.delimiter)
+            .has_headers(dialect.header.has_header_row)
+            .flexible(dialect.flexible)
+            .terminator(match dialect.terminator {
+                Terminator::CRLF => csv::Terminator::CRLF,
+                Terminator::LF => csv::Terminator::Any(b'\n'),
+                Terminator::CR => csv::Terminator::Any(b'\r'),
+                Terminator::Any(chr) => csv::Terminator::Any(chr),
+            })
+            .double_quote(dialect.doublequote_escapes);
+        if let Escape::Enabled(chr) = dialect.escape {
+            bldr.escape(chr);
+        }
+
+        match dialect.quote {
+            Quote::Some(character) => {
+                bldr.quote(character);
+                bldr.quote_style(dialect.quote_style.into());
+            }
+            Quote::None => {
+                bldr.quote_style(csv::QuoteStyle::Never);
+            }
+        }
+
+        bldr
+    }
+}
 
 /// Metadata about the header of the CSV file.
 #[derive(Debug, Clone, PartialEq)]
@@ -133,6 +916,8 @@ pub struct Header {
     /// Number of rows that occur before either the header row (if `has_header_row` is `true), or
     /// the first data row.
     pub num_preamble_rows: usize,
+    /// Number of rows at the end of the file that aren't part of the data (e.g. summary rows).
+    pub num_footer_rows: usize,
 }
 
 /// Metadata about the quoting style of the CSV file.
@@ -155,6 +940,321 @@ impl fmt::Debug for Quote {
     }
 }
 
+/// Which fields of a CSV file are quoted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuoteStyle {
+    /// All fields are quoted.
+    Always,
+    /// Only fields that contain special characters (delimiters, quotes, or terminators) are
+    /// quoted.
+    Necessary,
+    /// All non-numeric fields are quoted.
+    NonNumeric,
+}
+impl From<QuoteStyle> for csv::QuoteStyle {
+    fn from(quote_style: QuoteStyle) -> csv::QuoteStyle {
+        match quote_style {
+            QuoteStyle::Always => csv::QuoteStyle::Always,
+            QuoteStyle::Necessary => csv::QuoteStyle::Necessary,
+            QuoteStyle::NonNumeric => csv::QuoteStyle::NonNumeric,
+        }
+    }
+}
+
+/// Which fields of a CSV file are padded with whitespace that should be trimmed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trim {
+    /// No fields are trimmed.
+    None,
+    /// Only header fields are trimmed.
+    Headers,
+    /// Only non-header fields are trimmed.
+    Fields,
+    /// All fields are trimmed.
+    All,
+}
+impl From<Trim> for csv::Trim {
+    fn from(trim: Trim) -> csv::Trim {
+        match trim {
+            Trim::None => csv::Trim::None,
+            Trim::Headers => csv::Trim::Headers,
+            Trim::Fields => csv::Trim::Fields,
+            Trim::All => csv::Trim::All,
+        }
+    }
+}
+
+/// Statistics of the (non-null) values of a numeric field: `Type::Unsigned`, `Type::Signed`,
+/// `Type::BigInt`, `Type::Float`, `Type::Decimal` or `Type::Percentage` (with percentages taken
+/// as written, e.g. `12.5` for `12.5%`). Values that don't fit the type of the field (see
+/// [`Metadata::offending_values`](struct.Metadata.html#structfield.offending_values)) aren't
+/// included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericStats {
+    /// Number of values.
+    pub count: usize,
+    /// Smallest value.
+    pub min: f64,
+    /// Largest value.
+    pub max: f64,
+    /// Mean of the values.
+    pub mean: f64,
+}
+
+/// Statistics of the lengths of the (non-null) values of a field, in bytes (of UTF-8) and in
+/// characters, e.g. for sizing `VARCHAR(n)` columns or fixed-width buffers. Values that don't fit
+/// the type of the field are included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LengthStats {
+    /// Number of values.
+    pub count: usize,
+    /// Smallest length in bytes.
+    pub min_bytes: usize,
+    /// Largest length in bytes.
+    pub max_bytes: usize,
+    /// Mean length in bytes.
+    pub mean_bytes: f64,
+    /// Smallest length in characters.
+    pub min_chars: usize,
+    /// Largest length in characters.
+    pub max_chars: usize,
+    /// Mean length in characters.
+    pub mean_chars: f64,
+}
+impl LengthStats {
+    // Adds the length of a value.
+    pub(crate) fn add(stats: Option<LengthStats>, value: &str) -> LengthStats {
+        let (bytes, chars) = (value.len(), value.chars().count());
+        match stats {
+            Some(stats) => {
+                let count = stats.count + 1;
+                LengthStats {
+                    count,
+                    min_bytes: stats.min_bytes.min(bytes),
+                    max_bytes: stats.max_bytes.max(bytes),
+                    mean_bytes: stats.mean_bytes + (bytes as f64 - stats.mean_bytes) / count as f64,
+                    min_chars: stats.min_chars.min(chars),
+                    max_chars: stats.max_chars.max(chars),
+                    mean_chars: stats.mean_chars + (chars as f64 - stats.mean_chars) / count as f64,
+                }
+            }
+            None => LengthStats {
+                count: 1,
+                min_bytes: bytes,
+                max_bytes: bytes,
+                mean_bytes: bytes as f64,
+                min_chars: chars,
+                max_chars: chars,
+                mean_chars: chars as f64,
+            },
+        }
+    }
+}
+
+/// The separators used in the numbers of a CSV file (e.g. `1,234.56`, or `1.234,56` in many
+/// European locales). Numbers with grouping separators (e.g. `1,234,567` or `1 234 567`) are
+/// inferred as numbers all the same.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberFormat {
+    /// Character separating the integer part of a number from its fractional part (`.` or `,`).
+    pub decimal_separator: char,
+    /// Character separating groups of thousands in the integer part of a number (e.g. `,`, `.` or
+    /// a space), if any were found.
+    pub grouping_separator: Option<char>,
+}
+impl Default for NumberFormat {
+    fn default() -> NumberFormat {
+        NumberFormat {
+            decimal_separator: '.',
+            grouping_separator: None,
+        }
+    }
+}
+impl fmt::Display for NumberFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Number format:")?;
+        writeln!(f, "\tDecimal separator: {:?}", self.decimal_separator)?;
+        writeln!(f, "\tGrouping separator: {:?}", self.grouping_separator)
+    }
+}
+
+/// The order of the day and month in dates (e.g. `04/03/2021` is March 4 day-first, and April 3
+/// month-first).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateOrder {
+    /// The day comes before the month (e.g. `%d/%m/%Y`).
+    DayFirst,
+    /// The month comes before the day (e.g. `%m/%d/%Y`).
+    MonthFirst,
+}
+
+/// Results of streaming the whole file with the sniffed dialect (see
+/// [`Sniffer::verify`](../struct.Sniffer.html#method.verify)). Violations are counted against the
+/// dialect and types detected from the sample; the metadata is upgraded to accommodate them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verification {
+    /// Number of records checked (not including the header row).
+    pub num_records: usize,
+    /// Number of records with a different number of fields than detected.
+    pub num_field_count_violations: usize,
+    /// Number of fields whose value doesn't fit the detected type of its column.
+    pub num_type_violations: usize,
+}
+impl Verification {
+    /// Whether or not every record in the file conforms to the sniffed dialect and types.
+    pub fn is_valid(&self) -> bool {
+        self.num_field_count_violations == 0 && self.num_type_violations == 0
+    }
+}
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Verification:")?;
+        writeln!(f, "\tRecords checked: {}", self.num_records)?;
+        writeln!(
+            f,
+            "\tField count violations: {}",
+            self.num_field_count_violations
+        )?;
+        writeln!(f, "\tType violations: {}", self.num_type_violations)
+    }
+}
+
+/// The compression format of a CSV file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    /// The file isn't compressed.
+    None,
+    /// The file is gzip-compressed (decompressing it requires the `gzip` feature).
+    Gzip,
+    /// The file is zstd-compressed (decompressing it requires the `zstd` feature).
+    Zstd,
+    /// The file is bzip2-compressed (decompressing it requires the `bzip2` feature).
+    Bzip2,
+    /// The file is xz-compressed (decompressing it requires the `xz` feature).
+    Xz,
+}
+impl Compression {
+    // The name of the feature enabling decompression of this format.
+    pub(crate) fn feature(&self) -> &'static str {
+        match *self {
+            Compression::None => "",
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+            Compression::Bzip2 => "bzip2",
+            Compression::Xz => "xz",
+        }
+    }
+}
+
+/// The character encoding of a CSV file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    /// UTF-8 (including plain ASCII).
+    Utf8,
+    /// UTF-16, little-endian.
+    Utf16Le,
+    /// UTF-16, big-endian.
+    Utf16Be,
+    /// Windows-1252 (a superset of the printable characters of Latin-1 / ISO-8859-1).
+    Windows1252,
+}
+
+#[cfg(feature = "encoding")]
+impl From<Encoding> for &'static encoding_rs::Encoding {
+    fn from(encoding: Encoding) -> &'static encoding_rs::Encoding {
+        match encoding {
+            Encoding::Utf8 => encoding_rs::UTF_8,
+            Encoding::Utf16Le => encoding_rs::UTF_16LE,
+            Encoding::Utf16Be => encoding_rs::UTF_16BE,
+            Encoding::Windows1252 => encoding_rs::WINDOWS_1252,
+        }
+    }
+}
+
+/// A reader that transcodes its input to UTF-8. Created by
+/// [`Dialect::open_reader_transcoded`](struct.Dialect.html#method.open_reader_transcoded).
+#[cfg(feature = "encoding")]
+pub type TranscodingReader<R> = BufReader<DecodeReaderBytes<R, Vec<u8>>>;
+
+/// A byte order mark found at the start of a CSV file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bom {
+    /// UTF-8 byte order mark (`EF BB BF`).
+    Utf8,
+    /// UTF-16 little-endian byte order mark (`FF FE`).
+    Utf16Le,
+    /// UTF-16 big-endian byte order mark (`FE FF`).
+    Utf16Be,
+}
+impl Bom {
+    /// The character encoding indicated by the byte order mark.
+    pub fn encoding(&self) -> Encoding {
+        match *self {
+            Bom::Utf8 => Encoding::Utf8,
+            Bom::Utf16Le => Encoding::Utf16Le,
+            Bom::Utf16Be => Encoding::Utf16Be,
+        }
+    }
+    /// The bytes of the byte order mark.
+    pub fn bytes(&self) -> &'static [u8] {
+        match *self {
+            Bom::Utf8 => &[0xEF, 0xBB, 0xBF],
+            Bom::Utf16Le => &[0xFF, 0xFE],
+            Bom::Utf16Be => &[0xFE, 0xFF],
+        }
+    }
+}
+
+/// The record terminator of a CSV file.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Terminator {
+    /// Records end with a carriage return followed by a line feed (`\r\n`).
+    CRLF,
+    /// Records end with a line feed (`\n`).
+    LF,
+    /// Records end with a carriage return (`\r`).
+    CR,
+    /// Records end with the provided character.
+    Any(u8),
+}
+impl Terminator {
+    /// The byte that ends a record (the line feed in the case of `CRLF`).
+    pub(crate) fn byte(&self) -> u8 {
+        match *self {
+            Terminator::CRLF | Terminator::LF => b'\n',
+            Terminator::CR => b'\r',
+            Terminator::Any(chr) => chr,
+        }
+    }
+}
+impl From<Terminator> for csv::Terminator {
+    fn from(terminator: Terminator) -> csv::Terminator {
+        match terminator {
+            // the csv crate's CRLF terminator handles \r\n, \n, and \r
+            Terminator::CRLF | Terminator::LF | Terminator::CR => csv::Terminator::CRLF,
+            Terminator::Any(chr) => csv::Terminator::Any(chr),
+        }
+    }
+}
+impl From<Terminator> for csv_core::Terminator {
+    fn from(terminator: Terminator) -> csv_core::Terminator {
+        match terminator {
+            Terminator::CRLF | Terminator::LF | Terminator::CR => csv_core::Terminator::CRLF,
+            Terminator::Any(chr) => csv_core::Terminator::Any(chr),
+        }
+    }
+}
+impl fmt::Debug for Terminator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Terminator::CRLF => write!(f, "CRLF"),
+            Terminator::LF => write!(f, "LF"),
+            Terminator::CR => write!(f, "CR"),
+            Terminator::Any(chr) => write!(f, "Any({:?})", char::from(chr)),
+        }
+    }
+}
+
 /// The escape character (or `Disabled` if escaping is disabled)
 #[derive(Clone, PartialEq)]
 pub enum Escape {
@@ -0,0 +1,16 @@
+/*!
+Conversion of the inferred field types to a [Parquet](https://docs.rs/parquet) schema (see
+[`Metadata::to_parquet_schema`](../metadata/struct.Metadata.html#method.to_parquet_schema)).
+*/
+use parquet::arrow::ArrowSchemaConverter;
+use parquet::schema::types::SchemaDescriptor;
+
+use crate::metadata::Metadata;
+
+/// The Parquet schema of the fields of the provided metadata, converted from their Arrow schema
+/// as by the `parquet::arrow` writers.
+pub(crate) fn schema(metadata: &Metadata) -> SchemaDescriptor {
+    ArrowSchemaConverter::new()
+        .convert(&crate::arrow::schema(metadata))
+        .expect("the data types of sniffed fields are all supported by Parquet")
+}
@@ -0,0 +1,91 @@
+/*!
+Conversion of the sniffed metadata to a [Polars](https://docs.rs/polars) schema and CSV reading
+options (see
+[`Metadata::to_polars_schema`](../metadata/struct.Metadata.html#method.to_polars_schema) and
+[`Metadata::to_polars_read_options`](../metadata/struct.Metadata.html#method.to_polars_read_options)).
+*/
+use std::sync::Arc;
+
+use polars::prelude::{
+    CommentPrefix, CsvParseOptions, CsvReadOptions, DataType, Field, Schema, TimeUnit, TimeZone,
+};
+
+use crate::field_type::Type;
+use crate::metadata::{Comment, Metadata, Quote};
+
+// Maximum precision of Polars decimals.
+const MAX_DECIMAL_PRECISION: usize = 38;
+
+/// The Polars data type of a field of the provided type (and date-time format, if any). Values
+/// that Polars doesn't parse as numbers (e.g. amounts with currency symbols, or percentages) are
+/// kept as strings.
+pub(crate) fn data_type(ty: Type, format: Option<&str>) -> DataType {
+    match ty {
+        Type::Unsigned => DataType::UInt64,
+        Type::Signed => DataType::Int64,
+        Type::Boolean => DataType::Boolean,
+        Type::Float => DataType::Float64,
+        Type::Decimal { precision, scale } if precision <= MAX_DECIMAL_PRECISION => {
+            DataType::Decimal(precision, scale)
+        }
+        Type::Decimal { .. } => DataType::Float64,
+        Type::BigInt => DataType::Int128,
+        Type::DateTime => {
+            // date-times with offsets are converted to UTC
+            let has_offset =
+                format.is_some_and(|format| format.contains('z') || format.ends_with('Z'));
+            DataType::Datetime(TimeUnit::Microseconds, has_offset.then_some(TimeZone::UTC))
+        }
+        Type::Date => DataType::Date,
+        Type::Time => DataType::Time,
+        Type::Null => DataType::Null,
+        Type::Text
+        | Type::Currency { .. }
+        | Type::Percentage
+        | Type::Uuid
+        | Type::IpAddr
+        | Type::Json
+        | Type::NumericString
+        | Type::Hex
+        | Type::Binary { .. }
+        | Type::Categorical { .. } => DataType::String,
+    }
+}
+
+/// The Polars schema of the fields of the provided metadata.
+pub(crate) fn schema(metadata: &Metadata) -> Schema {
+    metadata
+        .types
+        .iter()
+        .enumerate()
+        .map(|(i, &ty)| {
+            let format = metadata.formats.get(i).and_then(|format| format.as_deref());
+            Field::new(metadata.field_name(i).into(), data_type(ty, format))
+        })
+        .collect()
+}
+
+/// The Polars CSV reading options matching the provided metadata (with its schema).
+pub(crate) fn read_options(metadata: &Metadata) -> CsvReadOptions {
+    let dialect = &metadata.dialect;
+    let quote_char = match dialect.quote {
+        Quote::Some(chr) => Some(chr),
+        Quote::None => None,
+    };
+    let comment_prefix = match dialect.comment {
+        Comment::Enabled(chr) => Some(CommentPrefix::new_single(chr)),
+        Comment::Disabled => None,
+    };
+    let parse_options = CsvParseOptions::default()
+        .with_separator(dialect.delimiter)
+        .with_quote_char(quote_char)
+        .with_eol_char(dialect.terminator.byte())
+        .with_comment_prefix(comment_prefix)
+        .with_truncate_ragged_lines(dialect.flexible)
+        .with_decimal_comma(metadata.number_format.decimal_separator == ',');
+    CsvReadOptions::default()
+        .with_has_header(dialect.header.has_header_row)
+        .with_skip_rows(dialect.header.num_preamble_rows)
+        .with_schema(Some(Arc::new(schema(metadata))))
+        .with_parse_options(parse_options)
+}
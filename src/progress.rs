@@ -0,0 +1,168 @@
+/*!
+Progress reporting while sniffing (see [`Sniffer::on_progress`](../struct.Sniffer.html#method.on_progress)),
+and cancellation of in-flight sniffs (see
+[`Sniffer::cancellation_token`](../struct.Sniffer.html#method.cancellation_token)).
+*/
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A stage of the sniffing process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Detecting the character encoding.
+    Encoding,
+    /// Detecting the line terminator.
+    Terminator,
+    /// Detecting the comment character.
+    Comment,
+    /// Detecting the delimiter, quote and escape characters.
+    Delimiter,
+    /// Detecting the preamble rows.
+    Preamble,
+    /// Detecting the footer rows.
+    Footer,
+    /// Detecting the quote style and field trimming.
+    Formatting,
+    /// Detecting the header row.
+    Header,
+    /// Inferring the field types.
+    Types,
+    /// Verifying the results against the whole file (see
+    /// [`Sniffer::verify`](../struct.Sniffer.html#method.verify)).
+    Verification,
+}
+
+/// Progress of sniffing a file, reported whenever a new stage starts and whenever data is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// The current stage.
+    pub stage: Stage,
+    /// Total number of bytes read so far (of the decompressed contents, for compressed files).
+    /// Stages may re-read parts of the file, so this can exceed the size of the file.
+    pub bytes_read: u64,
+}
+
+struct TrackerState {
+    callback: Box<dyn FnMut(Progress) + Send>,
+    progress: Progress,
+}
+impl TrackerState {
+    fn report(&mut self) {
+        (self.callback)(self.progress);
+    }
+}
+
+/// Shared handle to a progress callback (if any).
+#[derive(Clone, Default)]
+pub(crate) struct ProgressTracker(Option<Arc<Mutex<TrackerState>>>);
+impl ProgressTracker {
+    pub(crate) fn new<F: FnMut(Progress) + Send + 'static>(callback: F) -> ProgressTracker {
+        ProgressTracker(Some(Arc::new(Mutex::new(TrackerState {
+            callback: Box::new(callback),
+            progress: Progress {
+                stage: Stage::Encoding,
+                bytes_read: 0,
+            },
+        }))))
+    }
+
+    fn with_state<F: FnOnce(&mut TrackerState)>(&self, f: F) {
+        if let Some(ref state) = self.0 {
+            // a panicking callback doesn't leave the state inconsistent
+            let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+            f(&mut state);
+        }
+    }
+
+    /// Resets the byte count, at the start of sniffing a file.
+    pub(crate) fn start(&self) {
+        self.with_state(|state| state.progress.bytes_read = 0);
+    }
+
+    /// Reports the start of a stage.
+    pub(crate) fn stage(&self, stage: Stage) {
+        self.with_state(|state| {
+            state.progress.stage = stage;
+            state.report();
+        });
+    }
+
+    fn add_bytes(&self, n_bytes: usize) {
+        self.with_state(|state| {
+            state.progress.bytes_read += n_bytes as u64;
+            state.report();
+        });
+    }
+}
+impl fmt::Debug for ProgressTracker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ProgressTracker")
+            .field(&self.0.is_some())
+            .finish()
+    }
+}
+
+/// A handle for cancelling an in-flight sniff from another thread (or from a progress callback).
+///
+/// Clones share the same state: cancelling any clone cancels the sniff. A cancelled sniff fails
+/// with [`SnifferError::Cancelled`](../error/enum.SnifferError.html#variant.Cancelled) the next
+/// time it reads data. Once cancelled, a token stays cancelled, so a new token is needed to sniff
+/// again.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+impl CancellationToken {
+    /// Create a new (not cancelled) token.
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Cancel any sniff using this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether or not this token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A reader that reports the bytes read from an underlying reader to a progress tracker, and fails
+/// once its cancellation token is cancelled.
+pub(crate) struct ProgressReader<R> {
+    inner: R,
+    tracker: ProgressTracker,
+    cancellation: CancellationToken,
+}
+impl<R> ProgressReader<R> {
+    pub(crate) fn new(
+        inner: R,
+        tracker: ProgressTracker,
+        cancellation: CancellationToken,
+    ) -> ProgressReader<R> {
+        ProgressReader {
+            inner,
+            tracker,
+            cancellation,
+        }
+    }
+}
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.cancellation.is_cancelled() {
+            return Err(io::Error::other("sniffing cancelled"));
+        }
+        let n_read = self.inner.read(buf)?;
+        if n_read > 0 {
+            self.tracker.add_bytes(n_read);
+        }
+        Ok(n_read)
+    }
+}
+impl<R: Seek> Seek for ProgressReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
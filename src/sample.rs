@@ -1,6 +1,7 @@
-use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::time::{Duration, Instant};
 
-use crate::error::Result;
+use crate::{error::Result, metadata::Bom};
 
 /// Argument used when calling `sample_size` on `Sniffer`.
 #[derive(Debug, Clone, Copy)]
@@ -11,32 +12,260 @@ pub enum SampleSize {
     Bytes(usize),
     /// Sniff the entire sample.
     All,
+    /// Sniff as many records as possible within a time budget. The sniffer repeatedly sniffs
+    /// samples of increasing numbers of records, stopping (and keeping the latest results) when
+    /// the next sample isn't expected to finish within the budget, or when the sample covers the
+    /// whole file.
+    TimeBudget(Duration),
 }
 
-pub fn take_sample_from_start<R>(reader: &mut R, sample_size: SampleSize) -> Result<SampleIter<R>>
+/// Argument used when calling `sampling` on `Sniffer`: where in the file the sample used for type
+/// inference is taken from.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Sampling {
+    /// Only sample the head of the file (up to the sample size).
+    #[default]
+    Head,
+    /// In addition to the head of the file, sample a number of blocks (each of the provided number
+    /// of bytes) from pseudo-random offsets in the file, realigned to line boundaries. The offsets
+    /// are chosen deterministically (based on the file length), so sniffing the same file yields
+    /// the same results.
+    RandomBlocks {
+        /// Number of blocks to sample.
+        num_blocks: usize,
+        /// Size of each block, in bytes.
+        block_size: usize,
+    },
+    /// In addition to the head of the file, read the rest of the file and sample a fixed number of
+    /// records uniformly from it (using reservoir sampling, so memory use is bounded by the number
+    /// of records). The records are chosen deterministically, so sniffing the same file yields the
+    /// same results.
+    Reservoir {
+        /// Number of records to sample.
+        num_records: usize,
+    },
+}
+
+/// Argument used when calling `example_values` on `Sniffer`: which (non-null) values of each column
+/// are kept as examples.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ExampleValues {
+    /// Don't keep example values.
+    #[default]
+    None,
+    /// Keep the first values read of each column (up to the provided number).
+    First(usize),
+    /// Keep values sampled uniformly from each column (up to the provided number, using reservoir
+    /// sampling). The values are chosen deterministically, so sniffing the same file yields the
+    /// same examples.
+    Random(usize),
+}
+
+/// A simple (non-cryptographic) pseudo-random number generator (splitmix64), used to pick samples.
+pub(crate) struct SplitMix64(u64);
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> SplitMix64 {
+        SplitMix64(seed)
+    }
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+pub fn take_sample_from_start<R>(
+    reader: &mut R,
+    sample_size: SampleSize,
+    terminator: u8,
+    comment: Option<u8>,
+    skip_blank_lines: bool,
+) -> Result<SampleIter<'_, R>>
+where
+    R: Read + Seek,
+{
+    // skip the UTF-8 byte order mark, if any, so it doesn't end up in the first line
+    let bom = detect_bom(&take_raw_sample_from_start(reader, SampleSize::Bytes(3))?);
+    let start = match bom {
+        Some(Bom::Utf8) => Bom::Utf8.bytes().len() as u64,
+        _ => 0,
+    };
+    reader.seek(SeekFrom::Start(start))?;
+    Ok(SampleIter::new(
+        reader,
+        sample_size,
+        terminator,
+        comment,
+        skip_blank_lines,
+    ))
+}
+
+/// Detects the byte order mark (if any) at the start of a sample of raw bytes.
+pub(crate) fn detect_bom(sample: &[u8]) -> Option<Bom> {
+    [Bom::Utf8, Bom::Utf16Le, Bom::Utf16Be]
+        .into_iter()
+        .find(|bom| sample.starts_with(bom.bytes()))
+}
+
+/// Whether or not a line is blank (empty or containing only whitespace).
+pub(crate) fn is_blank(line: &str) -> bool {
+    line.bytes().all(|chr| chr.is_ascii_whitespace())
+}
+
+/// Whether or not the reader contains more than the provided number of lines (ended by the provided
+/// terminator).
+pub(crate) fn has_more_lines<R>(reader: &mut R, n_lines: usize, terminator: u8) -> Result<bool>
+where
+    R: Read + Seek,
+{
+    reader.seek(SeekFrom::Start(0))?;
+    let mut n_found = 0;
+    let mut buffer = vec![0; 1 << 12];
+    loop {
+        let n_read = reader.read(&mut buffer)?;
+        if n_read == 0 {
+            return Ok(false);
+        }
+        if n_found == n_lines {
+            // any byte after the last line starts another one
+            return Ok(true);
+        }
+        for pos in memchr::memchr_iter(terminator, &buffer[..n_read]) {
+            n_found += 1;
+            if n_found == n_lines {
+                if pos + 1 < n_read {
+                    return Ok(true);
+                }
+                break;
+            }
+        }
+    }
+}
+
+/// Reads the raw bytes at the start of the reader, up to the sample size (or a fixed maximum if the
+/// sample size isn't specified in bytes).
+pub fn take_raw_sample_from_start<R>(reader: &mut R, sample_size: SampleSize) -> Result<Vec<u8>>
 where
     R: Read + Seek,
 {
+    const MAX_RAW_SAMPLE_BYTES: usize = 1 << 16;
+    let max_bytes = match sample_size {
+        SampleSize::Bytes(bytes) => bytes,
+        SampleSize::Records(_) | SampleSize::All | SampleSize::TimeBudget(_) => {
+            MAX_RAW_SAMPLE_BYTES
+        }
+    };
     reader.seek(SeekFrom::Start(0))?;
-    Ok(SampleIter::new(reader, sample_size))
+    let mut buffer = vec![];
+    reader.take(max_bytes as u64).read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// A reader that keeps the bytes read from the start of the underlying reader (up to a maximum) in
+/// memory, so the sample can be re-read by every sniffing stage without reading (or seeking) the
+/// underlying reader again. Reads past the cached bytes go to the underlying reader.
+pub(crate) struct CachedReader<R> {
+    inner: R,
+    cache: Vec<u8>,
+    max_cache_bytes: usize,
+    // current position
+    pos: u64,
+    // position of the underlying reader, if known
+    inner_pos: Option<u64>,
+}
+impl<R: Read + Seek> CachedReader<R> {
+    pub(crate) fn new(inner: R, max_cache_bytes: usize) -> CachedReader<R> {
+        CachedReader {
+            inner,
+            cache: vec![],
+            max_cache_bytes,
+            pos: 0,
+            inner_pos: None,
+        }
+    }
+
+    // Reads from the underlying reader at the current position.
+    fn read_inner(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.inner_pos != Some(self.pos) {
+            self.inner.seek(SeekFrom::Start(self.pos))?;
+        }
+        let n_read = self.inner.read(buf)?;
+        self.inner_pos = Some(self.pos + n_read as u64);
+        Ok(n_read)
+    }
+}
+impl<R: Read + Seek> Read for CachedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let cache_len = self.cache.len() as u64;
+        if self.pos < cache_len {
+            let cached = &self.cache[self.pos as usize..];
+            let n_read = cached.len().min(buf.len());
+            buf[..n_read].copy_from_slice(&cached[..n_read]);
+            self.pos += n_read as u64;
+            return Ok(n_read);
+        }
+        let n_read = self.read_inner(buf)?;
+        if self.pos == cache_len && self.cache.len() < self.max_cache_bytes {
+            let n_cached = n_read.min(self.max_cache_bytes - self.cache.len());
+            self.cache.extend_from_slice(&buf[..n_cached]);
+        }
+        self.pos += n_read as u64;
+        Ok(n_read)
+    }
+}
+impl<R: Read + Seek> Seek for CachedReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+            SeekFrom::End(_) => {
+                let new_pos = self.inner.seek(pos)?;
+                self.inner_pos = Some(new_pos);
+                Some(new_pos)
+            }
+        };
+        self.pos = new_pos.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative position",
+            )
+        })?;
+        Ok(self.pos)
+    }
 }
 
 pub struct SampleIter<'a, R: 'a + Read> {
     reader: BufReader<&'a mut R>,
     sample_size: SampleSize,
+    terminator: u8,
+    comment: Option<u8>,
+    skip_blank_lines: bool,
     n_bytes: usize,
     n_records: usize,
+    started: Instant,
     is_done: bool,
 }
 
 impl<'a, R: Read> SampleIter<'a, R> {
-    fn new(reader: &'a mut R, sample_size: SampleSize) -> SampleIter<'a, R> {
+    fn new(
+        reader: &'a mut R,
+        sample_size: SampleSize,
+        terminator: u8,
+        comment: Option<u8>,
+        skip_blank_lines: bool,
+    ) -> SampleIter<'a, R> {
         let buf_reader = BufReader::new(reader);
         SampleIter {
             reader: buf_reader,
             sample_size,
+            terminator,
+            comment,
+            skip_blank_lines,
             n_bytes: 0,
             n_records: 0,
+            started: Instant::now(),
             is_done: false,
         }
     }
@@ -46,47 +275,74 @@ impl<'a, R: Read> Iterator for SampleIter<'a, R> {
     type Item = Result<String>;
 
     fn next(&mut self) -> Option<Result<String>> {
-        if self.is_done {
-            return None;
-        }
+        loop {
+            if self.is_done {
+                return None;
+            }
 
-        let mut output = String::new();
-        let n_bytes_read = match self.reader.read_line(&mut output) {
-            Ok(n_bytes_read) => n_bytes_read,
-            Err(e) => {
-                return Some(Err(e.into()));
+            let mut output = vec![];
+            let n_bytes_read = match self.reader.read_until(self.terminator, &mut output) {
+                Ok(n_bytes_read) => n_bytes_read,
+                Err(e) => {
+                    return Some(Err(e.into()));
+                }
+            };
+            if n_bytes_read == 0 {
+                self.is_done = true;
+                return None;
             }
-        };
-        if n_bytes_read == 0 {
-            self.is_done = true;
-            return None;
-        }
-        let last_byte = (output.as_ref() as &[u8])[output.len() - 1];
-        if last_byte != b'\n' && last_byte != b'\r' {
-            // non CR/LF-ended line
-            // line was cut off before ending, so we ignore it!
-            self.is_done = true;
-            return None;
-        } else {
-            output = output.trim_matches(|c| c == '\n' || c == '\r').into();
-        }
-        self.n_bytes += n_bytes_read;
-        self.n_records += 1;
-        match self.sample_size {
-            SampleSize::Records(max_records) => {
-                if self.n_records > max_records {
+            if output[output.len() - 1] != self.terminator {
+                // non-terminated line
+                // line was cut off before ending, so we ignore it!
+                self.is_done = true;
+                return None;
+            }
+            // strip the terminator (along with the CR of a CRLF terminator)
+            let trimmed_len = output.len()
+                - output
+                    .iter()
+                    .rev()
+                    .take_while(|&&c| c == self.terminator || c == b'\n' || c == b'\r')
+                    .count();
+            output.truncate(trimmed_len);
+            let output = match String::from_utf8(output) {
+                Ok(output) => output,
+                Err(e) => {
                     self.is_done = true;
-                    return None;
+                    return Some(Err(io::Error::new(io::ErrorKind::InvalidData, e).into()));
                 }
+            };
+            self.n_bytes += n_bytes_read;
+            if self.comment.is_some() && output.as_bytes().first() == self.comment.as_ref() {
+                // comment lines aren't part of the sample
+                continue;
             }
-            SampleSize::Bytes(max_bytes) => {
-                if self.n_bytes > max_bytes {
-                    self.is_done = true;
-                    return None;
+            if self.skip_blank_lines && is_blank(&output) {
+                continue;
+            }
+            self.n_records += 1;
+            match self.sample_size {
+                SampleSize::Records(max_records) => {
+                    if self.n_records > max_records {
+                        self.is_done = true;
+                        return None;
+                    }
+                }
+                SampleSize::Bytes(max_bytes) => {
+                    if self.n_bytes > max_bytes {
+                        self.is_done = true;
+                        return None;
+                    }
+                }
+                SampleSize::All => {}
+                SampleSize::TimeBudget(budget) => {
+                    if self.started.elapsed() > budget {
+                        self.is_done = true;
+                        return None;
+                    }
                 }
             }
-            SampleSize::All => {}
+            return Some(Ok(output));
         }
-        Some(Ok(output))
     }
 }
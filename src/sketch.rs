@@ -0,0 +1,50 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+// Number of bits of the hash used to pick a register (4096 registers, for a standard error of
+// about 1.6%).
+const PRECISION: u32 = 12;
+const N_REGISTERS: usize = 1 << PRECISION;
+
+/// HyperLogLog sketch, estimating the number of distinct values added to it in constant memory.
+#[derive(Debug, Clone)]
+pub(crate) struct HyperLogLog {
+    registers: Vec<u8>,
+}
+impl Default for HyperLogLog {
+    fn default() -> HyperLogLog {
+        HyperLogLog {
+            registers: vec![0; N_REGISTERS],
+        }
+    }
+}
+impl HyperLogLog {
+    /// Adds a value.
+    pub(crate) fn add(&mut self, value: &str) {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+        let index = (hash >> (u64::BITS - PRECISION)) as usize;
+        // the position of the first set bit in the rest of the hash (capped by a sentinel bit)
+        let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+    /// The estimated number of distinct values added.
+    pub(crate) fn estimate(&self) -> usize {
+        let m = N_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum = self
+            .registers
+            .iter()
+            .map(|&rank| 2f64.powi(-i32::from(rank)))
+            .sum::<f64>();
+        let estimate = alpha * m * m / sum;
+        let n_zeros = self.registers.iter().filter(|&&rank| rank == 0).count();
+        if estimate <= 2.5 * m && n_zeros > 0 {
+            // linear counting is more accurate for small numbers of values
+            (m * (m / n_zeros as f64).ln()).round() as usize
+        } else {
+            estimate.round() as usize
+        }
+    }
+}
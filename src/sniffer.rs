@@ -1,76 +1,605 @@
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
 use std::path::Path;
+use std::time::Instant;
 
-use csv::{self, Reader, StringRecord};
+use csv::{self, Reader, ReaderBuilder, StringRecord};
 use csv_core as csvc;
 use regex::Regex;
 
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+use crate::stream::AsyncSource;
+#[cfg(feature = "futures-io")]
+use crate::stream::FuturesSource;
+#[cfg(feature = "tokio")]
+use crate::stream::TokioSource;
 use crate::{
-    chain::{Chain, ViterbiResults, STATE_UNSTEADY},
+    chain::{Chain, ViterbiResults, STATE_STEADYFLEX, STATE_STEADYSTRICT, STATE_UNSTEADY},
+    compression::{detect_compression, DecompressReader},
+    datetime::{count_time_colons, format_type},
+    encoding::{decode, detect_encoding, encoded_len, DecodedSample},
     error::{Result, SnifferError},
-    field_type::{get_best_types, infer_record_types, infer_types, Type, TypeGuesses},
-    metadata::{Dialect, Header, Metadata, Quote},
-    sample::{take_sample_from_start, SampleIter, SampleSize},
-    snip::snip_preamble,
+    field_type::{
+        count_url_chars, get_best_types, get_date_order, get_distinct_counts, get_examples,
+        get_formats, get_lengths, get_offending_values, get_plain_values, get_semantic_types,
+        get_statistics, get_type_confidence, infer_record_types, infer_types, is_numeric,
+        parse_number, Column, Levels, NumberDetails, Type, TypeCounts, TypeGuesses, TypeOptions,
+    },
+    filter::FilteredReader,
+    fixed_width::fixed_width_columns,
+    keys::{hash_record, primary_keys, RecordHashes, MAX_KEY_RECORDS},
+    metadata::{
+        Comment, Compression, Confidence, DateOrder, Dialect, Encoding, Escape, Header, Metadata,
+        NumberFormat, Quote, QuoteStyle, Table, Terminator, Trim, Validation, Verification,
+    },
+    progress::{CancellationToken, Progress, ProgressReader, ProgressTracker, Stage},
+    sample::{
+        detect_bom, has_more_lines, is_blank, take_raw_sample_from_start, take_sample_from_start,
+        CachedReader, ExampleValues, SampleIter, SampleSize, Sampling, SplitMix64,
+    },
+    snip::{preamble_skipcount, snip_preamble},
+    stream::{truncate_partial_line, SampleBuffer, StreamReader, StreamSample},
+    validate::validate,
 };
 
+// Delimiter candidates paired with their scores, ranked from most to least likely.
+type DelimRanking = Vec<(u8, f64)>;
+
+// Markers that may start comment lines. Only the first character of the marker is used as the
+// comment character.
+const COMMENT_MARKERS: &[&[u8]] = &[b"#", b"//", b";", b"%"];
+
+// Delimiters that are preferred over other characters when guessing the delimiter.
+const PREFERRED_DELIMITERS: &[u8] = b",\t;|";
+// Prior weight of delimiters not in PREFERRED_DELIMITERS (preferred delimiters have weight 1.0).
+const DEFAULT_DELIMITER_PRIOR: f64 = 0.5;
+// Maximum number of delimiter candidates counted with one `memchr` scan each (rather than with a
+// byte histogram).
+const MEMCHR_MAX_CANDIDATES: usize = 8;
+
+// Maximum number of footer rows to look for at the end of the file.
+const MAX_FOOTER_ROWS: usize = 5;
+// Minimum number of data rows (above any potential footer rows) needed to look for footer rows.
+const MIN_FOOTER_REFERENCE_ROWS: usize = 3;
+// Number of bytes at the end of the file examined while looking for footer rows.
+const FOOTER_SAMPLE_BYTES: u64 = 1 << 14;
+
+// Number of bytes requested beyond the sample size when sniffing a remote file, so the sample can
+// end at the end of a line.
+#[cfg(feature = "http")]
+const URL_RANGE_SLACK_BYTES: usize = 1 << 12;
+
+// Maximum number of bytes from the start of the file kept in memory while sniffing, when the sample
+// size isn't specified in bytes.
+const MAX_CACHED_SAMPLE_BYTES: usize = 1 << 24;
+// Number of bytes kept in memory beyond a sample size specified in bytes (as stages may read
+// slightly past the sample to complete its last line).
+const CACHED_SAMPLE_SLACK_BYTES: usize = 1 << 16;
+
+// Number of bytes at the head of the data whose records are counted to estimate the number of
+// records in the whole file.
+const RECORD_ESTIMATE_BYTES: u64 = 1 << 16;
+
+// Number of records in the first sample sniffed when sniffing within a time budget.
+const MIN_TIME_BUDGET_RECORDS: usize = 64;
+
+// Size of the chunks read from asynchronous readers.
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+const ASYNC_CHUNK_BYTES: usize = 1 << 13;
+
+/// Whether or not a CSV file has a header row, as specified with
+/// [`Sniffer::header`](struct.Sniffer.html#method.header).
+#[derive(Debug, Clone, PartialEq)]
+pub enum HeaderHint {
+    /// The file has a header row (the preamble and footer rows are still detected).
+    HasHeader,
+    /// The file doesn't have a header row (the preamble and footer rows are still detected).
+    NoHeader,
+    /// Whether or not the file has a header row is detected (the default).
+    Detect,
+    /// The whole [`Header`](metadata/struct.Header.html) is known, including the numbers of
+    /// preamble and footer rows.
+    Known(Header),
+}
+impl From<Header> for HeaderHint {
+    fn from(header: Header) -> HeaderHint {
+        HeaderHint::Known(header)
+    }
+}
+
 /// A CSV sniffer.
 ///
 /// The sniffer examines a CSV file, passed in either through a file or a reader.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Sniffer {
     // CSV file dialect guesses
     delimiter: Option<u8>,
     num_preamble_rows: Option<usize>,
+    num_footer_rows: Option<usize>,
     has_header_row: Option<bool>,
     quote: Option<Quote>,
     flexible: Option<bool>,
+    terminator: Option<Terminator>,
+    escape: Option<Escape>,
+    comment: Option<Comment>,
+    doublequote_escapes: Option<bool>,
+    quote_style: Option<QuoteStyle>,
+    trim: Option<Trim>,
+    number_format: Option<NumberFormat>,
+    skip_blank_lines: Option<bool>,
 
     // Metadata guesses
     delimiter_freq: Option<usize>,
-    types: Vec<Type>,
+    top_row: Vec<String>,
+    num_trailing_empty_fields: usize,
+    num_blank_lines: usize,
+    types: Vec<TypeGuesses>,
+    number_details: Vec<NumberDetails>,
+    nullable: Vec<bool>,
+    levels: Vec<Levels>,
+    type_counts: Vec<TypeCounts>,
+    // number of records (including the top row) in the whole file, if the sample covered it
+    num_records: Option<usize>,
+    // lengths of the top row and of the records below it at the head of the sample
+    top_row_len: usize,
+    record_lens: RecordLengths,
+    // whether the values of each column below the top row are increasing at the head of the sample
+    sequences: Vec<Sequence>,
+    // hashes of the values of the records below the top row at the head of the sample (if primary
+    // keys are detected)
+    key_hashes: Vec<RecordHashes>,
+
+    // Confidence in the dialect guesses
+    delimiter_confidence: Option<f64>,
+    header_confidence: Option<f64>,
+    quote_confidence: Option<f64>,
+    // Ranked delimiter candidates (and their scores) considered while guessing the delimiter
+    delimiter_ranking: DelimRanking,
+
+    // character encoding of the file
+    encoding: Option<Encoding>,
 
     // sample size to sniff
     sample_size: Option<SampleSize>,
+    // where the type inference sample is taken from
+    sampling: Sampling,
+    // characters allowed to be guessed as the delimiter
+    delimiter_candidates: Option<Vec<u8>>,
+    // prior weights of delimiter candidates (overriding the defaults)
+    delimiter_priors: HashMap<u8, f64>,
+    // whether to check for fixed-width files
+    detect_fixed_width: bool,
+    // whether to detect primary keys
+    detect_primary_keys: bool,
+    // whether a byte sample size is a hard limit on the bytes read
+    strict_sample_size: bool,
+    // options for inferring field types
+    type_options: TypeOptions,
+    // types of known columns (which aren't inferred)
+    type_hints: Vec<(Column, Type)>,
+    // whether to verify the sniffed metadata against the whole file
+    verify: bool,
+    // progress callback (if any)
+    progress: ProgressTracker,
+    // token for cancelling in-flight sniffs
+    cancellation: CancellationToken,
 }
 impl Sniffer {
     /// Create a new CSV sniffer.
     pub fn new() -> Sniffer {
         Sniffer::default()
     }
-    /// Specify the delimiter character.
+    /// Specify the delimiter character. The delimiter isn't detected (and has a confidence of 1.0),
+    /// but the rest of the dialect (the header row, preamble, quoting, etc.) and the types are
+    /// still detected.
     pub fn delimiter(&mut self, delimiter: u8) -> &mut Sniffer {
         self.delimiter = Some(delimiter);
+        self.delimiter_confidence = Some(1.0);
+        self
+    }
+    /// Restrict delimiter detection to the provided candidate characters (for example,
+    /// `b",;\t|"`). By default, any ASCII character can be guessed as the delimiter. Has no effect
+    /// if the delimiter is specified using [`delimiter`](#method.delimiter).
+    pub fn delimiter_candidates(&mut self, candidates: &[u8]) -> &mut Sniffer {
+        self.delimiter_candidates = Some(candidates.to_vec());
         self
     }
-    /// Specify the header type (whether the CSV file has a header row, and where the data starts).
-    pub fn header(&mut self, header: Header) -> &mut Sniffer {
-        self.num_preamble_rows = Some(header.num_preamble_rows);
-        self.has_header_row = Some(header.has_header_row);
+    /// Specify the prior weight of a delimiter candidate. When guessing the delimiter, the
+    /// likelihood of each candidate is multiplied by its weight, so candidates with higher weights
+    /// are preferred when the evidence for several candidates is close.
+    ///
+    /// By default, `,`, `\t`, `;`, and `|` have a weight of 1.0, and all other characters have a
+    /// weight of 0.5.
+    pub fn delimiter_prior(&mut self, delimiter: u8, weight: f64) -> &mut Sniffer {
+        self.delimiter_priors.insert(delimiter, weight);
+        self
+    }
+    fn get_delimiter_prior(&self, delimiter: u8) -> f64 {
+        match self.delimiter_priors.get(&delimiter) {
+            Some(&weight) => weight,
+            None if PREFERRED_DELIMITERS.contains(&delimiter) => 1.0,
+            None => DEFAULT_DELIMITER_PRIOR,
+        }
+    }
+    /// Specify whether or not the CSV file has a header row, with a
+    /// [`HeaderHint`](enum.HeaderHint.html), or the whole header type (whether the CSV file has a
+    /// header row, and where the data starts) with a [`Header`](metadata/struct.Header.html).
+    pub fn header<H: Into<HeaderHint>>(&mut self, header: H) -> &mut Sniffer {
+        match header.into() {
+            HeaderHint::HasHeader => {
+                self.has_header_row = Some(true);
+                self.header_confidence = Some(1.0);
+            }
+            HeaderHint::NoHeader => {
+                self.has_header_row = Some(false);
+                self.header_confidence = Some(1.0);
+            }
+            HeaderHint::Detect => {
+                self.has_header_row = None;
+                self.header_confidence = None;
+            }
+            HeaderHint::Known(header) => {
+                self.num_preamble_rows = Some(header.num_preamble_rows);
+                self.num_footer_rows = Some(header.num_footer_rows);
+                self.has_header_row = Some(header.has_header_row);
+                self.header_confidence = Some(1.0);
+            }
+        }
         self
     }
     /// Specify the quote character (if any), and whether two quotes in a row as to be interepreted
     /// as an escaped quote.
     pub fn quote(&mut self, quote: Quote) -> &mut Sniffer {
         self.quote = Some(quote);
+        self.quote_confidence = Some(1.0);
+        self
+    }
+
+    /// Specify the escape character (or that escaping is disabled).
+    pub fn escape(&mut self, escape: Escape) -> &mut Sniffer {
+        self.escape = Some(escape);
+        self
+    }
+    fn get_escape(&self) -> Option<u8> {
+        self.escape.clone().and_then(|escape| escape.into())
+    }
+
+    /// Specify whether two quote characters in a row within a quoted field are interpreted as an
+    /// escaped quote.
+    pub fn doublequote_escapes(&mut self, doublequote_escapes: bool) -> &mut Sniffer {
+        self.doublequote_escapes = Some(doublequote_escapes);
+        self
+    }
+    fn get_doublequote_escapes(&self) -> bool {
+        self.doublequote_escapes.unwrap_or(true)
+    }
+
+    /// Specify the quoting style (which fields are quoted).
+    pub fn quote_style(&mut self, quote_style: QuoteStyle) -> &mut Sniffer {
+        self.quote_style = Some(quote_style);
+        self
+    }
+
+    /// Specify which fields are padded with whitespace that should be trimmed.
+    pub fn trim(&mut self, trim: Trim) -> &mut Sniffer {
+        self.trim = Some(trim);
+        self
+    }
+    /// Specify the separators used in numbers (see
+    /// [`NumberFormat`](metadata/struct.NumberFormat.html)).
+    pub fn number_format(&mut self, number_format: NumberFormat) -> &mut Sniffer {
+        self.number_format = Some(number_format);
+        self
+    }
+    fn get_number_format(&self) -> NumberFormat {
+        self.number_format.unwrap_or_default()
+    }
+
+    /// The order of the day and month in dates that can be read either way (e.g. `04/03/2021`,
+    /// when no date in the file has a day after the 12th). Dates that can only be read one way
+    /// decide the order of all dates in the file.
+    ///
+    /// Defaults to `DateOrder::MonthFirst`.
+    pub fn date_preference(&mut self, date_preference: DateOrder) -> &mut Sniffer {
+        self.type_options.date_preference = date_preference;
+        self
+    }
+
+    /// Specify additional `strftime`-style formats of date-times, dates and times (e.g.
+    /// `%d.%m.%Y %H:%M`), less preferred than the built-in formats. Formats with both date (`%Y`,
+    /// `%m`, `%d`, `%b` or `%B`) and time (`%H` or `%I`) specifiers are formats of
+    /// [`Type::DateTime`](enum.Type.html#variant.DateTime), the others of `Type::Date` or
+    /// `Type::Time`; formats with neither are ignored, as are formats beyond the first 8 of each
+    /// type. The `%M` (minute), `%S` (second), `%.f` (fractional seconds), `%p` (`AM` or `PM`),
+    /// `%z` and `%:z` (UTC offset) specifiers are supported as well.
+    pub fn custom_formats(&mut self, formats: &[&str]) -> &mut Sniffer {
+        self.type_options.custom_formats = formats
+            .iter()
+            .filter_map(|&format| Some((format_type(format)?, format.to_string())))
+            .collect();
+        self
+    }
+
+    /// Whether or not to treat digits with leading zeros (e.g. ZIP codes like `01234`) as strings
+    /// rather than numbers: columns with such values are detected as
+    /// [`Type::NumericString`](enum.Type.html#variant.NumericString) instead of
+    /// `Type::Unsigned`, so that converting them doesn't lose the zeros. A single `0` is still a
+    /// number.
+    ///
+    /// Defaults to `false`.
+    pub fn preserve_leading_zeros(&mut self, preserve_leading_zeros: bool) -> &mut Sniffer {
+        self.type_options.leading_zeros = preserve_leading_zeros;
+        self
+    }
+
+    /// Specify the share of the (non-null) values of a column that need to fit a type for the
+    /// column to be of that type, between 0.0 and 1.0: e.g. with `type_tolerance(0.99)`, a column
+    /// of integers with a few stray values (up to 1% of them) is still `Type::Unsigned` rather than
+    /// `Type::Text`. The stray values are reported in
+    /// [`Metadata::offending_values`](metadata/struct.Metadata.html#structfield.offending_values)
+    /// (and counted as violations when verifying).
+    ///
+    /// Defaults to `1.0` (every value needs to fit).
+    pub fn type_tolerance(&mut self, tolerance: f64) -> &mut Sniffer {
+        self.type_options.tolerance = tolerance;
+        self
+    }
+
+    /// Whether or not to accumulate the minimum, maximum and mean of the values of numeric columns,
+    /// of the lengths of the values of every column, and the (estimated) number of distinct values
+    /// of every column while inferring types, reported in
+    /// [`Metadata::statistics`](metadata/struct.Metadata.html#structfield.statistics),
+    /// [`Metadata::lengths`](metadata/struct.Metadata.html#structfield.lengths) and
+    /// [`Metadata::distinct_counts`](metadata/struct.Metadata.html#structfield.distinct_counts)
+    /// (for the whole file, if verified).
+    ///
+    /// Defaults to `false`.
+    pub fn compute_statistics(&mut self, compute_statistics: bool) -> &mut Sniffer {
+        self.type_options.statistics = compute_statistics;
+        self
+    }
+
+    /// Whether or not to detect the columns (or pairs of columns) that are plausible primary keys,
+    /// reported in
+    /// [`Metadata::primary_keys`](metadata/struct.Metadata.html#structfield.primary_keys). The
+    /// (hashed) values of up to 16384 records at the head of the sample are kept to check them.
+    ///
+    /// Defaults to `false`.
+    pub fn detect_primary_keys(&mut self, detect_primary_keys: bool) -> &mut Sniffer {
+        self.detect_primary_keys = detect_primary_keys;
+        self
+    }
+
+    /// Which (non-null) values of each column to keep as examples, reported in
+    /// [`Metadata::examples`](metadata/struct.Metadata.html#structfield.examples) (see
+    /// [`ExampleValues`](enum.ExampleValues.html)).
+    ///
+    /// Defaults to `ExampleValues::None`.
+    pub fn example_values(&mut self, example_values: ExampleValues) -> &mut Sniffer {
+        self.type_options.examples = example_values;
+        self
+    }
+
+    /// Whether or not to detect text columns with at most the provided number of distinct values
+    /// (each appearing at least twice on average) as
+    /// [`Type::Categorical`](enum.Type.html#variant.Categorical), listing their values in
+    /// [`Metadata::levels`](metadata/struct.Metadata.html#structfield.levels). The distinct
+    /// values of each column are only tracked up to the provided number.
+    ///
+    /// Defaults to `0` (categorical columns aren't detected).
+    pub fn detect_categorical(&mut self, max_levels: usize) -> &mut Sniffer {
+        self.type_options.max_levels = max_levels;
+        self
+    }
+
+    /// Whether or not to detect the [`SemanticType`](enum.SemanticType.html)s of text columns (e.g.
+    /// columns of email addresses or URLs), reported in
+    /// [`Metadata::semantic_types`](metadata/struct.Metadata.html#structfield.semantic_types).
+    ///
+    /// Defaults to `false`.
+    pub fn detect_semantic_types(&mut self, detect_semantic_types: bool) -> &mut Sniffer {
+        self.type_options.semantic_types = detect_semantic_types;
+        self
+    }
+
+    /// Specify the type of a column, identified by its index or by its name (its label in the
+    /// header row): e.g. `type_hint(0, Type::Unsigned)` or `type_hint("zip", Type::Text)`. The
+    /// column is reported with the provided type rather than an inferred one (and with the format
+    /// of its values, for types with formats, if they all match one). Can be called several times;
+    /// later hints take precedence over earlier ones for the same column.
+    pub fn type_hint<C: Into<Column>>(&mut self, column: C, ty: Type) -> &mut Sniffer {
+        self.type_hints.push((column.into(), ty));
+        self
+    }
+    /// Specify the types of several columns (e.g. the leading columns fixed by an ingest
+    /// contract), each identified by its index or by its name, as with
+    /// [`type_hint`](#method.type_hint): e.g. `type_hints([("id", Type::Signed), ("zip",
+    /// Type::Text)])`. The types of the other columns are still inferred.
+    pub fn type_hints<I, C>(&mut self, hints: I) -> &mut Sniffer
+    where
+        I: IntoIterator<Item = (C, Type)>,
+        C: Into<Column>,
+    {
+        self.type_hints
+            .extend(hints.into_iter().map(|(column, ty)| (column.into(), ty)));
+        self
+    }
+
+    fn get_trim(&self) -> Trim {
+        self.trim.unwrap_or(Trim::None)
+    }
+
+    /// Specify whether or not blank lines (empty or containing only whitespace) are skipped. If not
+    /// specified, blank lines are skipped while sniffing, and the dialect skips them if any are
+    /// found among the data rows.
+    pub fn skip_blank_lines(&mut self, skip_blank_lines: bool) -> &mut Sniffer {
+        self.skip_blank_lines = Some(skip_blank_lines);
+        self
+    }
+    fn get_skip_blank_lines(&self) -> bool {
+        self.skip_blank_lines.unwrap_or(true)
+    }
+
+    /// Specify the comment character (or that comments are disabled).
+    pub fn comment(&mut self, comment: Comment) -> &mut Sniffer {
+        self.comment = Some(comment);
+        self
+    }
+    fn get_comment(&self) -> Option<u8> {
+        self.comment.clone().and_then(|comment| comment.into())
+    }
+
+    /// Specify the character encoding of the file. If not specified, the encoding is detected from
+    /// the start of the file.
+    pub fn encoding(&mut self, encoding: Encoding) -> &mut Sniffer {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// Specify the record terminator.
+    pub fn terminator(&mut self, terminator: Terminator) -> &mut Sniffer {
+        self.terminator = Some(terminator);
         self
     }
 
     /// The size of the sample to examine while sniffing. If using `SampleSize::Records`, the
-    /// sniffer will use the `Terminator::CRLF` as record separator.
+    /// sniffer will use the detected (or specified) terminator as record separator.
     ///
-    /// The sample size defaults to `SampleSize::Bytes(4096)`.
+    /// The sample size defaults to `SampleSize::Bytes(16384)`.
     pub fn sample_size(&mut self, sample_size: SampleSize) -> &mut Sniffer {
         self.sample_size = Some(sample_size);
         self
     }
 
+    /// Where in the file to take the sample used for type inference from (see
+    /// [`Sampling`](enum.Sampling.html)). Other stages always use the head of the file.
+    ///
+    /// Defaults to `Sampling::Head`.
+    pub fn sampling(&mut self, sampling: Sampling) -> &mut Sniffer {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Specify the tokens of boolean values (matched case-insensitively), replacing the default
+    /// tokens (`true` / `false`, `yes` / `no`, `y` / `n` and `t` / `f`). For example,
+    /// `boolean_tokens(&["ja"], &["nein"])`.
+    pub fn boolean_tokens(&mut self, true_tokens: &[&str], false_tokens: &[&str]) -> &mut Sniffer {
+        self.type_options.true_tokens = true_tokens.iter().map(|&token| token.into()).collect();
+        self.type_options.false_tokens = false_tokens.iter().map(|&token| token.into()).collect();
+        self
+    }
+
+    /// Specify the tokens of null values (matched exactly), replacing the default tokens (the empty
+    /// string, `NULL`, `NA` and `\\N`). Null values don't count against a column's type; columns
+    /// containing them are reported as nullable (see
+    /// [`Metadata::nullable`](metadata/struct.Metadata.html#structfield.nullable)).
+    pub fn null_tokens(&mut self, null_tokens: &[&str]) -> &mut Sniffer {
+        self.type_options.null_tokens = null_tokens.iter().map(|&token| token.into()).collect();
+        self
+    }
+
+    /// Whether or not a sample size of `SampleSize::Bytes(n)` is a hard limit. If enabled, the
+    /// sniffer reads at most `n` bytes of the file (of the decompressed contents, for compressed
+    /// files) across all of its stages, including footer detection and type inference, and
+    /// ignores any partial line at the end of the sample. Otherwise, the sample is extended to the
+    /// end of its last line, and some stages (e.g. footer detection) read beyond the sample.
+    ///
+    /// Defaults to `false`.
+    pub fn strict_sample_size(&mut self, strict_sample_size: bool) -> &mut Sniffer {
+        self.strict_sample_size = strict_sample_size;
+        self
+    }
+    // The hard limit on the number of bytes read, if any.
+    fn get_byte_limit(&self) -> Option<usize> {
+        match self.get_sample_size() {
+            SampleSize::Bytes(n_bytes) if self.strict_sample_size => Some(n_bytes),
+            _ => None,
+        }
+    }
+
+    /// Whether or not to verify the sniffed metadata against the whole file. If enabled, after
+    /// sniffing the sample, the rest of the file is streamed with the sniffed dialect and each
+    /// record is checked for the detected number of fields and types. The results are reported in
+    /// [`Metadata::verification`](metadata/struct.Metadata.html#structfield.verification), and
+    /// the metadata is upgraded as needed: the dialect is made flexible if any record has a
+    /// different number of fields, and column types are widened to fit every value.
+    ///
+    /// Defaults to `false`.
+    pub fn verify(&mut self, verify: bool) -> &mut Sniffer {
+        self.verify = verify;
+        self
+    }
+
+    /// Register a callback to report the [`Progress`](progress/struct.Progress.html) of sniffing:
+    /// called with the current stage and the number of bytes read so far, whenever a stage starts
+    /// and whenever data is read. Replaces any previously registered callback.
+    pub fn on_progress<F>(&mut self, callback: F) -> &mut Sniffer
+    where
+        F: FnMut(Progress) + Send + 'static,
+    {
+        self.progress = ProgressTracker::new(callback);
+        self
+    }
+
+    /// Use a [`CancellationToken`](progress/struct.CancellationToken.html) to cancel in-flight
+    /// sniffs: once the token (or any clone of it) is cancelled, sniffing fails with
+    /// [`SnifferError::Cancelled`](error/enum.SnifferError.html#variant.Cancelled) the next time
+    /// it reads data.
+    pub fn cancellation_token(&mut self, token: CancellationToken) -> &mut Sniffer {
+        self.cancellation = token;
+        self
+    }
+
+    /// Whether or not to check if the file is a fixed-width (whitespace-aligned) table instead of a
+    /// delimited one. If enabled and the file appears to be fixed-width, sniffing fails with a
+    /// [`SnifferError::FixedWidth`](error/enum.SnifferError.html#variant.FixedWidth) error
+    /// containing the detected column boundaries. Ignored if the delimiter is specified using
+    /// [`delimiter`](#method.delimiter).
+    ///
+    /// Defaults to `false`.
+    pub fn detect_fixed_width(&mut self, detect_fixed_width: bool) -> &mut Sniffer {
+        self.detect_fixed_width = detect_fixed_width;
+        self
+    }
+
+    /// Whether or not to detect columns of plain decimal numbers (e.g. `1234.50`, without an
+    /// exponent) as [`Type::Decimal`](enum.Type.html#variant.Decimal), along with their precision
+    /// and scale, rather than as `Type::Float`.
+    ///
+    /// Defaults to `false`.
+    pub fn detect_decimals(&mut self, detect_decimals: bool) -> &mut Sniffer {
+        self.type_options.decimals = detect_decimals;
+        self
+    }
+
     fn get_sample_size(&self) -> SampleSize {
         self.sample_size.unwrap_or(SampleSize::Bytes(1 << 14))
     }
 
+    fn get_terminator(&self) -> Terminator {
+        self.terminator.unwrap_or(Terminator::CRLF)
+    }
+
+    // Takes a sample of lines from the start of the reader.
+    fn take_sample<'a, R: Read + Seek>(&self, reader: &'a mut R) -> Result<SampleIter<'a, R>> {
+        take_sample_from_start(
+            reader,
+            self.get_sample_size(),
+            self.get_terminator().byte(),
+            self.get_comment(),
+            self.get_skip_blank_lines(),
+        )
+    }
+
+    // Whether or not a raw line (as returned by sampling with no comment or blank line filtering)
+    // is skipped by the filtered sample.
+    fn is_skipped_line(&self, line: &str) -> bool {
+        let comment = self.get_comment();
+        (comment.is_some() && line.as_bytes().first() == comment.as_ref())
+            || (self.get_skip_blank_lines() && is_blank(line))
+    }
+
     /// Sniff the CSV file located at the provided path, and return a `Reader` (from the
     /// [`csv`](https://docs.rs/csv) crate) ready to ready the file.
     ///
@@ -78,6 +607,16 @@ impl Sniffer {
     pub fn open_path<P: AsRef<Path>>(&mut self, path: P) -> Result<Reader<File>> {
         self.open_reader(File::open(path)?)
     }
+    /// Sniff the (possibly compressed) CSV file located at the provided path, and return a `Reader`
+    /// (from the [`csv`](https://docs.rs/csv) crate) ready to read the decompressed file.
+    ///
+    /// Fails on file opening, decompression or reading errors, or on an error examining the file.
+    pub fn open_path_decompressed<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<Reader<DecompressReader<File>>> {
+        self.open_reader(DecompressReader::open(path)?)
+    }
     /// Sniff the CSV file provided by the reader, and return a [`csv`](https://docs.rs/csv)
     /// `Reader` object.
     ///
@@ -88,6 +627,17 @@ impl Sniffer {
         metadata.dialect.open_reader(reader)
     }
 
+    /// Sniff the CSV stream provided by the reader (which doesn't need to support seeking, e.g. a
+    /// pipe or a network stream), and return a [`csv`](https://docs.rs/csv) `Reader` object over the
+    /// whole stream (including the buffered sample).
+    ///
+    /// Fails on reading errors, or on an error examining the stream.
+    pub fn open_stream<R: Read>(&mut self, reader: R) -> Result<Reader<StreamReader<R>>> {
+        let (metadata, sample) = self.sniff_stream_sample(reader, self.get_deadline())?;
+        let bldr: ReaderBuilder = metadata.dialect.into();
+        Ok(bldr.from_reader(sample.into_reader(metadata.data_offset)))
+    }
+
     /// Sniff the CSV file located at the provided path, and return a
     /// [`Metadata`](struct.Metadata.html) object containing information about the CSV file.
     ///
@@ -96,23 +646,545 @@ impl Sniffer {
         let file = File::open(path)?;
         self.sniff_reader(&file)
     }
+    /// Sniff each of the CSV files located at the provided paths (e.g. the parts of a dataset
+    /// split into several files), and return [`Metadata`](struct.Metadata.html) fitting all of
+    /// them, merged with [`Metadata::merge`](metadata/struct.Metadata.html#method.merge) in
+    /// order. Each file is sniffed separately, from this sniffer's configuration.
+    ///
+    /// Fails if no paths are provided, or on an error opening or examining any of the files.
+    pub fn sniff_paths<I, P>(&mut self, paths: I) -> Result<Metadata>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let base = self.clone();
+        let mut merged: Option<Metadata> = None;
+        for path in paths {
+            let metadata = base.clone().sniff_path(path)?;
+            merged = Some(match merged {
+                Some(merged) => merged.merge(&metadata),
+                None => metadata,
+            });
+        }
+        merged.ok_or_else(|| SnifferError::SniffingFailed("no files to sniff".into()))
+    }
+    /// Validate the CSV file located at the provided path against the metadata expected of it (see
+    /// [`validate_reader`](#method.validate_reader)). Compressed files are decompressed
+    /// transparently.
+    ///
+    /// Fails on file opening or reading errors.
+    pub fn validate_path<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        expected: &Metadata,
+    ) -> Result<Validation> {
+        self.validate_reader(DecompressReader::open(path)?, expected)
+    }
+    /// Validate the CSV file provided by the reader against the metadata expected of it (e.g.
+    /// sniffed from an earlier delivery of a recurring feed), and return a
+    /// [`Validation`](metadata/struct.Validation.html) report of its violations: a different
+    /// number of preamble rows (sniffed with the expected delimiter and quote character), a
+    /// different header row, records with a different number of fields, and values that don't
+    /// fit the types (and formats) of their fields, or that are null in fields verified not to
+    /// have null values. The whole file is read with the expected dialect, and values are
+    /// checked with this sniffer's null and boolean tokens and the expected number format.
+    ///
+    /// Fails on reading errors.
+    pub fn validate_reader<R: Read + Seek>(
+        &mut self,
+        mut reader: R,
+        expected: &Metadata,
+    ) -> Result<Validation> {
+        let mut sniffer = self.clone();
+        sniffer
+            .delimiter(expected.dialect.delimiter)
+            .quote(expected.dialect.quote.clone())
+            .verify(false);
+        let num_preamble_rows = match sniffer.sniff_reader(&mut reader) {
+            Ok(metadata) => Some(metadata.dialect.header.num_preamble_rows),
+            // the records are checked all the same
+            Err(SnifferError::SniffingFailed(_)) => None,
+            Err(err) => return Err(err),
+        };
+        reader.seek(SeekFrom::Start(0))?;
+        validate(reader, expected, &self.type_options, num_preamble_rows)
+    }
+    /// Sniff the CSV file located at the provided path by memory-mapping it (instead of reading it
+    /// through a buffer), and return a [`Metadata`](struct.Metadata.html) object containing
+    /// information about the CSV file. Useful for sniffing large local files, particularly with
+    /// `SampleSize::All`.
+    ///
+    /// The file must not be modified (in particular, truncated) by another process while it's
+    /// being sniffed.
+    ///
+    /// Fails on file opening or mapping errors, or on an error examining the file.
+    #[cfg(feature = "mmap")]
+    pub fn sniff_path_mmap<P: AsRef<Path>>(&mut self, path: P) -> Result<Metadata> {
+        let file = File::open(path)?;
+        // SAFETY: the map is only read while sniffing, and the caller guarantees the file isn't
+        // modified in the meantime
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        self.sniff_bytes(&map)
+    }
     /// Sniff the CSV file provider by the reader, and return a
     /// [`Metadata`](struct.Metadata.html) object containing information about the CSV file.
     ///
     /// Fails on file opening or readering errors, or on an error examining the file.
     pub fn sniff_reader<R: Read + Seek>(&mut self, mut reader: R) -> Result<Metadata> {
+        let deadline = self.get_deadline();
+        let compression = detect_compression(&take_raw_sample_from_start(
+            &mut reader,
+            SampleSize::Bytes(6),
+        )?);
+        let mut metadata = if compression == Compression::None {
+            self.sniff_decompressed_reader(reader, deadline)?
+        } else {
+            // decompress the file and sniff that instead
+            reader.seek(SeekFrom::Start(0))?;
+            self.sniff_decompressed_reader(DecompressReader::new(reader, compression)?, deadline)?
+        };
+        metadata.compression = compression;
+        Ok(metadata)
+    }
+
+    /// Sniff the CSV data held in memory, and return a [`Metadata`](struct.Metadata.html) object
+    /// containing information about the CSV data.
+    ///
+    /// Fails on an error examining the data.
+    pub fn sniff_bytes(&mut self, bytes: &[u8]) -> Result<Metadata> {
+        self.sniff_reader(Cursor::new(bytes))
+    }
+    /// Sniff the CSV text held in memory, and return a [`Metadata`](struct.Metadata.html) object
+    /// containing information about the CSV text.
+    ///
+    /// Fails on an error examining the text.
+    pub fn sniff_str(&mut self, text: &str) -> Result<Metadata> {
+        self.sniff_bytes(text.as_bytes())
+    }
+
+    /// Sniff the CSV stream provided by the reader (which doesn't need to support seeking, e.g. a
+    /// pipe or a network stream), and return a [`Metadata`](struct.Metadata.html) object containing
+    /// information about the CSV stream. A sample (of the configured sample size) is buffered from
+    /// the start of the stream and sniffed; the sampled bytes are consumed from the stream (use
+    /// [`open_stream`](#method.open_stream) to keep reading it).
+    ///
+    /// Fails on reading errors, or on an error examining the stream.
+    pub fn sniff_stream<R: Read>(&mut self, reader: R) -> Result<Metadata> {
+        Ok(self.sniff_stream_sample(reader, self.get_deadline())?.0)
+    }
+
+    // Buffers a sample from the start of a stream and sniffs it.
+    fn sniff_stream_sample<R: Read>(
+        &mut self,
+        reader: R,
+        deadline: Option<Instant>,
+    ) -> Result<(Metadata, StreamSample<R>)> {
+        let sample = StreamSample::take(reader, self.sample_buffer())?;
+        let mut metadata = self.sniff_decompressed_reader(Cursor::new(&sample.sample), deadline)?;
+        metadata.compression = sample.compression;
+        if !sample.complete {
+            // the length of the rest of the stream isn't known
+            metadata.estimated_num_records = None;
+        }
+        Ok((metadata, sample))
+    }
+
+    /// Sniff the remote CSV file at the provided URL, and return a
+    /// [`Metadata`](struct.Metadata.html) object containing information about the CSV file. If the
+    /// sample size is specified in bytes, only (slightly more than) the sample is requested, using
+    /// an HTTP range request; otherwise, the file is streamed and only read until the sample is
+    /// complete.
+    ///
+    /// Fails on HTTP or reading errors, or on an error examining the file.
+    #[cfg(feature = "http")]
+    pub fn sniff_url(&mut self, url: &str) -> Result<Metadata> {
+        let mut request = ureq::get(url);
+        let mut n_requested = None;
+        if let SampleSize::Bytes(n_bytes) = self.get_sample_size() {
+            let n_bytes = match self.get_byte_limit() {
+                Some(_) => n_bytes,
+                None => n_bytes.saturating_add(URL_RANGE_SLACK_BYTES),
+            };
+            // an empty range can't be requested
+            if n_bytes > 0 {
+                request = request.set("Range", &format!("bytes=0-{}", n_bytes - 1));
+                n_requested = Some(n_bytes);
+            }
+        }
+        let response = request.call()?;
+        if response.status() != 206 {
+            // the server ignored the range (or none was requested)
+            return self.sniff_stream(response.into_reader());
+        }
+
+        let mut sample = vec![];
+        response.into_reader().read_to_end(&mut sample)?;
+        let partial = Some(sample.len()) == n_requested;
+        if partial {
+            // the file continues past the sample; drop the partial last line
+            truncate_partial_line(&mut sample, &self.stream_terminators());
+        }
+        let mut metadata = self.sniff_stream(Cursor::new(sample))?;
+        if partial {
+            metadata.estimated_num_records = None;
+        }
+        Ok(metadata)
+    }
+
+    // Creates a buffer for the sample to sniff from a stream.
+    fn sample_buffer(&self) -> SampleBuffer {
+        SampleBuffer::new(self.get_sample_size(), self.stream_terminators())
+            .strict(self.strict_sample_size)
+    }
+
+    // The bytes that can end a line in a sample buffered from a stream (before the terminator is
+    // known).
+    fn stream_terminators(&self) -> Vec<u8> {
+        match self.terminator {
+            Some(terminator) => vec![terminator.byte()],
+            None => vec![b'\n', b'\r'],
+        }
+    }
+
+    /// Asynchronously sniff the CSV file provided by the reader, and return a
+    /// [`Metadata`](struct.Metadata.html) object containing information about the CSV file. A
+    /// sample (of the configured sample size) is read asynchronously from the start of the file and
+    /// then sniffed without blocking on any further input; compressed files are read in their
+    /// entirety.
+    ///
+    /// Fails on reading errors, or on an error examining the file.
+    #[cfg(feature = "tokio")]
+    pub async fn sniff_async<R>(&mut self, reader: R) -> Result<Metadata>
+    where
+        R: tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin,
+    {
+        self.sniff_async_source(TokioSource(reader)).await
+    }
+    /// Asynchronously sniff the CSV file located at the provided path, and return a
+    /// [`Metadata`](struct.Metadata.html) object containing information about the CSV file.
+    ///
+    /// Fails on file opening or reading errors, or on an error examining the file.
+    #[cfg(feature = "tokio")]
+    pub async fn sniff_path_async<P: AsRef<Path>>(&mut self, path: P) -> Result<Metadata> {
+        let file = tokio::fs::File::open(path).await?;
+        self.sniff_async(file).await
+    }
+
+    /// Asynchronously sniff the CSV file provided by a [`futures-io`](https://docs.rs/futures-io)
+    /// reader (e.g. on an `async-std` or `smol` runtime), and return a
+    /// [`Metadata`](struct.Metadata.html) object containing information about the CSV file. Works
+    /// like [`sniff_async`](#method.sniff_async).
+    ///
+    /// Fails on reading errors, or on an error examining the file.
+    #[cfg(feature = "futures-io")]
+    pub async fn sniff_futures<R>(&mut self, reader: R) -> Result<Metadata>
+    where
+        R: futures_util::io::AsyncRead + futures_util::io::AsyncSeek + Unpin,
+    {
+        self.sniff_async_source(FuturesSource(reader)).await
+    }
+
+    // Reads a sample from the start of an asynchronous reader (of any runtime) and sniffs it.
+    #[cfg(any(feature = "tokio", feature = "futures-io"))]
+    async fn sniff_async_source<S: AsyncSource>(&mut self, mut source: S) -> Result<Metadata> {
+        let deadline = self.get_deadline();
+        source.seek(SeekFrom::Start(0)).await?;
+        let mut sample = self.async_sample_buffer();
+        let mut chunk = vec![0; ASYNC_CHUNK_BYTES];
+        let mut read_all = false;
+        while !sample.is_full() {
+            let n_read = source.read(&mut chunk).await?;
+            if n_read == 0 {
+                read_all = true;
+                break;
+            }
+            sample.push(&chunk[..n_read]);
+        }
+        let sample = sample.into_bytes();
+        let n_sample_bytes = sample.len();
+        let mut metadata = self.sniff_stream_sample(Cursor::new(sample), deadline)?.0;
+        if !read_all {
+            let len = source.seek(SeekFrom::End(0)).await?;
+            scale_num_records(&mut metadata, n_sample_bytes, len);
+        }
+        Ok(metadata)
+    }
+
+    // Creates the (runtime-agnostic) buffer that asynchronous readers fill with the sample to
+    // sniff.
+    #[cfg(any(feature = "tokio", feature = "futures-io"))]
+    fn async_sample_buffer(&self) -> SampleBuffer {
+        self.sample_buffer().whole_if_compressed(true)
+    }
+
+    // Sniffs an uncompressed (or already-decompressed) file.
+    fn sniff_decompressed_reader<R: Read + Seek>(
+        &mut self,
+        reader: R,
+        deadline: Option<Instant>,
+    ) -> Result<Metadata> {
+        self.progress.start();
+        let reader = ProgressReader::new(reader, self.progress.clone(), self.cancellation.clone());
+        // every stage re-reads the sample from memory rather than from the underlying reader
+        let reader = CachedReader::new(reader, self.get_cached_sample_bytes());
+        match self.sniff_tracked_reader(reader, deadline) {
+            // cancellation surfaces as a read error (possibly wrapped in a CSV error)
+            Err(_) if self.cancellation.is_cancelled() => Err(SnifferError::Cancelled),
+            result => result,
+        }
+    }
+
+    // Sniffs an uncompressed file, read through a progress-tracking reader.
+    fn sniff_tracked_reader<R: Read + Seek>(
+        &mut self,
+        mut reader: R,
+        deadline: Option<Instant>,
+    ) -> Result<Metadata> {
+        if let Some(deadline) = deadline {
+            return self.sniff_within_deadline(reader, deadline);
+        }
+        if let Some(n_bytes) = self.get_byte_limit() {
+            // read the sample up front, so no later stage reads past it
+            reader.seek(SeekFrom::Start(0))?;
+            let mut sample = vec![];
+            reader.take(n_bytes as u64).read_to_end(&mut sample)?;
+            if sample.len() == n_bytes {
+                truncate_partial_line(&mut sample, &self.stream_terminators());
+            }
+            return self.sniff_encoded_reader(Cursor::new(sample));
+        }
+        self.sniff_encoded_reader(reader)
+    }
+
+    // Sniffs samples of increasing numbers of records, until the next sample isn't expected to be
+    // sniffed by the deadline (or the sample covers the whole file). Keeps the results of the last
+    // sample.
+    fn sniff_within_deadline<R: Read + Seek>(
+        &mut self,
+        mut reader: R,
+        deadline: Instant,
+    ) -> Result<Metadata> {
+        let base = self.clone();
+        let mut n_records = MIN_TIME_BUDGET_RECORDS;
+        let mut best: Option<(Sniffer, Metadata)> = None;
+        loop {
+            let started = Instant::now();
+            let mut attempt = base.clone();
+            attempt.sample_size = Some(SampleSize::Records(n_records));
+            // only the final results are verified
+            attempt.verify = false;
+            let metadata = match attempt.sniff_encoded_reader(&mut reader) {
+                Ok(metadata) => metadata,
+                // keep the results of the previous sample (if any) if this one can't be sniffed
+                Err(e) if best.is_some() && is_sniffing_failure(&e) => break,
+                Err(e) => return Err(e),
+            };
+            let terminator = metadata.dialect.terminator.byte();
+            best = Some((attempt, metadata));
+
+            // sniffing time grows (roughly) linearly with the sample size
+            let is_out_of_time = Instant::now() + 2 * started.elapsed() > deadline;
+            if is_out_of_time || !has_more_lines(&mut reader, n_records, terminator)? {
+                break;
+            }
+            n_records *= 2;
+        }
+        // unwrap is safe: the loop only ends with a result
+        let (mut sniffer, mut metadata) = best.unwrap();
+        if base.verify {
+            // sniff the final sample again (reusing the detected details), verifying the results
+            sniffer.verify = true;
+            metadata = sniffer.sniff_encoded_reader(&mut reader)?;
+        }
+        *self = Sniffer {
+            sample_size: base.sample_size,
+            ..sniffer
+        };
+        Ok(metadata)
+    }
+
+    // The number of bytes from the start of the file kept in memory while sniffing.
+    fn get_cached_sample_bytes(&self) -> usize {
+        match self.get_sample_size() {
+            SampleSize::Bytes(n_bytes) => n_bytes.saturating_add(CACHED_SAMPLE_SLACK_BYTES),
+            SampleSize::Records(_) | SampleSize::All | SampleSize::TimeBudget(_) => {
+                MAX_CACHED_SAMPLE_BYTES
+            }
+        }
+    }
+
+    // The deadline for sniffing, if sniffing within a time budget.
+    fn get_deadline(&self) -> Option<Instant> {
+        match self.get_sample_size() {
+            SampleSize::TimeBudget(budget) => Some(Instant::now() + budget),
+            _ => None,
+        }
+    }
+
+    // Sniffs a file in any encoding, transcoding it to UTF-8 if necessary.
+    fn sniff_encoded_reader<R: Read + Seek>(&mut self, mut reader: R) -> Result<Metadata> {
+        self.progress.stage(Stage::Encoding);
+        let encoding = match self.encoding {
+            Some(encoding) => encoding,
+            None => detect_encoding(&take_raw_sample_from_start(
+                &mut reader,
+                self.get_sample_size(),
+            )?),
+        };
+        if encoding == Encoding::Utf8 {
+            return self.sniff_utf8_reader(reader);
+        }
+
+        // transcode a sample of the file to UTF-8 and sniff that instead (verification and sampling
+        // beyond the head of the file need the whole file)
+        reader.seek(SeekFrom::Start(0))?;
+        let buffer = if self.verify || !matches!(self.sampling, Sampling::Head) {
+            SampleBuffer::new(SampleSize::All, self.stream_terminators())
+        } else {
+            self.sample_buffer()
+        };
+        let footer_known = self.num_footer_rows.is_some();
+        let sample = DecodedSample::take(&mut reader, encoding, buffer)?;
+        let mut metadata = self.sniff_utf8_reader(Cursor::new(sample.text.as_bytes()))?;
+        if !sample.complete {
+            self.extrapolate_decoded_sample(
+                &mut reader,
+                encoding,
+                &sample,
+                footer_known,
+                &mut metadata,
+            )?;
+        }
+        metadata.data_offset = (sample.bom.map_or(0, |bom| bom.bytes().len())
+            + encoded_len(&sample.text[..metadata.data_offset as usize], encoding))
+            as u64;
+        metadata.bom = sample.bom;
+        metadata.encoding = encoding;
+        metadata.dialect.encoding = encoding;
+        Ok(metadata)
+    }
+
+    // Updates the metadata sniffed from a sample decoded from the start of a file (in an encoding
+    // other than UTF-8) that doesn't hold the whole file: detects the footer rows at the end of the
+    // file (unless known), and extrapolates the number of records from the sample to the file.
+    fn extrapolate_decoded_sample<R: Read + Seek>(
+        &mut self,
+        reader: &mut R,
+        encoding: Encoding,
+        sample: &DecodedSample,
+        footer_known: bool,
+        metadata: &mut Metadata,
+    ) -> Result<()> {
+        let sample_footer_rows = metadata.dialect.header.num_footer_rows;
+        let len = reader.seek(SeekFrom::End(0))?;
+        if !footer_known {
+            // UTF-16 code units start at even offsets
+            let start = match encoding {
+                Encoding::Utf16Le | Encoding::Utf16Be => {
+                    len.saturating_sub(FOOTER_SAMPLE_BYTES) & !1
+                }
+                _ => len.saturating_sub(FOOTER_SAMPLE_BYTES),
+            };
+            reader.seek(SeekFrom::Start(start))?;
+            let mut tail = vec![];
+            reader.read_to_end(&mut tail)?;
+            self.infer_footer_in(&decode(&tail, encoding), start > 0);
+            metadata.dialect.header.num_footer_rows = self.num_footer_rows.unwrap_or(0);
+        }
+
+        // the records were only counted in the sample
+        self.num_records = None;
+        metadata.estimated_num_records = metadata.estimated_num_records.map(|n_records| {
+            let scale = len as f64 / sample.n_raw_bytes.max(1) as f64;
+            (((n_records + sample_footer_rows) as f64 * scale).round() as usize)
+                .saturating_sub(metadata.dialect.header.num_footer_rows)
+        });
+        Ok(())
+    }
+
+    // Sniffs a UTF-8 encoded CSV file.
+    fn sniff_utf8_reader<R: Read + Seek>(&mut self, mut reader: R) -> Result<Metadata> {
+        let bom = detect_bom(&take_raw_sample_from_start(
+            &mut reader,
+            SampleSize::Bytes(3),
+        )?);
+        if self.terminator.is_none() {
+            self.progress.stage(Stage::Terminator);
+            self.infer_terminator(&mut reader)?;
+        }
+        if self.comment.is_none() {
+            self.progress.stage(Stage::Comment);
+            self.infer_comment(&mut reader)?;
+        }
+        self.progress.stage(Stage::Delimiter);
+
+        if self.detect_fixed_width && self.delimiter.is_none() {
+            self.check_fixed_width(&mut reader)?;
+        }
+
         // guess quotes & delim
         self.infer_quotes_delim(&mut reader)?;
 
+        if self.escape.is_none() {
+            self.infer_escape(&mut reader)?;
+        }
+        if self.doublequote_escapes.is_none() {
+            self.infer_doublequote_escapes(&mut reader)?;
+        }
+
         // if we have a delimiter, we just need to search for num_preamble_rows and check for
         // flexible. Otherwise, we need to guess a delimiter as well.
+        let preamble_known = self.num_preamble_rows.is_some();
+        self.progress.stage(Stage::Preamble);
         if self.delimiter.is_some() {
             self.infer_preamble_known_delim(&mut reader)?;
         } else {
             self.infer_delim_preamble(&mut reader)?;
         }
+        if !preamble_known {
+            // the preamble was found among non-comment, non-blank lines, but we need to report it
+            // in raw rows
+            let num_preamble_rows = self.num_preamble_rows.unwrap_or(0);
+            self.num_preamble_rows = Some(self.count_raw_rows(&mut reader, num_preamble_rows)?);
+        }
+        self.infer_blank_lines(&mut reader)?;
+        if self.num_footer_rows.is_none() {
+            self.progress.stage(Stage::Footer);
+            self.infer_footer(&mut reader)?;
+        }
+
+        self.progress.stage(Stage::Formatting);
+        if self.quote_style.is_none() {
+            self.infer_quote_style(&mut reader)?;
+        }
+        if self.trim.is_none() {
+            self.infer_trim(&mut reader)?;
+        }
+        if self.number_format.is_none() {
+            self.infer_number_format(&mut reader)?;
+        }
 
         self.infer_types(&mut reader)?;
+        let names = if self.has_header_row == Some(true) {
+            &self.top_row[..]
+        } else {
+            &[]
+        };
+        apply_type_hints(
+            &self.type_hints,
+            names,
+            &mut self.types,
+            &mut self.number_details,
+        );
+        let preamble = self.read_preamble(&mut reader)?;
+        reader.seek(SeekFrom::Start(0))?;
+        let data_offset = preamble_skipcount(
+            &mut reader,
+            self.num_preamble_rows.unwrap_or(0),
+            self.get_terminator().byte(),
+        )?
+        // the data starts after the byte order mark, if any
+        .max(bom.map_or(0, |bom| bom.bytes().len())) as u64;
 
         // as this point of the process, we should have all these filled in.
         assert!(
@@ -123,19 +1195,853 @@ impl Sniffer {
                 && self.delimiter_freq.is_some()
                 && self.has_header_row.is_some()
         );
-        Ok(Metadata {
+        let (date_order, date_order_ambiguous) = get_date_order(&self.types, &self.type_options);
+        let field_names = if self.has_header_row.unwrap() {
+            self.top_row.clone()
+        } else {
+            vec![]
+        };
+        let (types, levels) = get_column_types(
+            &self.types,
+            &self.number_details,
+            &self.levels,
+            &self.type_hints,
+            &field_names,
+        );
+        let type_counts = self
+            .type_counts
+            .iter()
+            .map(TypeCounts::counts)
+            .collect::<Vec<_>>();
+        let mut record_lens = self.record_lens;
+        let mut sequences = self.sequences.clone();
+        if self.has_header_row == Some(false) {
+            record_lens.add(self.top_row_len);
+            for (sequence, top) in sequences.iter_mut().zip(&self.top_row) {
+                sequence.prepend(parse_number(top, &self.type_options.number_format));
+            }
+        }
+        let increasing = get_increasing(&types, &sequences);
+        let primary_keys = if !self.detect_primary_keys {
+            vec![]
+        } else if self.has_header_row == Some(false) {
+            let top = hash_record(self.top_row.iter().map(String::as_str), &self.type_options);
+            let records = [vec![top], self.key_hashes.clone()].concat();
+            primary_keys(&records, types.len())
+        } else {
+            primary_keys(&self.key_hashes, types.len())
+        };
+        let type_confidence = get_type_confidence(&self.types, &types, &self.type_counts);
+        let offending_values = get_offending_values(&self.types, &self.type_counts);
+        let statistics = get_statistics(&self.types, &types, &self.type_counts);
+        let lengths = get_lengths(&self.type_counts);
+        let distinct_counts = get_distinct_counts(&self.type_counts, &self.type_options);
+        let mut metadata = Metadata {
             dialect: Dialect {
                 delimiter: self.delimiter.unwrap(),
                 header: Header {
                     num_preamble_rows: self.num_preamble_rows.unwrap(),
                     has_header_row: self.has_header_row.unwrap(),
+                    num_footer_rows: self.num_footer_rows.unwrap_or(0),
                 },
                 quote: self.quote.clone().unwrap(),
                 flexible: self.flexible.unwrap(),
+                terminator: self.get_terminator(),
+                escape: self.escape.clone().unwrap_or(Escape::Disabled),
+                comment: self.comment.clone().unwrap_or(Comment::Disabled),
+                doublequote_escapes: self.get_doublequote_escapes(),
+                quote_style: self.quote_style.unwrap_or(QuoteStyle::Necessary),
+                num_trailing_empty_fields: self.num_trailing_empty_fields,
+                trim: self.get_trim(),
+                skip_blank_lines: self.get_skip_blank_lines(),
+                encoding: Encoding::Utf8,
             },
             num_fields: self.delimiter_freq.unwrap() + 1,
-            types: self.types.clone(),
-        })
+            num_effective_fields: self.delimiter_freq.unwrap() + 1 - self.num_trailing_empty_fields,
+            num_blank_lines: self.num_blank_lines,
+            estimated_num_records: Some(self.estimate_num_records(&mut reader, data_offset)?),
+            mean_record_len: record_lens.mean(),
+            max_record_len: record_lens.max,
+            types,
+            formats: get_formats(
+                &self.types,
+                date_order.unwrap_or(self.type_options.date_preference),
+                &self.type_options,
+            ),
+            plain_values: get_plain_values(&self.types),
+            semantic_types: get_semantic_types(&self.types),
+            nullable: self.nullable.clone(),
+            null_counts: self.type_counts.iter().map(TypeCounts::nulls).collect(),
+            type_counts,
+            type_confidence,
+            offending_values,
+            statistics,
+            increasing,
+            primary_keys,
+            lengths,
+            distinct_counts,
+            examples: get_examples(&self.type_counts),
+            levels,
+            number_format: self.get_number_format(),
+            date_order,
+            date_order_ambiguous,
+            field_names,
+            preamble,
+            data_offset,
+            bom,
+            encoding: Encoding::Utf8,
+            compression: Compression::None,
+            confidence: Confidence {
+                delimiter: self.delimiter_confidence.unwrap_or(1.0),
+                header: self.header_confidence.unwrap_or(1.0),
+                quote: self.quote_confidence.unwrap_or(1.0),
+            },
+            verification: None,
+        };
+        if self.verify {
+            self.verify_metadata(&mut reader, &mut metadata)?;
+        }
+        Ok(metadata)
+    }
+
+    // Streams the whole file with the sniffed dialect, counting the records that violate it (or the
+    // detected types), and upgrading the metadata to accommodate them.
+    fn verify_metadata<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        metadata: &mut Metadata,
+    ) -> Result<()> {
+        self.progress.stage(Stage::Verification);
+        let dialect = &metadata.dialect;
+        reader.seek(SeekFrom::Start(0))?;
+        snip_preamble(
+            &mut *reader,
+            dialect.header.num_preamble_rows,
+            dialect.terminator.byte(),
+        )?;
+        let mut builder: ReaderBuilder = dialect.clone().into();
+        builder.flexible(true);
+        let mut csv_reader = builder.from_reader(
+            FilteredReader::new(reader, dialect.terminator.byte(), dialect.delimiter)
+                .comment(self.get_comment())
+                .skip_blank_lines(dialect.skip_blank_lines)
+                .skip_footer_rows(dialect.header.num_footer_rows),
+        );
+
+        // values of decimal columns (e.g. hinted ones) are checked as decimals, even if decimals
+        // aren't detected
+        let mut options = self.type_options.clone();
+        options.decimals |= metadata
+            .types
+            .iter()
+            .any(|ty| matches!(ty, Type::Decimal { .. }));
+        let expected = metadata
+            .types
+            .iter()
+            .zip(&metadata.formats)
+            .map(|(&ty, format)| TypeGuesses::with_format(ty, format.as_deref(), &options))
+            .collect::<Vec<_>>();
+        // integer columns can still be widened to decimals, dates can still turn out to be readable
+        // either day-first or month-first, text columns keep their semantic types, and values can
+        // still all be plain
+        let mut guesses = expected
+            .iter()
+            .zip(metadata.types.iter().zip(&metadata.formats))
+            .zip(&metadata.semantic_types)
+            .map(|((&expected, (&ty, format)), &semantic_type)| {
+                let expected = expected
+                    | TypeGuesses::with_swapped_format(ty, format.as_deref(), &options)
+                    | semantic_type.map_or(TypeGuesses::empty(), TypeGuesses::from)
+                    | TypeGuesses::PLAIN;
+                if options.decimals && expected.contains(TypeGuesses::SIGNED) {
+                    expected | TypeGuesses::DECIMAL
+                } else {
+                    expected
+                }
+            })
+            .collect::<Vec<_>>();
+        let expected_details = metadata
+            .types
+            .iter()
+            .map(|&ty| NumberDetails::of_type(ty))
+            .collect::<Vec<_>>();
+        let mut details = expected_details.clone();
+        let mut levels = vec![Levels::default(); expected.len()];
+        let mut type_counts = vec![TypeCounts::default(); expected.len()];
+        let mut verification = Verification {
+            num_records: 0,
+            num_field_count_violations: 0,
+            num_type_violations: 0,
+        };
+        let mut record_lens = RecordLengths::default();
+        let mut start = None;
+        let mut sequences = vec![Sequence::Empty; expected.len()];
+        for record in csv_reader.records() {
+            let record = record?;
+            let record_start = record.position().map_or(0, |position| position.byte());
+            if let Some(start) = start {
+                record_lens.add((record_start - start) as usize);
+            }
+            start = Some(record_start);
+            verification.num_records += 1;
+            if record.len() != metadata.num_fields {
+                verification.num_field_count_violations += 1;
+            }
+            for (i, field) in record.iter().enumerate().take(expected.len()) {
+                sequences[i].add(parse_number(field, &options.number_format));
+                let field_guesses = infer_types(field, &options);
+                let field_details =
+                    NumberDetails::of(field, &options.number_format).unwrap_or_default();
+                if !field_guesses.contains(expected[i])
+                    || (expected[i].contains(TypeGuesses::DECIMAL)
+                        && !field_details.fits(expected_details[i]))
+                {
+                    verification.num_type_violations += 1;
+                }
+                guesses[i] &= field_guesses;
+                details[i].widen(field_details);
+                type_counts[i].add(field, field_guesses, field_details, &options);
+                if options.is_null(field) {
+                    metadata.nullable[i] = true;
+                } else if options.max_levels > 0 {
+                    levels[i].add(field, options.max_levels);
+                }
+            }
+        }
+
+        if verification.num_field_count_violations > 0 {
+            metadata.dialect.flexible = true;
+        }
+        // the values that don't fit the types are tolerated (but still counted as violations)
+        if options.tolerance < 1.0 {
+            for (guesses, counts) in guesses.iter_mut().zip(&type_counts) {
+                *guesses = counts.tolerant(options.tolerance);
+            }
+        }
+        apply_type_hints(
+            &self.type_hints,
+            &metadata.field_names,
+            &mut guesses,
+            &mut details,
+        );
+        let (date_order, date_order_ambiguous) = get_date_order(&guesses, &options);
+        metadata.semantic_types = get_semantic_types(&guesses);
+        (metadata.types, metadata.levels) = get_column_types(
+            &guesses,
+            &details,
+            &levels,
+            &self.type_hints,
+            &metadata.field_names,
+        );
+        metadata.formats = get_formats(
+            &guesses,
+            date_order.unwrap_or(options.date_preference),
+            &options,
+        );
+        metadata.plain_values = get_plain_values(&guesses);
+        metadata.date_order = date_order;
+        metadata.date_order_ambiguous = date_order_ambiguous;
+        metadata.type_counts = type_counts.iter().map(TypeCounts::counts).collect();
+        metadata.null_counts = type_counts.iter().map(TypeCounts::nulls).collect();
+        metadata.type_confidence = get_type_confidence(&guesses, &metadata.types, &type_counts);
+        metadata.offending_values = get_offending_values(&guesses, &type_counts);
+        metadata.statistics = get_statistics(&guesses, &metadata.types, &type_counts);
+        metadata.lengths = get_lengths(&type_counts);
+        metadata.distinct_counts = get_distinct_counts(&type_counts, &options);
+        metadata.examples = get_examples(&type_counts);
+        if let Some(start) = start {
+            record_lens.add((csv_reader.position().byte() - start) as usize);
+        }
+        metadata.mean_record_len = record_lens.mean();
+        metadata.increasing = get_increasing(&metadata.types, &sequences);
+        metadata.max_record_len = record_lens.max;
+        metadata.estimated_num_records = Some(verification.num_records);
+        metadata.verification = Some(verification);
+        Ok(())
+    }
+
+    /// Sniff the CSV file provided by the reader, and return all plausible
+    /// [`Dialect`](metadata/struct.Dialect.html)s, ranked from most to least likely, each paired
+    /// with a score between 0.0 and 1.0. The first candidate is the dialect that
+    /// [`sniff_reader`](#method.sniff_reader) would return.
+    ///
+    /// Fails on file opening or readering errors, or on an error examining the file.
+    pub fn sniff_candidates<R: Read + Seek>(
+        &mut self,
+        mut reader: R,
+    ) -> Result<Vec<(Dialect, f64)>> {
+        // keep the sniffer's configuration so every candidate is sniffed from the same starting
+        // point
+        let base = self.clone();
+        let metadata = self.sniff_reader(&mut reader)?;
+        let best_delim = metadata.dialect.delimiter;
+        let mut candidates = vec![(metadata.dialect, metadata.confidence.delimiter)];
+
+        for &(delim, score) in &self.delimiter_ranking {
+            if delim == best_delim {
+                continue;
+            }
+            let mut sniffer = base.clone();
+            sniffer.delimiter = Some(delim);
+            sniffer.delimiter_confidence = Some(score);
+            match sniffer.sniff_reader(&mut reader) {
+                Ok(metadata) => candidates.push((metadata.dialect, score)),
+                // this delimiter doesn't pan out once the rest of the dialect is considered
+                Err(SnifferError::SniffingFailed(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// Sniff the CSV file provided by the reader as a series of separate tables (regions of the file
+    /// separated by one or more blank lines), and return the byte range and
+    /// [`Metadata`](metadata/struct.Metadata.html) of each. Regions that can't be sniffed as a
+    /// table are skipped.
+    ///
+    /// Fails on file opening or readering errors, or on an error examining the file.
+    pub fn sniff_tables<R: Read + Seek>(&mut self, mut reader: R) -> Result<Vec<Table>> {
+        // every table is sniffed from the sniffer's configuration, but they share a terminator
+        let mut base = self.clone();
+        if base.terminator.is_none() {
+            base.infer_terminator(&mut reader)?;
+        }
+        let terminator = base.get_terminator().byte();
+
+        reader.seek(SeekFrom::Start(0))?;
+        let mut ranges = vec![];
+        {
+            let mut buf_reader = BufReader::new(&mut reader);
+            let mut line = vec![];
+            let (mut pos, mut start) = (0, None);
+            loop {
+                line.clear();
+                let n_read = buf_reader.read_until(terminator, &mut line)? as u64;
+                if n_read == 0 {
+                    break;
+                }
+                let is_blank_line = std::str::from_utf8(&line).is_ok_and(is_blank);
+                match (is_blank_line, start) {
+                    (false, None) => start = Some(pos),
+                    (true, Some(table_start)) => {
+                        ranges.push(table_start..pos);
+                        start = None;
+                    }
+                    _ => {}
+                }
+                pos += n_read;
+            }
+            if let Some(table_start) = start {
+                ranges.push(table_start..pos);
+            }
+        }
+
+        let mut tables = vec![];
+        for range in ranges {
+            reader.seek(SeekFrom::Start(range.start))?;
+            let mut contents = vec![];
+            (&mut reader)
+                .take(range.end - range.start)
+                .read_to_end(&mut contents)?;
+            let mut metadata = match base.clone().sniff_reader(Cursor::new(contents)) {
+                Ok(metadata) => metadata,
+                Err(SnifferError::SniffingFailed(_)) => continue,
+                Err(err) => return Err(err),
+            };
+            metadata.data_offset += range.start;
+            tables.push(Table { range, metadata });
+        }
+        Ok(tables)
+    }
+
+    // Estimates the number of data records in the whole file, from the length of the data and the
+    // average length of the records at its head (or counts them, if the whole file was sampled).
+    fn estimate_num_records<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        data_offset: u64,
+    ) -> Result<usize> {
+        let n_header_rows = usize::from(self.has_header_row == Some(true));
+        if let Some(num_records) = self.num_records {
+            return Ok(num_records.saturating_sub(n_header_rows));
+        }
+        let len = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(data_offset))?;
+        let mut head = vec![];
+        reader.take(RECORD_ESTIMATE_BYTES).read_to_end(&mut head)?;
+        let at_end = data_offset + (head.len() as u64) >= len;
+
+        // count the records ending within the head (or ending the file)
+        let mut builder = csvc::ReaderBuilder::new();
+        builder
+            .delimiter(self.delimiter.unwrap_or(b','))
+            .terminator(self.get_terminator().into())
+            .escape(self.get_escape())
+            .double_quote(self.get_doublequote_escapes());
+        match self.quote {
+            Some(Quote::Some(character)) => builder.quote(character),
+            _ => builder.quoting(false),
+        };
+        let mut csv_reader = builder.build();
+        let (mut output, mut ends) = (vec![0; 1 << 12], vec![0; 1 << 8]);
+        let (mut input, mut n_records, mut n_bytes) = (&head[..], 0, 0);
+        loop {
+            let (result, n_read, _, _) = csv_reader.read_record(input, &mut output, &mut ends);
+            input = &input[n_read..];
+            match result {
+                csvc::ReadRecordResult::Record => {
+                    n_records += 1;
+                    n_bytes = head.len() - input.len();
+                }
+                csvc::ReadRecordResult::InputEmpty if !at_end => break,
+                csvc::ReadRecordResult::End => break,
+                _ => {}
+            }
+        }
+        if n_records == 0 {
+            return Ok(0);
+        }
+        let n_data_bytes = len.saturating_sub(data_offset) as f64;
+        let n_records = if at_end {
+            n_records
+        } else {
+            (n_data_bytes * n_records as f64 / n_bytes as f64).round() as usize
+        };
+        Ok(n_records
+            .saturating_sub(n_header_rows)
+            .saturating_sub(self.num_footer_rows.unwrap_or(0)))
+    }
+
+    // Infers the record terminator from the raw bytes at the start of the file.
+    fn infer_terminator<R: Read + Seek>(&mut self, reader: &mut R) -> Result<()> {
+        let sample = take_raw_sample_from_start(reader, self.get_sample_size())?;
+        self.terminator = Some(detect_terminator(&sample));
+        Ok(())
+    }
+
+    // Infers whether quotes within quoted fields are escaped by doubling them (`""`) rather than
+    // with the escape character (`\"`). Defaults to doubling if there's no evidence either way.
+    // Updates self.doublequote_escapes.
+    fn infer_doublequote_escapes<R: Read + Seek>(&mut self, reader: &mut R) -> Result<()> {
+        let quote = match self.quote {
+            Some(Quote::Some(character)) => character,
+            _ => {
+                self.doublequote_escapes = Some(true);
+                return Ok(());
+            }
+        };
+        let escape = self.get_escape();
+
+        let (mut n_doubled, mut n_escaped) = (0, 0);
+        for line in self.take_sample(reader)? {
+            let line = line?;
+            let (doubled, escaped) = count_quote_escapes(line.as_bytes(), quote, escape);
+            n_doubled += doubled;
+            n_escaped += escaped;
+        }
+        self.doublequote_escapes = Some(n_doubled > 0 || n_escaped == 0);
+        Ok(())
+    }
+
+    // Infers which fields are quoted: all of them, only the non-numeric ones, or only those that
+    // need it. Preamble rows are not considered. Updates self.quote_style.
+    fn infer_quote_style<R: Read + Seek>(&mut self, reader: &mut R) -> Result<()> {
+        // prerequisites for calling this function:
+        assert!(self.delimiter.is_some() && self.num_preamble_rows.is_some());
+        let quote = match self.quote {
+            Some(Quote::Some(character)) => character,
+            _ => {
+                self.quote_style = Some(QuoteStyle::Necessary);
+                return Ok(());
+            }
+        };
+        let (delim, escape) = (self.delimiter.unwrap(), self.get_escape());
+
+        let (mut n_quoted, mut n_unquoted) = (0, 0);
+        let mut non_numeric = true;
+        let lines = take_sample_from_start(
+            reader,
+            self.get_sample_size(),
+            self.get_terminator().byte(),
+            None,
+            false,
+        )?;
+        for line in lines.skip(self.num_preamble_rows.unwrap()) {
+            let line = line?;
+            if self.is_skipped_line(&line) {
+                continue;
+            }
+            for (quoted, field) in split_fields(line.as_bytes(), delim, quote, escape) {
+                let is_numeric = std::str::from_utf8(field)
+                    .ok()
+                    .is_some_and(|field| field.parse::<f64>().is_ok());
+                if quoted {
+                    n_quoted += 1;
+                    non_numeric &= !is_numeric;
+                } else if !field.is_empty() {
+                    // empty unquoted fields don't tell us anything about the style
+                    n_unquoted += 1;
+                    non_numeric &= is_numeric;
+                }
+            }
+        }
+        self.quote_style = Some(if n_quoted > 0 && n_unquoted == 0 {
+            QuoteStyle::Always
+        } else if n_quoted > 0 && non_numeric {
+            QuoteStyle::NonNumeric
+        } else {
+            QuoteStyle::Necessary
+        });
+        Ok(())
+    }
+
+    // Infers whether fields are padded with whitespace around the delimiter (e.g. `a , b , c`).
+    // Preamble rows are not considered. Updates self.trim.
+    fn infer_trim<R: Read + Seek>(&mut self, reader: &mut R) -> Result<()> {
+        // prerequisites for calling this function:
+        assert!(self.delimiter.is_some() && self.num_preamble_rows.is_some());
+        let delim = self.delimiter.unwrap();
+        if delim.is_ascii_whitespace() {
+            // whitespace-delimited files can't have padding
+            self.trim = Some(Trim::None);
+            return Ok(());
+        }
+        let quote = match self.quote {
+            Some(Quote::Some(character)) => Some(character),
+            _ => None,
+        };
+        let escape = self.get_escape();
+
+        let (mut n_padded, mut n_fields) = (0, 0);
+        let lines = take_sample_from_start(
+            reader,
+            self.get_sample_size(),
+            self.get_terminator().byte(),
+            None,
+            false,
+        )?;
+        for line in lines.skip(self.num_preamble_rows.unwrap()) {
+            let line = line?;
+            if self.is_skipped_line(&line) {
+                continue;
+            }
+            let fields = match quote {
+                Some(quote) => split_fields(line.as_bytes(), delim, quote, escape),
+                None => line
+                    .as_bytes()
+                    .split(|&chr| chr == delim)
+                    .map(|field| (false, field))
+                    .collect(),
+            };
+            for (_, field) in fields
+                .into_iter()
+                .filter(|(quoted, field)| !quoted && !field.is_empty())
+            {
+                n_fields += 1;
+                if field[0].is_ascii_whitespace() || field[field.len() - 1].is_ascii_whitespace() {
+                    n_padded += 1;
+                }
+            }
+        }
+        self.trim = Some(if n_fields > 0 && 2 * n_padded > n_fields {
+            Trim::All
+        } else {
+            Trim::None
+        });
+        Ok(())
+    }
+
+    // Infers the separators used in numbers from the numbers that can only be read one way (e.g.
+    // `3,14` or `1.234,56` for a decimal comma, `3.14` or `1,234.56` for a decimal point). Preamble
+    // rows are not considered. Updates self.number_format.
+    fn infer_number_format<R: Read + Seek>(&mut self, reader: &mut R) -> Result<()> {
+        // prerequisites for calling this function:
+        assert!(self.delimiter.is_some() && self.num_preamble_rows.is_some());
+        let delim = self.delimiter.unwrap();
+        let quote = match self.quote {
+            Some(Quote::Some(character)) => Some(character),
+            _ => None,
+        };
+        let escape = self.get_escape();
+
+        let (mut n_decimal_points, mut n_decimal_commas) = (0, 0);
+        let mut grouping_counts: HashMap<char, usize> = HashMap::new();
+        let mut ambiguous_counts: HashMap<char, usize> = HashMap::new();
+        let lines = take_sample_from_start(
+            reader,
+            self.get_sample_size(),
+            self.get_terminator().byte(),
+            None,
+            false,
+        )?;
+        for line in lines.skip(self.num_preamble_rows.unwrap()) {
+            let line = line?;
+            if self.is_skipped_line(&line) {
+                continue;
+            }
+            let fields = match quote {
+                Some(quote) => split_fields(line.as_bytes(), delim, quote, escape),
+                None => line
+                    .as_bytes()
+                    .split(|&chr| chr == delim)
+                    .map(|field| (false, field))
+                    .collect(),
+            };
+            for (_, field) in fields {
+                let Ok(field) = std::str::from_utf8(field) else {
+                    continue;
+                };
+                let Some((decimal, grouping)) = number_separators(field) else {
+                    if let Some(separator) = ambiguous_separator(field) {
+                        *ambiguous_counts.entry(separator).or_insert(0) += 1;
+                    }
+                    continue;
+                };
+                // a grouping separator of `.` or `,` implies the other one is the decimal separator
+                match decimal.or(match grouping {
+                    Some('.') => Some(','),
+                    Some(',') => Some('.'),
+                    _ => None,
+                }) {
+                    Some('.') => n_decimal_points += 1,
+                    Some(',') => n_decimal_commas += 1,
+                    _ => {}
+                }
+                if let Some(grouping) = grouping {
+                    *grouping_counts.entry(grouping).or_insert(0) += 1;
+                }
+            }
+        }
+        let decimal_separator = if n_decimal_commas > n_decimal_points {
+            ','
+        } else {
+            '.'
+        };
+        let grouping_separator = grouping_counts
+            .into_iter()
+            .filter(|&(grouping, _)| grouping != decimal_separator)
+            // break ties by character, so the result doesn't depend on the hash order
+            .max_by_key(|&(grouping, count)| (count, std::cmp::Reverse(grouping)))
+            .map(|(grouping, _)| grouping)
+            // numbers like `1,234` are read with the separator that isn't the decimal separator as
+            // a grouping separator, so report it when they're all there is
+            .or_else(|| {
+                let other = if decimal_separator == ',' { '.' } else { ',' };
+                ambiguous_counts.contains_key(&other).then_some(other)
+            });
+        self.number_format = Some(NumberFormat {
+            decimal_separator,
+            grouping_separator,
+        });
+        Ok(())
+    }
+
+    // Infers the comment character, if any: a marker that starts some (but not all) lines, and that
+    // rarely appears in the lines it doesn't start (which would suggest it's part of the data).
+    // Failing that, a marker that starts every line of a block at the top of the file (and no line
+    // after it) marks a commented preamble. Comment lines at the top of the file are counted as
+    // preamble rows.
+    fn infer_comment<R: Read + Seek>(&mut self, reader: &mut R) -> Result<()> {
+        let lines = take_sample_from_start(
+            reader,
+            self.get_sample_size(),
+            self.get_terminator().byte(),
+            None,
+            false,
+        )?
+        .filter(|line| line.as_ref().map_or(true, |line| !is_blank(line)))
+        .collect::<Result<Vec<_>>>()?;
+
+        let best_marker = COMMENT_MARKERS
+            .iter()
+            .filter_map(|marker| {
+                let (marked, unmarked): (Vec<&String>, Vec<&String>) = lines
+                    .iter()
+                    .partition(|line| line.as_bytes().starts_with(marker));
+                let n_unmarked_containing = unmarked
+                    .iter()
+                    .filter(|line| line.as_bytes().contains(&marker[0]))
+                    .count();
+                if marked.is_empty()
+                    || unmarked.is_empty()
+                    || 2 * n_unmarked_containing >= unmarked.len()
+                {
+                    None
+                } else {
+                    Some((marker[0], marked.len()))
+                }
+            })
+            .max_by_key(|&(_, n_marked)| n_marked)
+            .or_else(|| {
+                COMMENT_MARKERS.iter().find_map(|marker| {
+                    let n_leading = lines
+                        .iter()
+                        .take_while(|line| line.as_bytes().starts_with(marker))
+                        .count();
+                    let is_block = n_leading > 0
+                        && n_leading < lines.len()
+                        && !lines[n_leading..]
+                            .iter()
+                            .any(|line| line.as_bytes().starts_with(marker));
+                    if is_block {
+                        Some((marker[0], n_leading))
+                    } else {
+                        None
+                    }
+                })
+            });
+
+        self.comment = Some(match best_marker {
+            Some((comment, _)) => Comment::Enabled(comment),
+            None => Comment::Disabled,
+        });
+        Ok(())
+    }
+
+    // Infers the number of footer rows (e.g. summary lines such as `Total,,,12345.67`) by comparing
+    // the rows at the end of the file to the rows above them. Updates self.num_footer_rows.
+    fn infer_footer<R: Read + Seek>(&mut self, reader: &mut R) -> Result<()> {
+        let end = reader.seek(SeekFrom::End(0))?;
+        let start = end.saturating_sub(FOOTER_SAMPLE_BYTES);
+        reader.seek(SeekFrom::Start(start))?;
+        let mut tail = vec![];
+        reader.read_to_end(&mut tail)?;
+        self.infer_footer_in(&String::from_utf8_lossy(&tail), start > 0);
+        Ok(())
+    }
+
+    // Infers the number of footer rows from the tail of the file, which starts in the middle of the
+    // file if `is_cut` (and at the start of the file otherwise). Updates self.num_footer_rows.
+    fn infer_footer_in(&mut self, tail: &str, is_cut: bool) {
+        // prerequisites for calling this function:
+        assert!(self.delimiter.is_some() && self.num_preamble_rows.is_some());
+        let (delim, escape, terminator) = (
+            self.delimiter.unwrap(),
+            self.get_escape(),
+            self.get_terminator().byte(),
+        );
+        let quote = match self.quote {
+            Some(Quote::Some(character)) => Some(character),
+            _ => None,
+        };
+        let lines = tail
+            .split(char::from(terminator))
+            .map(|line| line.trim_end_matches(['\r', '\n']))
+            // when starting in the middle of the file, the first line is likely cut off; otherwise,
+            // skip the preamble
+            .skip(if is_cut {
+                1
+            } else {
+                self.num_preamble_rows.unwrap()
+            })
+            .filter(|line| !self.is_skipped_line(line) && !line.is_empty())
+            // the first row might be a header row (which isn't known yet)
+            .skip(usize::from(!is_cut))
+            .collect::<Vec<_>>();
+        let rows = lines
+            .iter()
+            .map(|line| {
+                let fields = match quote {
+                    Some(quote) => split_fields(line.as_bytes(), delim, quote, escape),
+                    None => line
+                        .as_bytes()
+                        .split(|&chr| chr == delim)
+                        .map(|field| (false, field))
+                        .collect(),
+                };
+                fields
+                    .into_iter()
+                    .map(|(_, field)| FooterField::of(field, &self.type_options))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // the most footer rows such that each of them stands out from all the rows above them
+        let max_footer_rows = rows
+            .len()
+            .saturating_sub(MIN_FOOTER_REFERENCE_ROWS)
+            .min(MAX_FOOTER_ROWS);
+        self.num_footer_rows = Some(
+            (1..=max_footer_rows)
+                .rev()
+                .find(|&n_footer_rows| {
+                    let (reference, footer) = rows.split_at(rows.len() - n_footer_rows);
+                    footer.iter().all(|row| is_footer_row(row, reference))
+                })
+                .unwrap_or(0),
+        );
+    }
+
+    // Reads the preamble rows (without their terminators).
+    fn read_preamble<R: Read + Seek>(&self, reader: &mut R) -> Result<Vec<String>> {
+        take_sample_from_start(
+            reader,
+            SampleSize::All,
+            self.get_terminator().byte(),
+            None,
+            false,
+        )?
+        .take(self.num_preamble_rows.unwrap_or(0))
+        .collect()
+    }
+
+    // Counts the blank lines among the data rows of the sample, and decides whether or not they
+    // should be skipped. Updates self.num_blank_lines and self.skip_blank_lines.
+    fn infer_blank_lines<R: Read + Seek>(&mut self, reader: &mut R) -> Result<()> {
+        // prerequisites for calling this function:
+        assert!(self.num_preamble_rows.is_some());
+        let lines = take_sample_from_start(
+            reader,
+            self.get_sample_size(),
+            self.get_terminator().byte(),
+            None,
+            false,
+        )?;
+        let mut num_blank_lines = 0;
+        for line in lines.skip(self.num_preamble_rows.unwrap()) {
+            if is_blank(&line?) {
+                num_blank_lines += 1;
+            }
+        }
+        self.num_blank_lines = num_blank_lines;
+        if self.skip_blank_lines.is_none() {
+            self.skip_blank_lines = Some(num_blank_lines > 0);
+        }
+        Ok(())
+    }
+
+    // Counts the raw rows (including comment and blank lines) that precede the given number of
+    // rows of the filtered sample.
+    fn count_raw_rows<R: Read + Seek>(&self, reader: &mut R, n_rows: usize) -> Result<usize> {
+        let lines = take_sample_from_start(
+            reader,
+            SampleSize::All,
+            self.get_terminator().byte(),
+            None,
+            false,
+        )?;
+        let mut n_seen = 0;
+        let mut n_raw_rows = 0;
+        for line in lines {
+            let line = line?;
+            if !self.is_skipped_line(&line) {
+                if n_seen == n_rows {
+                    break;
+                }
+                n_seen += 1;
+            }
+            n_raw_rows += 1;
+        }
+        Ok(n_raw_rows)
+    }
+
+    // Fails with a SnifferError::FixedWidth if the sample looks like a fixed-width table.
+    fn check_fixed_width<R: Read + Seek>(&mut self, reader: &mut R) -> Result<()> {
+        let lines = self.take_sample(reader)?.collect::<Result<Vec<_>>>()?;
+        match fixed_width_columns(&lines) {
+            Some(column_starts) => Err(SnifferError::FixedWidth(column_starts)),
+            None => Ok(()),
+        }
     }
 
     // Infers quotes and delimiter from quoted (or possibly quoted) files. If quotes detected,
@@ -155,37 +2061,98 @@ impl Sniffer {
             }
             None => vec![b'\'', b'"', b'`'],
         };
-        // TODO: this can probably be replaced with a try_fold whenever that leaves nightly
-        let (quote_chr, (quote_cnt, delim_guess)) = quote_guesses.iter().fold(
-            Ok((b'"', (0, b'\0'))),
-            |acc: Result<(u8, (usize, u8))>, &chr| {
-                if let Ok(acc) = acc {
-                    let mut sample_reader = take_sample_from_start(reader, self.get_sample_size())?;
-                    if let Some((cnt, delim_chr)) =
-                        quote_count(&mut sample_reader, char::from(chr), &self.delimiter)?
-                    {
-                        Ok(if cnt > (acc.1).0 {
-                            (chr, (cnt, delim_chr))
-                        } else {
-                            acc
-                        })
-                    } else {
-                        Ok(acc)
-                    }
-                } else {
-                    acc
-                }
+        let mut total_cnt = 0;
+        let (quote_chr, (quote_cnt, delim_ranking)) = quote_guesses.iter().try_fold(
+            (b'"', (0, vec![])),
+            |acc: (u8, (usize, DelimRanking)), &chr| -> Result<(u8, (usize, DelimRanking))> {
+                let mut sample_reader = self.take_sample(reader)?;
+                Ok(
+                    match quote_count(
+                        &mut sample_reader,
+                        char::from(chr),
+                        &self.delimiter,
+                        &self.delimiter_candidates,
+                    )? {
+                        Some((cnt, delim_ranking)) => {
+                            total_cnt += cnt;
+                            if cnt > (acc.1).0 {
+                                (chr, (cnt, delim_ranking))
+                            } else {
+                                acc
+                            }
+                        }
+                        None => acc,
+                    },
+                )
             },
         )?;
         if quote_cnt == 0 {
+            // no quoted fields found anywhere in the sample
             self.quote = Some(Quote::None);
+            self.quote_confidence = Some(1.0);
         } else {
             self.quote = Some(Quote::Some(quote_chr));
-            self.delimiter = Some(delim_guess);
+            self.quote_confidence = Some(quote_cnt as f64 / total_cnt as f64);
+            if self.delimiter.is_none() {
+                let delim_ranking = self.weigh_ranking(delim_ranking);
+                // ranking is never empty; quote_count always finds a delimiter for its matches
+                let (delim_guess, delim_conf) = delim_ranking[0];
+                self.delimiter = Some(delim_guess);
+                self.delimiter_confidence = Some(delim_conf);
+                self.delimiter_ranking = delim_ranking;
+            }
+        };
+        Ok(())
+    }
+
+    // Infers whether a backslash is used to escape quotes or delimiters. Updates self.escape.
+    fn infer_escape<R: Read + Seek>(&mut self, reader: &mut R) -> Result<()> {
+        const BACKSLASH: u8 = b'\\';
+        let mut escapable = match self.delimiter {
+            Some(delim) => vec![delim],
+            None => match self.delimiter_candidates {
+                Some(ref candidates) => candidates.clone(),
+                None => PREFERRED_DELIMITERS.to_vec(),
+            },
         };
+        if let Some(Quote::Some(character)) = self.quote {
+            escapable.push(character);
+        }
+
+        let mut n_escapes = 0;
+        for line in self.take_sample(reader)? {
+            let line = line?;
+            n_escapes += line
+                .as_bytes()
+                .windows(2)
+                .filter(|pair| pair[0] == BACKSLASH && escapable.contains(&pair[1]))
+                .count();
+        }
+        self.escape = Some(if n_escapes > 0 {
+            Escape::Enabled(BACKSLASH)
+        } else {
+            Escape::Disabled
+        });
         Ok(())
     }
 
+    // Applies the delimiter prior weights to the scores of a delimiter ranking, and re-ranks it.
+    fn weigh_ranking(&self, ranking: DelimRanking) -> DelimRanking {
+        let mut ranking = ranking
+            .into_iter()
+            .map(|(delim, score)| (delim, score * self.get_delimiter_prior(delim)))
+            .collect::<Vec<_>>();
+        let total_score: f64 = ranking.iter().map(|&(_, score)| score).sum();
+        if total_score > 0.0 {
+            for (_, score) in ranking.iter_mut() {
+                *score /= total_score;
+            }
+        }
+        // stable sort, so ties keep their original order
+        ranking.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranking
+    }
+
     // Updates delimiter frequency, number of preamble rows, and flexible boolean.
     fn infer_preamble_known_delim<R: Read + Seek>(&mut self, reader: &mut R) -> Result<()> {
         // prerequisites for calling this function:
@@ -193,7 +2160,7 @@ impl Sniffer {
         // unwraps for delimiter and quote are safe
         let (quote, delim) = (self.quote.clone().unwrap(), self.delimiter.unwrap());
 
-        let sample_iter = take_sample_from_start(reader, self.get_sample_size())?;
+        let sample_iter = self.take_sample(reader)?;
 
         let mut chain = Chain::default();
 
@@ -203,6 +2170,8 @@ impl Sniffer {
             let mut csv_reader = csvc::ReaderBuilder::new()
                 .delimiter(delim)
                 .quote(character)
+                .escape(self.get_escape())
+                .double_quote(self.get_doublequote_escapes())
                 .build();
 
             let mut output = vec![];
@@ -232,60 +2201,89 @@ impl Sniffer {
                 chain.add_observation(n_ends);
             }
         } else {
+            let escape = self.get_escape();
+            let mut freqs = [0];
             for line in sample_iter {
                 let line = line?;
-                let freq = line.as_bytes().iter().filter(|&&c| c == delim).count();
-                chain.add_observation(freq);
+                candidate_frequencies(line.as_bytes(), &[delim], escape, &mut freqs);
+                chain.add_observation(freqs[0]);
             }
         }
-        self.run_chains(vec![chain])
+        self.run_chains(vec![(delim, chain)])
     }
 
     // Updates delimiter, delimiter frequency, number of preamble rows, and flexible boolean.
     fn infer_delim_preamble<R: Read + Seek>(&mut self, reader: &mut R) -> Result<()> {
-        let sample_iter = take_sample_from_start(reader, self.get_sample_size())?;
+        let sample_iter = self.take_sample(reader)?;
 
-        const NUM_ASCII_CHARS: usize = 128;
-        let mut chains = vec![Chain::default(); NUM_ASCII_CHARS];
+        const NUM_ASCII_CHARS: u8 = 128;
+        let candidates = match self.delimiter_candidates {
+            Some(ref candidates) => candidates.clone(),
+            None => (0..NUM_ASCII_CHARS).collect(),
+        };
+        let mut chains = vec![Chain::default(); candidates.len()];
+        let escape = self.get_escape();
+        let mut freqs = vec![0; candidates.len()];
+        // the colons in times of day and the colons and slashes in URLs aren't delimiters (unless
+        // the character is the only candidate)
+        let position = |target: u8| match candidates.len() {
+            1 => None,
+            _ => candidates.iter().position(|&chr| chr == target),
+        };
+        let colon = position(b':');
+        let slash = position(b'/');
         for line in sample_iter {
             let line = line?;
-            let mut freqs = [0; NUM_ASCII_CHARS];
-            for &chr in line.as_bytes() {
-                if chr < NUM_ASCII_CHARS as u8 {
-                    freqs[chr as usize] += 1;
-                }
+            candidate_frequencies(line.as_bytes(), &candidates, escape, &mut freqs);
+            if let Some(i) = colon {
+                freqs[i] = freqs[i].saturating_sub(
+                    count_time_colons(line.as_bytes()) + count_url_chars(line.as_bytes(), b':'),
+                );
             }
-            for (chr, &freq) in freqs.iter().enumerate() {
-                chains[chr as usize].add_observation(freq);
+            if let Some(i) = slash {
+                freqs[i] = freqs[i].saturating_sub(count_url_chars(line.as_bytes(), b'/'));
+            }
+            for (chain, &freq) in chains.iter_mut().zip(&freqs) {
+                chain.add_observation(freq);
             }
         }
 
-        self.run_chains(chains)
+        self.run_chains(candidates.into_iter().zip(chains).collect())
     }
 
     // Updates delimiter (if not already known), delimiter frequency, number of preamble rows, and
     // flexible boolean.
-    fn run_chains(&mut self, mut chains: Vec<Chain>) -> Result<()> {
+    fn run_chains(&mut self, mut chains: Vec<(u8, Chain)>) -> Result<()> {
         // Find the 'best' delimiter: choose strict (non-flexible) delimiters over flexible ones,
-        // and choose the one that had the highest probability markov chain in the end.
+        // and choose the one that had the highest probability markov chain in the end (weighted by
+        // the delimiter's prior).
         //
-        // In the case where delim is already known, 'best_delim' will be incorrect (since it won't
-        // correspond with position in a vector of Chains), but we'll just ignore it when
-        // constructing our return value later. 'best_state' and 'path' are necessary, though, to
-        // compute the preamble rows.
-        let (best_delim, delim_freq, best_state, path, _) = chains.iter_mut().enumerate().fold(
+        // In the case where delim is already known, there will only be the one chain, but we still
+        // need 'best_state' and 'path' to compute the preamble rows.
+        //
+        // While we're at it, we keep track of all the chains that ended up in a steady state, so
+        // we can rank them against each other.
+        let mut steady_chains = vec![];
+        let (best_delim, delim_freq, best_state, path, _) = chains.iter_mut().fold(
             (b',', 0, STATE_UNSTEADY, vec![], 0.0),
-            |acc, (i, ref mut chain)| {
+            |acc, (delim, chain)| {
                 let (_, _, best_state, _, best_state_prob) = acc;
                 let ViterbiResults {
                     max_delim_freq,
                     path,
                 } = chain.viterbi();
-                let (final_state, final_viter) = path[path.len() - 1];
-                if final_state < best_state
-                    || (final_state == best_state && final_viter.prob > best_state_prob)
+                let (final_state, final_viter) = match path.last() {
+                    Some(&last) => last,
+                    // no observations at all (empty sample)
+                    None => return acc,
+                };
+                let prob = final_viter.prob * self.get_delimiter_prior(*delim);
+                if final_state != STATE_UNSTEADY {
+                    steady_chains.push((*delim, final_state, prob));
+                }
+                if final_state < best_state || (final_state == best_state && prob > best_state_prob)
                 {
-                    (i as u8, max_delim_freq, final_state, path, final_viter.prob)
+                    (*delim, max_delim_freq, final_state, path, prob)
                 } else {
                     acc
                 }
@@ -312,10 +2310,18 @@ impl Sniffer {
             num_preamble_rows += 1;
         }
         if self.delimiter.is_none() {
+            self.delimiter_ranking = rank_chains(best_state, steady_chains);
             self.delimiter = Some(best_delim);
+            self.delimiter_confidence = self
+                .delimiter_ranking
+                .iter()
+                .find(|&&(delim, _)| delim == best_delim)
+                .map(|&(_, score)| score);
         }
         self.delimiter_freq = Some(delim_freq);
-        self.num_preamble_rows = Some(num_preamble_rows);
+        if self.num_preamble_rows.is_none() {
+            self.num_preamble_rows = Some(num_preamble_rows);
+        }
         Ok(())
     }
 
@@ -325,89 +2331,380 @@ impl Sniffer {
         // unwrap is safe
         let field_count = self.delimiter_freq.unwrap() + 1;
 
+        self.type_options.number_format = self.get_number_format();
+        self.progress.stage(Stage::Header);
         let mut csv_reader = self.create_csv_reader(reader)?;
-        let mut records_iter = csv_reader.records();
+        // records are read into the same buffer and folded into the running state of each column,
+        // so memory use doesn't grow with the size of the sample
+        let mut record = StringRecord::new();
         let mut n_bytes = 0;
         let mut n_records = 0;
         let sample_size = self.get_sample_size();
+        let started = Instant::now();
+        // number of leading fields that are non-empty in at least one record
+        let mut n_nonempty_fields = 0;
 
         // Infer types for the top row. We'll save this set of types to check against the types
         // of the remaining rows to see if this is part of the data or a separate header row.
-        let header_row_types = match records_iter.next() {
-            Some(record) => {
-                let record = record?;
-                n_records += 1;
-                n_bytes += count_bytes(&record);
-                infer_record_types(&record)
-            }
-            None => {
-                return Err(SnifferError::SniffingFailed(
-                    "CSV empty (after preamble)".into(),
-                ));
+        if !csv_reader.read_record(&mut record)? {
+            return Err(SnifferError::SniffingFailed(
+                "CSV empty (after preamble)".into(),
+            ));
+        }
+        self.top_row_len = csv_reader.position().byte() as usize;
+        self.record_lens = RecordLengths::default();
+        n_records += 1;
+        n_bytes += count_bytes(&record);
+        n_nonempty_fields = n_nonempty_fields.max(count_nonempty_fields(&record));
+        let header_row_types = infer_record_types(&record, &self.type_options);
+        let header_row_details = record
+            .iter()
+            .map(|field| {
+                NumberDetails::of(field, &self.type_options.number_format).unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        let header_row_nulls = (0..field_count)
+            .map(|i| {
+                record
+                    .get(i)
+                    .is_some_and(|field| self.type_options.is_null(field))
+            })
+            .collect::<Vec<_>>();
+        self.top_row = record.iter().map(|field| field.to_string()).collect();
+
+        self.progress.stage(Stage::Types);
+        let mut columns = (0..field_count)
+            .map(|i| ColumnState::new(record.get(i)))
+            .collect::<Vec<_>>();
+        let type_options = &self.type_options;
+        let mut fold_record = |record: &StringRecord| {
+            for (field, column) in record.iter().zip(&mut columns) {
+                column.fold(field, type_options);
             }
         };
-        let mut row_types = vec![TypeGuesses::all(); field_count];
-
-        for record in records_iter {
-            let record = record?;
-            for (i, field) in record.iter().enumerate() {
-                row_types[i] &= infer_types(field);
+        let mut read_all = true;
+        let mut start = csv_reader.position().byte();
+        let mut sequences = vec![Sequence::Empty; field_count];
+        let mut key_hashes = vec![];
+        while csv_reader.read_record(&mut record)? {
+            let end = csv_reader.position().byte();
+            self.record_lens.add((end - start) as usize);
+            start = end;
+            fold_record(&record);
+            for (field, sequence) in record.iter().zip(&mut sequences) {
+                sequence.add(parse_number(field, &type_options.number_format));
+            }
+            if self.detect_primary_keys && key_hashes.len() < MAX_KEY_RECORDS {
+                key_hashes.push(hash_record(&record, type_options));
             }
             n_records += 1;
             n_bytes += count_bytes(&record);
+            n_nonempty_fields = n_nonempty_fields.max(count_nonempty_fields(&record));
             // break if we pass sample size limits
-            match sample_size {
-                SampleSize::Records(recs) => {
-                    if n_records > recs {
-                        break;
+            let limit_reached = match sample_size {
+                SampleSize::TimeBudget(budget) => started.elapsed() > budget,
+                SampleSize::Records(recs) => n_records > recs,
+                SampleSize::Bytes(bytes) => n_bytes > bytes,
+                SampleSize::All => false,
+            };
+            if limit_reached {
+                read_all = false;
+                break;
+            }
+        }
+        self.num_records = read_all.then_some(n_records);
+        self.sequences = sequences;
+        self.key_hashes = key_hashes;
+        match self.sampling {
+            Sampling::Head => {}
+            Sampling::RandomBlocks {
+                num_blocks,
+                block_size,
+            } => {
+                drop(csv_reader);
+                n_records += self.sample_random_blocks(
+                    reader,
+                    num_blocks,
+                    block_size,
+                    field_count,
+                    &mut fold_record,
+                )?;
+            }
+            Sampling::Reservoir { num_records } => {
+                // sample the records following the head uniformly (Algorithm R)
+                let mut reservoir = Vec::with_capacity(num_records);
+                let mut rng = SplitMix64::new(num_records as u64);
+                let mut i = 0;
+                while csv_reader.read_record(&mut record)? {
+                    if reservoir.len() < num_records {
+                        reservoir.push(record.clone());
+                    } else {
+                        let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+                        if j < num_records {
+                            reservoir[j].clone_from(&record);
+                        }
                     }
+                    i += 1;
                 }
-                SampleSize::Bytes(bytes) => {
-                    if n_bytes > bytes {
-                        break;
-                    }
+                for record in &reservoir {
+                    fold_record(record);
+                    n_records += 1;
                 }
-                SampleSize::All => {}
             }
         }
+        self.num_trailing_empty_fields = if n_nonempty_fields > 0 {
+            field_count.saturating_sub(n_nonempty_fields)
+        } else {
+            0
+        };
+
         if n_records == 1 {
             // there's only one row in the whole data file (the top row already parsed),
             // so we're going to assume it's a data row, not a header row.
-            self.has_header_row = Some(false);
-            self.types = get_best_types(header_row_types);
+            if self.has_header_row.is_none() {
+                self.has_header_row = Some(false);
+                self.header_confidence = Some(0.5);
+            }
+            self.type_counts = vec![TypeCounts::default(); field_count];
+            add_top_row_counts(
+                &mut self.type_counts,
+                &self.top_row,
+                &header_row_types,
+                &header_row_details,
+                &self.type_options,
+            );
+            self.types = header_row_types;
+            self.number_details = header_row_details;
+            self.nullable = header_row_nulls;
+            self.levels = vec![Levels::default(); field_count];
             return Ok(());
         }
 
-        if header_row_types
+        let mut row_types = columns
             .iter()
-            .zip(&row_types)
-            .any(|(header, data)| !data.allows(header))
-        {
-            self.has_header_row = Some(true);
+            .map(|column| column.guesses(&self.type_options))
+            .collect::<Vec<_>>();
+        let mut row_details = columns
+            .iter()
+            .map(|column| column.details)
+            .collect::<Vec<_>>();
+        let mut row_nulls = columns
+            .iter()
+            .map(|column| column.nullable)
+            .collect::<Vec<_>>();
+        let mut row_levels = columns
+            .iter()
+            .map(|column| column.levels.clone())
+            .collect::<Vec<_>>();
+        let mut row_counts = columns
+            .iter()
+            .map(|column| column.type_counts.clone())
+            .collect::<Vec<_>>();
+        // the types of hinted columns are known (if the top row has the names of columns hinted by
+        // name, it's most likely a header row)
+        let names = if self.has_header_row == Some(false) {
+            &[]
         } else {
+            &self.top_row[..]
+        };
+        apply_type_hints(&self.type_hints, names, &mut row_types, &mut row_details);
+
+        if let Some(has_header_row) = self.has_header_row {
+            // header row presence was specified, so we only need to fold the top row into the
+            // data types if it isn't a header row
+            if !has_header_row {
+                add_top_row_counts(
+                    &mut row_counts,
+                    &self.top_row,
+                    &header_row_types,
+                    &header_row_details,
+                    &self.type_options,
+                );
+                for ((data, header), counts) in
+                    row_types.iter_mut().zip(&header_row_types).zip(&row_counts)
+                {
+                    *data = if self.type_options.tolerance < 1.0 {
+                        counts.tolerant(self.type_options.tolerance)
+                    } else {
+                        *data & *header
+                    };
+                }
+                for (data, header) in row_details.iter_mut().zip(&header_row_details) {
+                    data.widen(*header);
+                }
+                for (data, header) in row_nulls.iter_mut().zip(&header_row_nulls) {
+                    *data |= *header;
+                }
+                self.add_top_row_levels(&mut row_levels);
+            }
+            self.types = row_types;
+            self.number_details = row_details;
+            self.nullable = row_nulls;
+            self.levels = row_levels;
+            self.type_counts = row_counts;
+            return Ok(());
+        }
+
+        // Only columns whose data is narrower than text can tell a header row apart from a data
+        // row; count those, and how many of them have a top-row value that doesn't fit.
+        let (n_evidence, n_mismatches) = header_row_types.iter().zip(&row_types).fold(
+            (0, 0),
+            |(n_evidence, n_mismatches), (header, data)| {
+                if *data == TypeGuesses::TEXT {
+                    (n_evidence, n_mismatches)
+                } else if data.allows(header) {
+                    (n_evidence + 1, n_mismatches)
+                } else {
+                    (n_evidence + 1, n_mismatches + 1)
+                }
+            },
+        );
+        if n_mismatches > 0 {
+            self.has_header_row = Some(true);
+            self.header_confidence = Some(0.5 + 0.5 * n_mismatches as f64 / n_evidence as f64);
+        } else if n_evidence > 0 {
             self.has_header_row = Some(false);
+            self.header_confidence = Some(0.5 + 0.5 * n_evidence as f64 / field_count as f64);
+        } else {
+            // every column is text, so compare the top-row values to the values below them
+            let (n_header_votes, n_data_votes): (usize, usize) = columns.iter().fold(
+                (0, 0),
+                |(n_header_votes, n_data_votes), column| match column.text_header_vote() {
+                    Some(true) => (n_header_votes + 1, n_data_votes),
+                    Some(false) => (n_header_votes, n_data_votes + 1),
+                    None => (n_header_votes, n_data_votes),
+                },
+            );
+            self.has_header_row = Some(n_header_votes > n_data_votes);
+            self.header_confidence =
+                Some(0.5 + 0.5 * n_header_votes.abs_diff(n_data_votes) as f64 / field_count as f64);
         }
 
-        self.types = get_best_types(row_types);
+        if self.has_header_row == Some(false) {
+            // the top row is data, so its values need to fit
+            for (data, header) in row_details.iter_mut().zip(&header_row_details) {
+                data.widen(*header);
+            }
+            for (data, header) in row_nulls.iter_mut().zip(&header_row_nulls) {
+                *data |= *header;
+            }
+            self.add_top_row_levels(&mut row_levels);
+            add_top_row_counts(
+                &mut row_counts,
+                &self.top_row,
+                &header_row_types,
+                &header_row_details,
+                &self.type_options,
+            );
+        }
+        self.types = row_types;
+        self.number_details = row_details;
+        self.nullable = row_nulls;
+        self.levels = row_levels;
+        self.type_counts = row_counts;
         Ok(())
     }
 
+    // Adds the (non-null) values of the top row to the levels of the columns, when the top row is
+    // a data row.
+    fn add_top_row_levels(&self, levels: &mut [Levels]) {
+        if self.type_options.max_levels == 0 {
+            return;
+        }
+        for (levels, value) in levels.iter_mut().zip(&self.top_row) {
+            if !self.type_options.is_null(value) {
+                levels.add(value, self.type_options.max_levels);
+            }
+        }
+    }
+
+    // Samples records (with the expected number of fields) from blocks at pseudo-random offsets in
+    // the data (after the preamble and header row), realigning each block to line boundaries.
+    // Folds each sampled record, and returns the number of records sampled.
+    fn sample_random_blocks<R, F>(
+        &self,
+        reader: &mut R,
+        num_blocks: usize,
+        block_size: usize,
+        field_count: usize,
+        fold_record: &mut F,
+    ) -> Result<usize>
+    where
+        R: Read + Seek,
+        F: FnMut(&StringRecord),
+    {
+        let terminator = self.get_terminator().byte();
+        reader.seek(SeekFrom::Start(0))?;
+        let data_start =
+            preamble_skipcount(reader, self.num_preamble_rows.unwrap_or(0), terminator)? as u64;
+        let len = reader.seek(SeekFrom::End(0))?;
+        if data_start >= len {
+            return Ok(0);
+        }
+
+        let mut rng = SplitMix64::new(len);
+        let mut offsets = (0..num_blocks)
+            .map(|_| data_start + rng.next_u64() % (len - data_start))
+            .collect::<Vec<_>>();
+        offsets.sort_unstable();
+
+        let mut record = StringRecord::new();
+        let mut n_records = 0;
+        for offset in offsets {
+            reader.seek(SeekFrom::Start(offset))?;
+            let mut block = vec![];
+            reader.take(block_size as u64).read_to_end(&mut block)?;
+            // the block starts and ends in the middle of lines (the first line is dropped even if
+            // the block happens to start at a line boundary, so the header row is never included)
+            let start = match memchr::memchr(terminator, &block) {
+                Some(pos) => pos + 1,
+                None => continue,
+            };
+            if offset + (block.len() as u64) < len {
+                truncate_partial_line(&mut block, &[terminator]);
+            }
+            let mut csv_reader = self.csv_reader_from(&block[start..]);
+            loop {
+                match csv_reader.read_record(&mut record) {
+                    Ok(true) if record.len() == field_count => {
+                        fold_record(&record);
+                        n_records += 1;
+                    }
+                    Ok(true) => {}
+                    Ok(false) => break,
+                    // the block was misaligned (e.g. started within a quoted field)
+                    Err(_) => break,
+                }
+            }
+        }
+        Ok(n_records)
+    }
+
     fn create_csv_reader<'a, R: Read + Seek>(
         &self,
         mut reader: &'a mut R,
-    ) -> Result<Reader<&'a mut R>> {
+    ) -> Result<Reader<FilteredReader<&'a mut R>>> {
         reader.seek(SeekFrom::Start(0))?;
         if let Some(num_preamble_rows) = self.num_preamble_rows {
-            snip_preamble(&mut reader, num_preamble_rows)?;
+            snip_preamble(&mut reader, num_preamble_rows, self.get_terminator().byte())?;
         }
+        Ok(self.csv_reader_from(reader))
+    }
 
+    // Creates a CSV reader (using the dialect details detected so far) over the provided reader,
+    // starting at its current position.
+    fn csv_reader_from<R: Read>(&self, reader: R) -> Reader<FilteredReader<R>> {
         let mut builder = csv::ReaderBuilder::new();
+        builder.terminator(self.get_terminator().into());
+        builder.escape(self.get_escape());
+        builder.comment(self.get_comment());
+        builder.double_quote(self.get_doublequote_escapes());
+        builder.trim(self.get_trim().into());
         if let Some(delim) = self.delimiter {
             builder.delimiter(delim);
         }
-        if let Some(has_header_row) = self.has_header_row {
-            builder.has_headers(has_header_row);
-        }
+        // we need to see the top row in order to determine whether or not it's a header row
+        builder.has_headers(false);
         match self.quote {
             Some(Quote::Some(chr)) => {
                 builder.quoting(true);
@@ -422,18 +2719,72 @@ impl Sniffer {
             builder.flexible(flexible);
         }
 
-        Ok(builder.from_reader(reader))
+        builder.from_reader(
+            FilteredReader::new(
+                reader,
+                self.get_terminator().byte(),
+                self.delimiter.unwrap_or(b','),
+            )
+            .comment(self.get_comment())
+            .skip_blank_lines(self.get_skip_blank_lines())
+            .skip_footer_rows(self.num_footer_rows.unwrap_or(0)),
+        )
     }
 }
 
+// Ranks steady-state chains (given as delimiter, final state, and final probability) from most to
+// least likely, scoring each by its share of the total final probability of all the chains. If
+// probabilities have underflowed to zero, every chain in the best state is considered equally
+// likely.
+fn rank_chains(best_state: usize, mut steady_chains: Vec<(u8, usize, f64)>) -> DelimRanking {
+    // stable sort, so ties are broken the same way as when choosing the best chain
+    steady_chains.sort_by(|a, b| a.1.cmp(&b.1).then(b.2.total_cmp(&a.2)));
+    let total_prob: f64 = steady_chains.iter().map(|&(_, _, prob)| prob).sum();
+    let n_best = steady_chains
+        .iter()
+        .filter(|&&(_, state, _)| state == best_state)
+        .count();
+    steady_chains
+        .into_iter()
+        .map(|(delim, state, prob)| {
+            let score = if total_prob > 0.0 {
+                prob / total_prob
+            } else if state == best_state {
+                1.0 / n_best as f64
+            } else {
+                0.0
+            };
+            (delim, score)
+        })
+        .collect()
+}
+
+// Counts quote-delimiter-quote patterns for the given quote character. Returns the number of
+// patterns found, and the delimiters found between quotes ranked by the share of patterns that
+// used them.
 fn quote_count<R: Read>(
     sample_iter: &mut SampleIter<R>,
     character: char,
     delim: &Option<u8>,
-) -> Result<Option<(usize, u8)>> {
-    let pattern = match *delim {
-        Some(delim) => format!(r#"{}\s*?{}\s*{}"#, character, delim, character),
-        None => format!(r#"{}\s*?(?P<delim>[^\w\n'"`])\s*{}"#, character, character),
+    delim_candidates: &Option<Vec<u8>>,
+) -> Result<Option<(usize, DelimRanking)>> {
+    let pattern = match (*delim, delim_candidates) {
+        (Some(delim), _) => format!(
+            r#"{}\s*?{}\s*{}"#,
+            character,
+            regex::escape(&char::from(delim).to_string()),
+            character
+        ),
+        (None, Some(candidates)) => format!(
+            r#"{}\s*?(?P<delim>[{}])\s*{}"#,
+            character,
+            candidates
+                .iter()
+                .map(|&chr| regex::escape(&char::from(chr).to_string()))
+                .collect::<String>(),
+            character
+        ),
+        (None, None) => format!(r#"{}\s*?(?P<delim>[^\w\n'"`])\s*{}"#, character, character),
     };
     let re = Regex::new(&pattern).unwrap();
 
@@ -458,27 +2809,568 @@ fn quote_count<R: Read>(
 
     // if we already know delimiter, no need to go through map
     if let Some(delim) = *delim {
-        return Ok(Some((count, delim)));
+        return Ok(Some((count, vec![(delim, 1.0)])));
+    }
+
+    // rank the delimiters by count (breaking ties by character, so the result is deterministic)
+    let mut delim_counts = delim_count_map
+        .iter()
+        .map(|(delim, &delim_count)| {
+            assert!(delim.len() == 1);
+            ((delim.as_ref() as &[u8])[0], delim_count)
+        })
+        .collect::<Vec<_>>();
+    delim_counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    // delim_counts should be nonempty; delim should always match at least something
+    assert!(
+        !delim_counts.is_empty(),
+        "invalid regex match: no delimiter found"
+    );
+    Ok(Some((
+        count,
+        delim_counts
+            .into_iter()
+            .map(|(delim, delim_count)| (delim, delim_count as f64 / count as f64))
+            .collect(),
+    )))
+}
+
+// Characters separating groups of thousands in numbers, besides `.` and `,`: spaces (including
+// the no-break spaces used in French).
+const SPACE_SEPARATORS: [char; 3] = [' ', '\u{a0}', '\u{202f}'];
+
+// Finds the separators of a number (possibly with a sign, currency symbol or percent sign): its
+// decimal separator and grouping separator, as far as they can be told apart. Returns `None` for
+// values that aren't numbers, and for numbers that could be read either way (like `1,234`).
+fn number_separators(field: &str) -> Option<(Option<char>, Option<char>)> {
+    let body = field.trim_matches(|chr: char| !chr.is_ascii_digit());
+    if body.is_empty() || field.chars().any(char::is_alphabetic) {
+        return None;
     }
+    let mut groups = vec![];
+    let mut separators = vec![];
+    let mut start = 0;
+    for (i, chr) in body.char_indices() {
+        if chr.is_ascii_digit() {
+            continue;
+        }
+        if chr != '.' && chr != ',' && !SPACE_SEPARATORS.contains(&chr) {
+            return None;
+        }
+        groups.push(&body[start..i]);
+        separators.push(chr);
+        start = i + chr.len_utf8();
+    }
+    groups.push(&body[start..]);
+    // plain integers don't tell the separators apart
+    let &last = separators.last()?;
+    let is_grouped = |groups: &[&str]| {
+        (1..=3).contains(&groups[0].len()) && groups[1..].iter().all(|group| group.len() == 3)
+    };
+    if separators.iter().all(|&separator| separator == last) {
+        if SPACE_SEPARATORS.contains(&last) || separators.len() > 1 {
+            // only a grouping separator
+            return is_grouped(&groups).then_some((None, Some(last)));
+        }
+        // a single `.` or `,`: a decimal separator, unless followed by three digits (and preceded
+        // by a non-zero number of at most three digits)
+        return if groups[1].len() != 3 || groups[0] == "0" || groups[0].len() > 3 {
+            Some((Some(last), None))
+        } else {
+            None
+        };
+    }
+    // a grouping separator, followed by a single decimal separator
+    let grouping = separators[0];
+    let n_grouping = separators.len() - 1;
+    if SPACE_SEPARATORS.contains(&last)
+        || separators[..n_grouping]
+            .iter()
+            .any(|&separator| separator != grouping)
+        || !is_grouped(&groups[..=n_grouping])
+    {
+        return None;
+    }
+    Some((Some(last), Some(grouping)))
+}
+
+// Finds the separator of a number that could be read either way (like `1,234`, or `1.234`): a
+// single `.` or `,` between a non-zero number of at most three digits and three digits. Returns
+// `None` for other values.
+fn ambiguous_separator(field: &str) -> Option<char> {
+    let body = field.trim_matches(|chr: char| !chr.is_ascii_digit());
+    if field.chars().any(char::is_alphabetic) {
+        return None;
+    }
+    let (integer, fraction) = body.split_once(['.', ','])?;
+    let is_digits = |group: &str| group.bytes().all(|chr| chr.is_ascii_digit());
+    let is_ambiguous = (1..=3).contains(&integer.len())
+        && integer != "0"
+        && fraction.len() == 3
+        && is_digits(integer)
+        && is_digits(fraction);
+    // unwrap is safe: the body has a separator after the integer part
+    is_ambiguous.then(|| body[integer.len()..].chars().next().unwrap())
+}
+
+// Splits a line into its fields, each paired with whether or not the field is quoted. Quoted
+// fields are returned without their surrounding quotes (but are otherwise left unescaped).
+fn split_fields(line: &[u8], delim: u8, quote: u8, escape: Option<u8>) -> Vec<(bool, &[u8])> {
+    let mut fields = vec![];
+    let mut start = 0;
+    while start <= line.len() {
+        if line.get(start) == Some(&quote) {
+            // find the closing quote, skipping escaped and doubled quotes
+            let mut end = start + 1;
+            while end < line.len() {
+                if Some(line[end]) == escape {
+                    end += 1;
+                } else if line[end] == quote {
+                    if line.get(end + 1) != Some(&quote) {
+                        break;
+                    }
+                    end += 1;
+                }
+                end += 1;
+            }
+            fields.push((true, &line[start + 1..end.min(line.len())]));
+            start = match memchr::memchr(delim, &line[end.min(line.len())..]) {
+                Some(pos) => end + pos + 1,
+                None => break,
+            };
+        } else {
+            let end = memchr::memchr(delim, &line[start..]).map_or(line.len(), |pos| start + pos);
+            fields.push((false, &line[start..end]));
+            start = end + 1;
+        }
+    }
+    fields
+}
+
+// A value in a field, as used when comparing potential footer rows to the data rows above them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FooterField {
+    Empty,
+    // text with letters that isn't of any other type (e.g. `Total`)
+    Label,
+    Value(TypeGuesses),
+}
+impl FooterField {
+    fn of(field: &[u8], options: &TypeOptions) -> FooterField {
+        let field = String::from_utf8_lossy(field);
+        let field = field.trim();
+        if field.is_empty() {
+            return FooterField::Empty;
+        }
+        let guesses = infer_types(field, options);
+        if guesses == TypeGuesses::TEXT && field.chars().any(char::is_alphabetic) {
+            FooterField::Label
+        } else {
+            FooterField::Value(guesses)
+        }
+    }
+    fn guesses(self) -> Option<TypeGuesses> {
+        match self {
+            FooterField::Empty => None,
+            FooterField::Label => Some(TypeGuesses::TEXT),
+            FooterField::Value(guesses) => Some(guesses),
+        }
+    }
+}
 
-    // find the highest-count delimiter in the map
-    let (delim_count, delim) =
-        delim_count_map
+// Whether or not a row stands out from the (data) rows above it: it has a different number of
+// fields while they all have the same number, or it's empty where they never are, or it has a
+// label where they never have text (e.g. `Total` in a column of dates or amounts).
+fn is_footer_row(row: &[FooterField], reference: &[Vec<FooterField>]) -> bool {
+    let n_fields = reference[0].len();
+    if reference.iter().all(|data| data.len() == n_fields) && row.len() != n_fields {
+        return true;
+    }
+    let max_fields = reference.iter().map(Vec::len).max().unwrap_or(0);
+    (0..max_fields).any(|i| {
+        let column = reference
             .iter()
-            .fold((0, b'\0'), |acc, (delim, &delim_count)| {
-                assert!(delim.len() == 1);
-                if delim_count > acc.0 {
-                    (delim_count, (delim.as_ref() as &[u8])[0])
-                } else {
-                    acc
+            .map(|data| data.get(i).copied().unwrap_or(FooterField::Empty));
+        match row.get(i).copied().unwrap_or(FooterField::Empty) {
+            FooterField::Empty => column.clone().all(|field| field != FooterField::Empty),
+            FooterField::Label => column
+                .filter_map(FooterField::guesses)
+                .reduce(|guesses, value| guesses & value)
+                .is_some_and(|guesses| guesses != TypeGuesses::TEXT),
+            FooterField::Value(_) => false,
+        }
+    })
+}
+
+// Counts the quotes within quoted fields of a line that are escaped by doubling (`""`) and that are
+// escaped using the escape character (`\"`), respectively.
+fn count_quote_escapes(line: &[u8], quote: u8, escape: Option<u8>) -> (usize, usize) {
+    let (mut n_doubled, mut n_escaped) = (0, 0);
+    let mut in_quotes = false;
+    let mut i = 0;
+    while i < line.len() {
+        let chr = line[i];
+        if !in_quotes {
+            in_quotes = chr == quote;
+        } else if Some(chr) == escape {
+            if line.get(i + 1) == Some(&quote) {
+                n_escaped += 1;
+            }
+            i += 1;
+        } else if chr == quote {
+            if line.get(i + 1) == Some(&quote) {
+                n_doubled += 1;
+                i += 1;
+            } else {
+                in_quotes = false;
+            }
+        }
+        i += 1;
+    }
+    (n_doubled, n_escaped)
+}
+
+// Counts the occurrences of each candidate byte in a line (into `freqs`, in the same order as
+// `candidates`), skipping any bytes escaped by the escape character.
+//
+// The line is split into runs of unescaped bytes with `memchr`. For a few candidates, each run is
+// scanned once per candidate with `memchr` (which is vectorized); for many candidates (e.g. the
+// default of all ASCII characters), a single pass building a byte histogram is faster.
+fn candidate_frequencies(line: &[u8], candidates: &[u8], escape: Option<u8>, freqs: &mut [usize]) {
+    freqs.fill(0);
+    let mut count = |run: &[u8]| {
+        if candidates.len() <= MEMCHR_MAX_CANDIDATES {
+            for (freq, &chr) in freqs.iter_mut().zip(candidates) {
+                *freq += memchr::memchr_iter(chr, run).count();
+            }
+        } else {
+            let mut histogram = [0; 256];
+            for &chr in run {
+                histogram[chr as usize] += 1;
+            }
+            for (freq, &chr) in freqs.iter_mut().zip(candidates) {
+                *freq += histogram[chr as usize];
+            }
+        }
+    };
+    let escape = match escape {
+        Some(escape) => escape,
+        None => return count(line),
+    };
+    let mut start = 0;
+    for pos in memchr::memchr_iter(escape, line) {
+        // an escape character that was itself escaped
+        if pos < start {
+            continue;
+        }
+        count(&line[start..pos]);
+        start = pos + 2;
+    }
+    count(&line[start.min(line.len())..]);
+}
+
+// Determines the most common line ending in a sample of raw bytes. If the sample contains no line
+// endings at all, it may use the ASCII record separator character (0x1E) instead.
+fn detect_terminator(sample: &[u8]) -> Terminator {
+    const RECORD_SEPARATOR: u8 = 0x1E;
+    let (mut n_crlf, mut n_lf, mut n_cr) = (0, 0, 0);
+    let mut bytes = sample.iter().peekable();
+    while let Some(&chr) = bytes.next() {
+        match chr {
+            b'\r' if bytes.peek() == Some(&&b'\n') => {
+                bytes.next();
+                n_crlf += 1;
+            }
+            b'\r' => n_cr += 1,
+            b'\n' => n_lf += 1,
+            _ => {}
+        }
+    }
+    if n_crlf == 0 && n_lf == 0 && n_cr == 0 {
+        return if sample.contains(&RECORD_SEPARATOR) {
+            Terminator::Any(RECORD_SEPARATOR)
+        } else {
+            Terminator::CRLF
+        };
+    }
+    if n_crlf >= n_lf && n_crlf >= n_cr {
+        Terminator::CRLF
+    } else if n_lf >= n_cr {
+        Terminator::LF
+    } else {
+        Terminator::CR
+    }
+}
+
+// Running check of whether the values of a column are strictly increasing numbers.
+#[derive(Debug, Clone, Copy)]
+enum Sequence {
+    Empty,
+    Increasing { first: f64, last: f64, count: usize },
+    Broken,
+}
+impl Sequence {
+    // Adds the next value (if it's a number).
+    fn add(&mut self, number: Option<f64>) {
+        *self = match (*self, number) {
+            (Sequence::Empty, Some(number)) => Sequence::Increasing {
+                first: number,
+                last: number,
+                count: 1,
+            },
+            (Sequence::Increasing { first, last, count }, Some(number)) if number > last => {
+                Sequence::Increasing {
+                    first,
+                    last: number,
+                    count: count + 1,
                 }
-            });
+            }
+            _ => Sequence::Broken,
+        };
+    }
+    // Adds a value (if it's a number) before the others.
+    fn prepend(&mut self, number: Option<f64>) {
+        *self = match (*self, number) {
+            (Sequence::Empty, Some(number)) => Sequence::Increasing {
+                first: number,
+                last: number,
+                count: 1,
+            },
+            (Sequence::Increasing { first, last, count }, Some(number)) if number < first => {
+                Sequence::Increasing {
+                    first: number,
+                    last,
+                    count: count + 1,
+                }
+            }
+            _ => Sequence::Broken,
+        };
+    }
+    fn is_increasing(&self) -> bool {
+        matches!(self, Sequence::Increasing { count, .. } if *count >= 2)
+    }
+}
+
+// Whether or not each (numeric) column's values are strictly increasing.
+fn get_increasing(types: &[Type], sequences: &[Sequence]) -> Vec<bool> {
+    types
+        .iter()
+        .enumerate()
+        .map(|(i, &ty)| is_numeric(ty) && sequences.get(i).is_some_and(Sequence::is_increasing))
+        .collect()
+}
 
-    // delim_count should be nonzero; delim should always match at least something
-    assert_ne!(delim_count, 0, "invalid regex match: no delimiter found");
-    Ok(Some((count, delim)))
+// Running statistics of the lengths (in bytes) of records.
+#[derive(Debug, Clone, Copy, Default)]
+struct RecordLengths {
+    count: usize,
+    total: usize,
+    max: usize,
+}
+impl RecordLengths {
+    fn add(&mut self, len: usize) {
+        self.count += 1;
+        self.total += len;
+        self.max = self.max.max(len);
+    }
+    fn mean(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.total as f64 / self.count as f64
+        }
+    }
 }
 
 fn count_bytes(record: &StringRecord) -> usize {
     record.iter().fold(0, |acc, field| acc + field.len())
 }
+
+// Running state of a column's values below the top row, updated one value at a time.
+struct ColumnState {
+    // the types that every value fits
+    types: TypeGuesses,
+    // the column's value in the top row (if the top row has one)
+    top: Option<String>,
+    // whether any value equals the top-row value
+    has_top_value: bool,
+    // the range of the values' lengths (in characters)
+    len_range: Option<(usize, usize)>,
+    // the details of the numeric values
+    details: NumberDetails,
+    // whether any value is null
+    nullable: bool,
+    // the distinct values (if categorical columns are detected)
+    levels: Levels,
+    // the numbers of values of each type
+    type_counts: TypeCounts,
+}
+impl ColumnState {
+    fn new(top: Option<&str>) -> ColumnState {
+        ColumnState {
+            types: TypeGuesses::all(),
+            top: top.map(|top| top.to_string()),
+            has_top_value: false,
+            len_range: None,
+            details: NumberDetails::default(),
+            nullable: false,
+            levels: Levels::default(),
+            type_counts: TypeCounts::default(),
+        }
+    }
+
+    fn fold(&mut self, value: &str, options: &TypeOptions) {
+        let guesses = infer_types(value, options);
+        self.types &= guesses;
+        if options.is_null(value) {
+            self.nullable = true;
+        } else if options.max_levels > 0 {
+            self.levels.add(value, options.max_levels);
+        }
+        let details = NumberDetails::of(value, &options.number_format).unwrap_or_default();
+        self.details.widen(details);
+        self.type_counts.add(value, guesses, details, options);
+        if self.top.as_deref() == Some(value) {
+            self.has_top_value = true;
+        }
+        let len = value.chars().count();
+        self.len_range = Some(match self.len_range {
+            Some((min_len, max_len)) => (min_len.min(len), max_len.max(len)),
+            None => (len, len),
+        });
+    }
+
+    // The types that the values fit (or that at least the tolerated share of the values fit).
+    fn guesses(&self, options: &TypeOptions) -> TypeGuesses {
+        if options.tolerance < 1.0 {
+            self.type_counts.tolerant(options.tolerance)
+        } else {
+            self.types
+        }
+    }
+
+    // Decides whether the top-row value of a text column looks like a column label
+    // (`Some(true)`), like a data value (`Some(false)`), or whether there's no telling (`None`).
+    // Empty values and values that repeat below look like data; values whose length is outside the
+    // range of the lengths below look like labels.
+    fn text_header_vote(&self) -> Option<bool> {
+        let top = self.top.as_deref()?;
+        if top.is_empty() || self.has_top_value {
+            return Some(false);
+        }
+        let (min_len, max_len) = self.len_range?;
+        let top_len = top.chars().count();
+        if top_len < min_len || top_len > max_len {
+            Some(true)
+        } else {
+            None
+        }
+    }
+}
+
+// Counts the values of the top row in the type counts of the columns, when the top row is a data
+// row.
+fn add_top_row_counts(
+    counts: &mut [TypeCounts],
+    top_row: &[String],
+    top_row_types: &[TypeGuesses],
+    top_row_details: &[NumberDetails],
+    options: &TypeOptions,
+) {
+    for (((counts, value), &guesses), &details) in counts
+        .iter_mut()
+        .zip(top_row)
+        .zip(top_row_types)
+        .zip(top_row_details)
+    {
+        counts.add(value, guesses, details, options);
+    }
+}
+
+// Whether or not an error is a failure to sniff the data (rather than a failure to read it, or a
+// cancellation, which surfaces as a read error).
+fn is_sniffing_failure(err: &SnifferError) -> bool {
+    match err {
+        SnifferError::SniffingFailed(_) => true,
+        SnifferError::Csv(err) => !matches!(err.kind(), csv::ErrorKind::Io(_)),
+        _ => false,
+    }
+}
+
+// Scales the estimated number of records in a sample (of the provided number of bytes) from the
+// start of a file up to the whole file (of the provided length).
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+fn scale_num_records(metadata: &mut Metadata, n_sample_bytes: usize, len: u64) {
+    let n_sample_data_bytes = (n_sample_bytes as u64).saturating_sub(metadata.data_offset);
+    let n_data_bytes = len.saturating_sub(metadata.data_offset);
+    metadata.estimated_num_records = metadata
+        .estimated_num_records
+        .filter(|_| n_sample_data_bytes > 0)
+        .map(|n| (n as f64 * n_data_bytes as f64 / n_sample_data_bytes as f64).round() as usize);
+}
+
+// Narrows the guesses (and details) of the columns with type hints to their hinted types (among
+// columns with the provided names, empty if there's no header row).
+fn apply_type_hints(
+    hints: &[(Column, Type)],
+    names: &[String],
+    guesses: &mut [TypeGuesses],
+    details: &mut [NumberDetails],
+) {
+    for (i, (guess, details)) in guesses.iter_mut().zip(details).enumerate() {
+        if let Some(ty) = type_hint(hints, i, names) {
+            *guess = guess.hinted(ty);
+            *details = NumberDetails::of_type(ty);
+        }
+    }
+}
+
+// The hinted type of the column at the provided index (among columns with the provided names, empty
+// if there's no header row), if any. Later hints take precedence over earlier ones.
+fn type_hint(hints: &[(Column, Type)], index: usize, names: &[String]) -> Option<Type> {
+    hints
+        .iter()
+        .rev()
+        .find(|(column, _)| column.is(index, names))
+        .map(|&(_, ty)| ty)
+}
+
+// The types of the columns with the provided guesses (and details), along with the levels of each
+// `Type::Categorical` column (empty for other columns). Text columns with few distinct values are
+// categorical, unless they're hinted otherwise (among columns with the provided names).
+fn get_column_types(
+    guesses: &[TypeGuesses],
+    details: &[NumberDetails],
+    levels: &[Levels],
+    hints: &[(Column, Type)],
+    names: &[String],
+) -> (Vec<Type>, Vec<Vec<String>>) {
+    let mut types = get_best_types(guesses, details);
+    let mut column_levels = vec![vec![]; types.len()];
+    for (i, ty) in types.iter_mut().enumerate() {
+        let hint = type_hint(hints, i, names);
+        let categories = levels.get(i).and_then(Levels::categories);
+        match (hint, categories) {
+            (Some(hint @ Type::Categorical { .. }), _) => *ty = hint,
+            (None, Some(categories)) if *ty == Type::Text => {
+                *ty = Type::Categorical {
+                    cardinality: categories.len(),
+                }
+            }
+            _ => continue,
+        }
+        if let Some(categories) = categories {
+            column_levels[i] = categories.iter().cloned().collect();
+        }
+    }
+    (types, column_levels)
+}
+
+// Counts the fields in a record up to (and including) the last non-empty one.
+fn count_nonempty_fields(record: &StringRecord) -> usize {
+    (0..record.len())
+        .rev()
+        .find(|&i| !record[i].is_empty())
+        .map_or(0, |i| i + 1)
+}
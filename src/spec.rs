@@ -0,0 +1,238 @@
+/*!
+Parsing and formatting of compact textual dialect specifications (see
+[`Dialect::from_spec`](../metadata/struct.Dialect.html#method.from_spec)).
+*/
+use crate::error::{Result, SnifferError};
+use crate::metadata::{Comment, Dialect, Encoding, Escape, Quote, QuoteStyle, Terminator, Trim};
+
+fn invalid(key: &str, value: &str) -> SnifferError {
+    SnifferError::DialectSpec(format!("invalid value `{}` for `{}`", value, key))
+}
+
+// The `key=value` pairs of a specification. Values start with the character following the `=`
+// (even if it's a `;`, e.g. `delim=;`) and end at the next `;`.
+fn pairs(spec: &str) -> Result<Vec<(&str, &str)>> {
+    let mut pairs = vec![];
+    let mut rest = spec.trim();
+    while !rest.is_empty() {
+        let (key, value) = rest.split_once('=').ok_or_else(|| {
+            SnifferError::DialectSpec(format!("expected `key=value`, found `{}`", rest))
+        })?;
+        let first_len = value.chars().next().map_or(0, char::len_utf8);
+        let end = value[first_len..]
+            .find(';')
+            .map_or(value.len(), |end| first_len + end);
+        pairs.push((key.trim(), &value[..end]));
+        rest = value[end..].strip_prefix(';').unwrap_or("").trim_start();
+    }
+    Ok(pairs)
+}
+
+fn byte(key: &str, value: &str) -> Result<u8> {
+    match value {
+        "tab" => Ok(b'\t'),
+        _ => match value.as_bytes() {
+            &[byte] => Ok(byte),
+            _ => Err(invalid(key, value)),
+        },
+    }
+}
+
+fn optional_byte(key: &str, value: &str) -> Result<Option<u8>> {
+    match value {
+        "none" => Ok(None),
+        _ => byte(key, value).map(Some),
+    }
+}
+
+fn boolean(key: &str, value: &str) -> Result<bool> {
+    match value {
+        "yes" | "true" => Ok(true),
+        "no" | "false" => Ok(false),
+        _ => Err(invalid(key, value)),
+    }
+}
+
+fn count(key: &str, value: &str) -> Result<usize> {
+    value.parse().map_err(|_| invalid(key, value))
+}
+
+/// The dialect described by the provided specification, with the settings it doesn't mention
+/// taken from [`Dialect::excel`](../metadata/struct.Dialect.html#method.excel).
+pub(crate) fn parse(spec: &str) -> Result<Dialect> {
+    let mut dialect = Dialect::excel();
+    for (key, value) in pairs(spec)? {
+        match key {
+            "delim" => dialect.delimiter = byte(key, value)?,
+            "quote" => {
+                dialect.quote = optional_byte(key, value)?.map_or(Quote::None, Quote::Some);
+            }
+            "escape" => {
+                dialect.escape =
+                    optional_byte(key, value)?.map_or(Escape::Disabled, Escape::Enabled);
+            }
+            "comment" => {
+                dialect.comment =
+                    optional_byte(key, value)?.map_or(Comment::Disabled, Comment::Enabled);
+            }
+            "doublequote" => dialect.doublequote_escapes = boolean(key, value)?,
+            "header" => dialect.header.has_header_row = boolean(key, value)?,
+            "preamble" => dialect.header.num_preamble_rows = count(key, value)?,
+            "footer" => dialect.header.num_footer_rows = count(key, value)?,
+            "flexible" => dialect.flexible = boolean(key, value)?,
+            "terminator" => {
+                dialect.terminator = match value {
+                    "crlf" => Terminator::CRLF,
+                    "lf" => Terminator::LF,
+                    "cr" => Terminator::CR,
+                    _ => Terminator::Any(byte(key, value)?),
+                };
+            }
+            "quote_style" => {
+                dialect.quote_style = match value {
+                    "always" => QuoteStyle::Always,
+                    "necessary" => QuoteStyle::Necessary,
+                    "nonnumeric" => QuoteStyle::NonNumeric,
+                    _ => return Err(invalid(key, value)),
+                };
+            }
+            "trailing_empty" => dialect.num_trailing_empty_fields = count(key, value)?,
+            "trim" => {
+                dialect.trim = match value {
+                    "none" => Trim::None,
+                    "headers" => Trim::Headers,
+                    "fields" => Trim::Fields,
+                    "all" => Trim::All,
+                    _ => return Err(invalid(key, value)),
+                };
+            }
+            "skip_blank" => dialect.skip_blank_lines = boolean(key, value)?,
+            "encoding" => {
+                dialect.encoding = match value {
+                    "utf-8" => Encoding::Utf8,
+                    "utf-16le" => Encoding::Utf16Le,
+                    "utf-16be" => Encoding::Utf16Be,
+                    "windows-1252" => Encoding::Windows1252,
+                    _ => return Err(invalid(key, value)),
+                };
+            }
+            _ => {
+                return Err(SnifferError::DialectSpec(format!("unknown key `{}`", key)));
+            }
+        }
+    }
+    Ok(dialect)
+}
+
+fn format_byte(byte: u8) -> String {
+    match byte {
+        b'\t' => "tab".into(),
+        _ => char::from(byte).to_string(),
+    }
+}
+
+fn format_optional_byte(byte: Option<u8>) -> String {
+    byte.map_or_else(|| "none".into(), format_byte)
+}
+
+fn format_boolean(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// Every setting of the provided dialect, as its key and value in a specification.
+pub(crate) fn settings(dialect: &Dialect) -> Vec<(&'static str, String)> {
+    vec![
+        ("delim", format_byte(dialect.delimiter)),
+        (
+            "quote",
+            format_optional_byte(match dialect.quote {
+                Quote::Some(chr) => Some(chr),
+                Quote::None => None,
+            }),
+        ),
+        (
+            "header",
+            format_boolean(dialect.header.has_header_row).into(),
+        ),
+        ("preamble", dialect.header.num_preamble_rows.to_string()),
+        ("footer", dialect.header.num_footer_rows.to_string()),
+        (
+            "escape",
+            format_optional_byte(dialect.escape.clone().into()),
+        ),
+        (
+            "comment",
+            format_optional_byte(dialect.comment.clone().into()),
+        ),
+        (
+            "doublequote",
+            format_boolean(dialect.doublequote_escapes).into(),
+        ),
+        ("flexible", format_boolean(dialect.flexible).into()),
+        (
+            "terminator",
+            match dialect.terminator {
+                Terminator::CRLF => "crlf".into(),
+                Terminator::LF => "lf".into(),
+                Terminator::CR => "cr".into(),
+                Terminator::Any(chr) => format_byte(chr),
+            },
+        ),
+        (
+            "quote_style",
+            match dialect.quote_style {
+                QuoteStyle::Always => "always",
+                QuoteStyle::Necessary => "necessary",
+                QuoteStyle::NonNumeric => "nonnumeric",
+            }
+            .into(),
+        ),
+        (
+            "trailing_empty",
+            dialect.num_trailing_empty_fields.to_string(),
+        ),
+        (
+            "trim",
+            match dialect.trim {
+                Trim::None => "none",
+                Trim::Headers => "headers",
+                Trim::Fields => "fields",
+                Trim::All => "all",
+            }
+            .into(),
+        ),
+        (
+            "skip_blank",
+            format_boolean(dialect.skip_blank_lines).into(),
+        ),
+        (
+            "encoding",
+            match dialect.encoding {
+                Encoding::Utf8 => "utf-8",
+                Encoding::Utf16Le => "utf-16le",
+                Encoding::Utf16Be => "utf-16be",
+                Encoding::Windows1252 => "windows-1252",
+            }
+            .into(),
+        ),
+    ]
+}
+
+/// The specification of the provided dialect: its delimiter, quote character, header row and
+/// number of preamble rows, followed by the settings that differ from
+/// [`Dialect::excel`](../metadata/struct.Dialect.html#method.excel).
+pub(crate) fn format(dialect: &Dialect) -> String {
+    let defaults = settings(&Dialect::excel());
+    settings(dialect)
+        .into_iter()
+        .zip(defaults)
+        .enumerate()
+        .filter(|(i, (setting, default))| *i < 4 || setting != default)
+        .map(|(_, ((key, value), _))| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join(";")
+}
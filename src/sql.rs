@@ -0,0 +1,148 @@
+/*!
+Generation of SQL `CREATE TABLE` statements from the sniffed metadata (see
+[`Metadata::to_sql_ddl`](../metadata/struct.Metadata.html#method.to_sql_ddl)).
+*/
+use crate::field_type::Type;
+use crate::metadata::Metadata;
+
+// Maximum precision of MySQL decimals.
+const MYSQL_MAX_DECIMAL_PRECISION: usize = 65;
+// Maximum scale of MySQL decimals.
+const MYSQL_MAX_DECIMAL_SCALE: usize = 30;
+// Maximum length of MySQL `VARCHAR` columns (in characters, so that rows of 4-byte characters
+// stay within the 65535-byte row limit).
+const MYSQL_MAX_VARCHAR_LEN: usize = 16383;
+// Maximum precision of PostgreSQL decimals.
+const POSTGRES_MAX_DECIMAL_PRECISION: usize = 1000;
+
+/// The SQL dialect of the statements generated by
+/// [`Metadata::to_sql_ddl`](struct.Metadata.html#method.to_sql_ddl).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SqlDialect {
+    /// PostgreSQL.
+    Postgres,
+    /// MySQL (and MariaDB).
+    MySql,
+    /// SQLite.
+    Sqlite,
+    /// Standard SQL.
+    Ansi,
+}
+impl SqlDialect {
+    // Quotes an identifier (a table or column name).
+    fn quote(&self, name: &str) -> String {
+        match *self {
+            SqlDialect::MySql => format!("`{}`", name.replace('`', "``")),
+            _ => format!("\"{}\"", name.replace('"', "\"\"")),
+        }
+    }
+
+    // The type of text columns, sized to the provided maximum length (in characters), if known.
+    fn text_type(&self, max_len: Option<usize>) -> String {
+        match (*self, max_len) {
+            (SqlDialect::Sqlite, _) => "TEXT".into(),
+            (SqlDialect::MySql, Some(len)) if len <= MYSQL_MAX_VARCHAR_LEN => {
+                format!("VARCHAR({})", len.max(1))
+            }
+            (SqlDialect::Postgres | SqlDialect::Ansi, Some(len)) => {
+                format!("VARCHAR({})", len.max(1))
+            }
+            (SqlDialect::Ansi, _) => "CLOB".into(),
+            _ => "TEXT".into(),
+        }
+    }
+
+    // The SQL type of a column of the provided type (and date-time format, if any), with values of
+    // at most the provided length (in characters), if known. Values that aren't plain numbers
+    // (e.g. amounts with currency symbols, or percentages) are kept as text.
+    fn column_type(&self, ty: Type, format: Option<&str>, max_len: Option<usize>) -> String {
+        use SqlDialect::*;
+        match (ty, *self) {
+            (Type::Unsigned, MySql) => "BIGINT UNSIGNED".into(),
+            (Type::Unsigned | Type::Signed, Sqlite) => "INTEGER".into(),
+            (Type::Unsigned | Type::Signed, _) => "BIGINT".into(),
+            (Type::BigInt, Sqlite) => self.text_type(max_len),
+            (Type::BigInt, MySql) => "DECIMAL(39, 0)".into(),
+            (Type::BigInt, _) => "NUMERIC(39, 0)".into(),
+            (Type::Boolean, _) => "BOOLEAN".into(),
+            (Type::Float, MySql) => "DOUBLE".into(),
+            (Type::Float, Sqlite) => "REAL".into(),
+            (Type::Float, _) => "DOUBLE PRECISION".into(),
+            (Type::Decimal { precision, scale }, MySql)
+                if precision <= MYSQL_MAX_DECIMAL_PRECISION && scale <= MYSQL_MAX_DECIMAL_SCALE =>
+            {
+                format!("DECIMAL({}, {})", precision, scale)
+            }
+            (Type::Decimal { .. }, MySql) => "DOUBLE".into(),
+            (Type::Decimal { .. }, Sqlite) => "NUMERIC".into(),
+            (Type::Decimal { precision, .. }, Postgres)
+                if precision > POSTGRES_MAX_DECIMAL_PRECISION =>
+            {
+                "NUMERIC".into()
+            }
+            (Type::Decimal { precision, scale }, _) => format!("NUMERIC({}, {})", precision, scale),
+            (Type::DateTime | Type::Date | Type::Time, Sqlite) => "TEXT".into(),
+            (Type::DateTime, MySql) => "DATETIME".into(),
+            (Type::DateTime, _) => {
+                let has_offset =
+                    format.is_some_and(|format| format.contains('z') || format.ends_with('Z'));
+                if has_offset {
+                    "TIMESTAMP WITH TIME ZONE".into()
+                } else {
+                    "TIMESTAMP".into()
+                }
+            }
+            (Type::Date, _) => "DATE".into(),
+            (Type::Time, _) => "TIME".into(),
+            (Type::Uuid, Postgres) => "UUID".into(),
+            (Type::IpAddr, Postgres) => "INET".into(),
+            (Type::Json, Postgres) => "JSONB".into(),
+            (Type::Json, MySql) => "JSON".into(),
+            (
+                Type::Text
+                | Type::Currency { .. }
+                | Type::Percentage
+                | Type::Null
+                | Type::Uuid
+                | Type::IpAddr
+                | Type::Json
+                | Type::NumericString
+                | Type::Hex
+                | Type::Binary { .. }
+                | Type::Categorical { .. },
+                _,
+            ) => self.text_type(max_len),
+        }
+    }
+}
+
+/// The `CREATE TABLE` statement of a table with the provided name and the fields of the provided
+/// metadata.
+pub(crate) fn create_table(metadata: &Metadata, dialect: SqlDialect, table: &str) -> String {
+    let columns = metadata
+        .types
+        .iter()
+        .enumerate()
+        .map(|(i, &ty)| {
+            let format = metadata.formats.get(i).and_then(|format| format.as_deref());
+            let max_len = metadata
+                .lengths
+                .get(i)
+                .and_then(|lengths| lengths.map(|lengths| lengths.max_chars));
+            // null values can't be ruled out from a sample
+            let nullable =
+                metadata.verification.is_none() || metadata.nullable.get(i) != Some(&false);
+            format!(
+                "    {} {}{}",
+                dialect.quote(&metadata.field_name(i)),
+                dialect.column_type(ty, format, max_len),
+                if nullable { "" } else { " NOT NULL" }
+            )
+        })
+        .collect::<Vec<_>>();
+    format!(
+        "CREATE TABLE {} (\n{}\n);\n",
+        dialect.quote(table),
+        columns.join(",\n")
+    )
+}
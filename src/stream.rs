@@ -0,0 +1,245 @@
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+use std::io::SeekFrom;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::time::Instant;
+
+use crate::{
+    compression::{detect_compression, DecompressReader},
+    error::Result,
+    metadata::Compression,
+    sample::SampleSize,
+};
+
+// Maximum number of bytes buffered from a stream when sampling within a time budget.
+const MAX_TIME_BUDGET_BYTES: usize = 1 << 22;
+
+// The (decompressed) rest of a stream, with the bytes read while detecting the compression format
+// put back in front.
+type StreamRest<R> = BufReader<DecompressReader<io::Chain<Cursor<Vec<u8>>, R>>>;
+
+/// A reader over a stream whose start was buffered for sniffing. Reads the rest of the buffered
+/// sample first, then the rest of the stream. Created by
+/// [`Sniffer::open_stream`](../struct.Sniffer.html#method.open_stream).
+pub struct StreamReader<R: Read> {
+    inner: io::Chain<Cursor<Vec<u8>>, StreamRest<R>>,
+}
+impl<R: Read> Read for StreamReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+/// A buffer collecting a sample from the start of a stream, fed chunks of the stream until it's
+/// full. The sample ends at the end of a line (ended by any of the provided terminator bytes, or a
+/// CRLF).
+pub(crate) struct SampleBuffer {
+    sample_size: SampleSize,
+    terminators: Vec<u8>,
+    // whether or not to keep sampling until the end of compressed streams (which can't be
+    // decompressed from a partial sample)
+    whole_if_compressed: bool,
+    // whether or not a byte sample size is a hard limit (instead of being extended to the end of
+    // the line)
+    strict: bool,
+    bytes: Vec<u8>,
+    n_lines: usize,
+    // whether or not the sample currently ends at the end of a line
+    at_line_end: bool,
+    // whether or not the sample ends in a CR that may yet be followed by an LF
+    pending_cr: bool,
+    started: Instant,
+}
+impl SampleBuffer {
+    pub(crate) fn new(sample_size: SampleSize, terminators: Vec<u8>) -> SampleBuffer {
+        SampleBuffer {
+            sample_size,
+            terminators,
+            whole_if_compressed: false,
+            strict: false,
+            bytes: vec![],
+            n_lines: 0,
+            at_line_end: true,
+            pending_cr: false,
+            started: Instant::now(),
+        }
+    }
+    #[cfg_attr(not(any(feature = "tokio", feature = "futures-io")), allow(dead_code))]
+    pub(crate) fn whole_if_compressed(mut self, whole_if_compressed: bool) -> SampleBuffer {
+        self.whole_if_compressed = whole_if_compressed;
+        self
+    }
+
+    pub(crate) fn strict(mut self, strict: bool) -> SampleBuffer {
+        self.strict = strict;
+        self
+    }
+
+    /// Whether or not the sample is complete.
+    pub(crate) fn is_full(&self) -> bool {
+        if self.is_capped() {
+            return true;
+        }
+        if self.pending_cr || !self.at_line_end {
+            return false;
+        }
+        if self.whole_if_compressed
+            && (self.bytes.len() < 6 || detect_compression(&self.bytes) != Compression::None)
+        {
+            return false;
+        }
+        match self.sample_size {
+            SampleSize::Records(n_records) => self.n_lines >= n_records,
+            SampleSize::Bytes(n_bytes) => self.bytes.len() >= n_bytes,
+            SampleSize::All => false,
+            // leave (at least) half of the budget for sniffing the sample
+            SampleSize::TimeBudget(budget) => {
+                self.bytes.len() >= MAX_TIME_BUDGET_BYTES || self.started.elapsed() >= budget / 2
+            }
+        }
+    }
+
+    /// Adds a chunk of the stream to the sample. Returns the number of bytes of the chunk that were
+    /// used (the rest of the chunk follows the end of the sample).
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> usize {
+        for (i, &chr) in chunk.iter().enumerate() {
+            if self.pending_cr {
+                self.pending_cr = false;
+                self.end_line();
+                if chr == b'\n' {
+                    self.bytes.push(chr);
+                    continue;
+                }
+            }
+            if self.is_full() {
+                return i;
+            }
+            self.bytes.push(chr);
+            self.at_line_end = false;
+            if chr == b'\r'
+                && self.terminators.contains(&b'\r')
+                && self.terminators.contains(&b'\n')
+            {
+                self.pending_cr = true;
+            } else if self.terminators.contains(&chr) {
+                self.end_line();
+            }
+        }
+        chunk.len()
+    }
+    fn end_line(&mut self) {
+        self.n_lines += 1;
+        self.at_line_end = true;
+    }
+
+    // Whether or not the sample has reached its hard size limit (if any).
+    fn is_capped(&self) -> bool {
+        match self.sample_size {
+            SampleSize::Bytes(n_bytes) => self.strict && self.bytes.len() >= n_bytes,
+            _ => false,
+        }
+    }
+
+    pub(crate) fn into_bytes(mut self) -> Vec<u8> {
+        if self.is_capped() {
+            truncate_partial_line(&mut self.bytes, &self.terminators);
+        }
+        self.bytes
+    }
+}
+
+/// Drops the partial line (if any) at the end of a sample that was cut off at an arbitrary byte, so
+/// that the sample ends at the end of a line (ended by any of the provided terminator bytes).
+pub(crate) fn truncate_partial_line(sample: &mut Vec<u8>, terminators: &[u8]) {
+    if let Some(last_end) = sample.iter().rposition(|chr| terminators.contains(chr)) {
+        sample.truncate(last_end + 1);
+    }
+}
+
+/// A sample of (decompressed) bytes buffered from the start of a stream, along with the rest of
+/// the stream.
+pub(crate) struct StreamSample<R: Read> {
+    pub(crate) sample: Vec<u8>,
+    pub(crate) compression: Compression,
+    // whether or not the sample holds the whole stream
+    pub(crate) complete: bool,
+    rest: StreamRest<R>,
+}
+impl<R: Read> StreamSample<R> {
+    /// Buffers a sample of the provided size from the start of the stream, ending the sample at the
+    /// end of a line (ended by any of the provided terminator bytes).
+    pub(crate) fn take(mut reader: R, mut sample: SampleBuffer) -> Result<StreamSample<R>> {
+        let mut magic = vec![];
+        (&mut reader).take(6).read_to_end(&mut magic)?;
+        let compression = detect_compression(&magic);
+        let mut rest = BufReader::new(DecompressReader::new(
+            Cursor::new(magic).chain(reader),
+            compression,
+        )?);
+
+        while !sample.is_full() {
+            let chunk = rest.fill_buf()?;
+            if chunk.is_empty() {
+                break;
+            }
+            let n_used = sample.push(chunk);
+            rest.consume(n_used);
+        }
+        Ok(StreamSample {
+            sample: sample.into_bytes(),
+            compression,
+            complete: rest.fill_buf()?.is_empty(),
+            rest,
+        })
+    }
+
+    /// Converts the sample back into a reader over the whole stream, starting at the provided
+    /// offset within the sample.
+    pub(crate) fn into_reader(self, offset: u64) -> StreamReader<R> {
+        let mut sample = Cursor::new(self.sample);
+        sample.set_position(offset);
+        StreamReader {
+            inner: sample.chain(self.rest),
+        }
+    }
+}
+
+/// An asynchronous, seekable reader that a sample is read from, independent of the runtime (see
+/// [`Sniffer::sniff_async`](../struct.Sniffer.html#method.sniff_async) and
+/// [`Sniffer::sniff_futures`](../struct.Sniffer.html#method.sniff_futures)).
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+pub(crate) trait AsyncSource {
+    async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+    async fn seek(&mut self, pos: SeekFrom) -> io::Result<u64>;
+}
+
+/// A [`tokio`](https://docs.rs/tokio) reader, as an `AsyncSource`.
+#[cfg(feature = "tokio")]
+pub(crate) struct TokioSource<R>(pub(crate) R);
+#[cfg(feature = "tokio")]
+impl<R> AsyncSource for TokioSource<R>
+where
+    R: tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        tokio::io::AsyncReadExt::read(&mut self.0, buf).await
+    }
+    async fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        tokio::io::AsyncSeekExt::seek(&mut self.0, pos).await
+    }
+}
+
+/// A [`futures-io`](https://docs.rs/futures-io) reader, as an `AsyncSource`.
+#[cfg(feature = "futures-io")]
+pub(crate) struct FuturesSource<R>(pub(crate) R);
+#[cfg(feature = "futures-io")]
+impl<R> AsyncSource for FuturesSource<R>
+where
+    R: futures_util::io::AsyncRead + futures_util::io::AsyncSeek + Unpin,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        futures_util::io::AsyncReadExt::read(&mut self.0, buf).await
+    }
+    async fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        futures_util::io::AsyncSeekExt::seek(&mut self.0, pos).await
+    }
+}
@@ -0,0 +1,238 @@
+/*!
+Validation of a file against the metadata expected of it (see
+[`Sniffer::validate_reader`](../struct.Sniffer.html#method.validate_reader)).
+*/
+use std::fmt;
+use std::io::{Read, Seek};
+
+use crate::error::Result;
+use crate::field_type::{infer_types, NumberDetails, Type, TypeGuesses, TypeOptions};
+use crate::metadata::{Metadata, ReadOptions};
+
+// Maximum number of violations listed in a validation (all of them are counted).
+const MAX_VIOLATIONS: usize = 100;
+
+/// A way in which a file doesn't conform to the metadata expected of it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// The file has a different number of preamble rows than expected.
+    Preamble {
+        /// Expected number of preamble rows.
+        expected: usize,
+        /// Number of preamble rows detected in the file.
+        found: usize,
+    },
+    /// The header row has different field names than expected.
+    Header {
+        /// Expected field names.
+        expected: Vec<String>,
+        /// Field names in the header row of the file.
+        found: Vec<String>,
+    },
+    /// A record has a different number of fields than expected (or more fields than expected,
+    /// for flexible dialects).
+    FieldCount {
+        /// Index of the record (among the data records, starting at 0).
+        record: usize,
+        /// Expected number of fields.
+        expected: usize,
+        /// Number of fields of the record.
+        found: usize,
+    },
+    /// A value doesn't fit the type (and format) of its field, or is null in a field expected not
+    /// to have null values (as verified, see
+    /// [`Sniffer::verify`](../struct.Sniffer.html#method.verify)).
+    Type {
+        /// Index of the record (among the data records, starting at 0).
+        record: usize,
+        /// Name of the field.
+        field: String,
+        /// The value.
+        value: String,
+        /// Expected type of the field.
+        expected: Type,
+    },
+}
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Violation::Preamble { expected, found } => {
+                write!(f, "{} preamble rows found, {} expected", found, expected)
+            }
+            Violation::Header {
+                ref expected,
+                ref found,
+            } => write!(f, "header row {:?}, {:?} expected", found, expected),
+            Violation::FieldCount {
+                record,
+                expected,
+                found,
+            } => write!(
+                f,
+                "record {}: {} fields, {} expected",
+                record, found, expected
+            ),
+            Violation::Type {
+                record,
+                ref field,
+                ref value,
+                expected,
+            } => write!(
+                f,
+                "record {}: value {:?} of field {} isn't {}",
+                record, value, field, expected
+            ),
+        }
+    }
+}
+
+/// Results of validating a file against the metadata expected of it, generated by
+/// [`Sniffer::validate_reader`](../struct.Sniffer.html#method.validate_reader).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Validation {
+    /// Number of records checked (not including the header row).
+    pub num_records: usize,
+    /// Number of records with a different number of fields than expected.
+    pub num_field_count_violations: usize,
+    /// Number of values that don't fit the type of their field.
+    pub num_type_violations: usize,
+    /// The [`Violation`](enum.Violation.html)s found (up to 100, in the order found).
+    pub violations: Vec<Violation>,
+}
+impl Validation {
+    /// Whether or not the file conforms to the expected metadata.
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+    fn add(&mut self, violation: Violation) {
+        if self.violations.len() < MAX_VIOLATIONS {
+            self.violations.push(violation);
+        }
+    }
+}
+impl fmt::Display for Validation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Validation:")?;
+        writeln!(f, "\tRecords checked: {}", self.num_records)?;
+        writeln!(
+            f,
+            "\tField count violations: {}",
+            self.num_field_count_violations
+        )?;
+        writeln!(f, "\tType violations: {}", self.num_type_violations)?;
+        writeln!(f, "\tViolations:")?;
+        for violation in &self.violations {
+            writeln!(f, "\t\t{}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+/// Streams the records of the provided reader (positioned at the start of the file), checking
+/// them against the expected metadata. The number of preamble rows detected in the file, if any,
+/// is checked as well.
+pub(crate) fn validate<R: Read + Seek>(
+    reader: R,
+    expected: &Metadata,
+    options: &TypeOptions,
+    num_preamble_rows: Option<usize>,
+) -> Result<Validation> {
+    let mut validation = Validation {
+        num_records: 0,
+        num_field_count_violations: 0,
+        num_type_violations: 0,
+        violations: vec![],
+    };
+    let expected_preamble_rows = expected.dialect.header.num_preamble_rows;
+    if let Some(found) = num_preamble_rows.filter(|&found| found != expected_preamble_rows) {
+        validation.add(Violation::Preamble {
+            expected: expected_preamble_rows,
+            found,
+        });
+    }
+
+    // records with the wrong number of fields are reported rather than failing the reader
+    let mut dialect = expected.dialect.clone();
+    dialect.flexible = true;
+    let mut csv_reader = dialect.open_reader_with(
+        reader,
+        &ReadOptions {
+            drop_trailing_empty_fields: false,
+            skip_footer_rows: true,
+        },
+    )?;
+    if dialect.header.has_header_row {
+        let found = csv_reader
+            .headers()?
+            .iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        if found != expected.field_names {
+            validation.add(Violation::Header {
+                expected: expected.field_names.clone(),
+                found,
+            });
+        }
+    }
+
+    // values of decimal columns are checked as decimals
+    let mut options = options.clone();
+    options.number_format = expected.number_format;
+    options.decimals |= expected
+        .types
+        .iter()
+        .any(|ty| matches!(ty, Type::Decimal { .. }));
+    let guesses = expected
+        .types
+        .iter()
+        .enumerate()
+        .map(|(i, &ty)| {
+            let format = expected.formats.get(i).and_then(|format| format.as_deref());
+            TypeGuesses::with_format(ty, format, &options)
+        })
+        .collect::<Vec<_>>();
+    let details = expected
+        .types
+        .iter()
+        .map(|&ty| NumberDetails::of_type(ty))
+        .collect::<Vec<_>>();
+    let required = (0..guesses.len())
+        .map(|i| expected.verification.is_some() && expected.nullable.get(i) == Some(&false))
+        .collect::<Vec<_>>();
+    for (index, record) in csv_reader.records().enumerate() {
+        let record = record?;
+        validation.num_records += 1;
+        let found = record.len();
+        let fits_count = found == expected.num_fields
+            || (expected.dialect.flexible && found < expected.num_fields);
+        if !fits_count {
+            validation.num_field_count_violations += 1;
+            validation.add(Violation::FieldCount {
+                record: index,
+                expected: expected.num_fields,
+                found,
+            });
+        }
+        for (i, value) in record.iter().enumerate().take(guesses.len()) {
+            let fits = if options.is_null(value) {
+                !required[i]
+            } else {
+                let value_details =
+                    NumberDetails::of(value, &options.number_format).unwrap_or_default();
+                infer_types(value, &options).contains(guesses[i])
+                    && (!guesses[i].contains(TypeGuesses::DECIMAL)
+                        || value_details.fits(details[i]))
+            };
+            if !fits {
+                validation.num_type_violations += 1;
+                validation.add(Violation::Type {
+                    record: index,
+                    field: expected.field_name(i),
+                    value: value.into(),
+                    expected: expected.types[i],
+                });
+            }
+        }
+    }
+    Ok(validation)
+}
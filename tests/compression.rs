@@ -0,0 +1,100 @@
+extern crate csv_sniffer;
+
+use std::path::Path;
+
+use csv_sniffer::metadata::*;
+use csv_sniffer::Sniffer;
+
+#[test]
+fn test_compression() {
+    let metadata = Sniffer::new()
+        .sniff_path(Path::new(file!()).parent().unwrap().join("data/gdp.csv"))
+        .unwrap();
+    assert_eq!(metadata.compression, Compression::None);
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_gzip() {
+    use std::io::{Cursor, Write};
+
+    use csv_sniffer::Type;
+    use flate2::{write::GzEncoder, Compression as Level};
+
+    let text = "name;count;price\nfoo;1;1.5\nbar;2;2.5\nbaz;3;3.5\nqux;4;4.5\n";
+    let mut encoder = GzEncoder::new(vec![], Level::default());
+    encoder.write_all(text.as_bytes()).unwrap();
+    let gzipped = encoder.finish().unwrap();
+
+    let metadata = Sniffer::new().sniff_reader(Cursor::new(&gzipped)).unwrap();
+    assert_eq!(metadata.compression, Compression::Gzip);
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert_eq!(metadata.num_fields, 3);
+    assert_eq!(
+        metadata.types,
+        vec![Type::Text, Type::Unsigned, Type::Float]
+    );
+
+    let path = std::env::temp_dir().join(format!("csv-sniffer-{}.csv.gz", std::process::id()));
+    std::fs::write(&path, &gzipped).unwrap();
+    let metadata = Sniffer::new().sniff_path(&path).unwrap();
+    assert_eq!(metadata.compression, Compression::Gzip);
+    let mut reader = metadata.dialect.open_path_decompressed(&path).unwrap();
+    assert_eq!(reader.headers().unwrap(), vec!["name", "count", "price"]);
+    assert_eq!(reader.records().count(), 4);
+    let mut reader = Sniffer::new().open_path_decompressed(&path).unwrap();
+    assert_eq!(reader.records().count(), 4);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(any(feature = "zstd", feature = "bzip2", feature = "xz"))]
+fn check_compressed(compressed: Vec<u8>, compression: Compression) {
+    use std::io::Cursor;
+
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(&compressed))
+        .unwrap();
+    assert_eq!(metadata.compression, compression);
+    assert_eq!(metadata.dialect.delimiter, b'|');
+    assert_eq!(metadata.num_fields, 3);
+
+    let reader = DecompressReader::detect(Cursor::new(compressed)).unwrap();
+    assert_eq!(reader.compression(), compression);
+    let mut reader = metadata.dialect.open_reader(reader).unwrap();
+    assert_eq!(reader.headers().unwrap(), vec!["name", "count", "price"]);
+    assert_eq!(reader.records().count(), 4);
+}
+
+#[cfg(any(feature = "zstd", feature = "bzip2", feature = "xz"))]
+const COMPRESSED_TEXT: &str = "name|count|price\nfoo|1|1.5\nbar|2|2.5\nbaz|3|3.5\nqux|4|4.5\n";
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_zstd() {
+    let compressed = zstd::encode_all(COMPRESSED_TEXT.as_bytes(), 0).unwrap();
+    check_compressed(compressed, Compression::Zstd);
+}
+
+#[cfg(feature = "bzip2")]
+#[test]
+fn test_bzip2() {
+    use std::io::Read;
+
+    let mut compressed = vec![];
+    bzip2::read::BzEncoder::new(COMPRESSED_TEXT.as_bytes(), bzip2::Compression::default())
+        .read_to_end(&mut compressed)
+        .unwrap();
+    check_compressed(compressed, Compression::Bzip2);
+}
+
+#[cfg(feature = "xz")]
+#[test]
+fn test_xz() {
+    use std::io::Read;
+
+    let mut compressed = vec![];
+    xz2::read::XzEncoder::new(COMPRESSED_TEXT.as_bytes(), 6)
+        .read_to_end(&mut compressed)
+        .unwrap();
+    check_compressed(compressed, Compression::Xz);
+}
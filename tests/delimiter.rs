@@ -1,6 +1,7 @@
 extern crate csv;
 extern crate csv_sniffer;
 
+use std::io::Cursor;
 use std::path::Path;
 
 use csv_sniffer::metadata::*;
@@ -24,9 +25,19 @@ fn test_semicolon() {
                 header: Header {
                     has_header_row: true,
                     num_preamble_rows: 0,
+                    num_footer_rows: 0,
                 },
                 quote: Quote::None,
-                flexible: false
+                flexible: false,
+                terminator: Terminator::CRLF,
+                escape: Escape::Disabled,
+                comment: Comment::Disabled,
+                doublequote_escapes: true,
+                quote_style: QuoteStyle::Necessary,
+                num_trailing_empty_fields: 0,
+                trim: Trim::None,
+                skip_blank_lines: false,
+                encoding: Encoding::Utf8
             },
             num_fields: 5,
             types: vec![
@@ -35,7 +46,8 @@ fn test_semicolon() {
                 Type::Unsigned,
                 Type::Text,
                 Type::Text
-            ]
+            ],
+            ..metadata.clone()
         }
     );
 }
@@ -58,9 +70,19 @@ fn test_comma() {
                 header: Header {
                     has_header_row: true,
                     num_preamble_rows: 0,
+                    num_footer_rows: 0,
                 },
                 quote: Quote::None,
-                flexible: false
+                flexible: false,
+                terminator: Terminator::LF,
+                escape: Escape::Disabled,
+                comment: Comment::Disabled,
+                doublequote_escapes: true,
+                quote_style: QuoteStyle::Necessary,
+                num_trailing_empty_fields: 0,
+                trim: Trim::None,
+                skip_blank_lines: false,
+                encoding: Encoding::Utf8
             },
             num_fields: 5,
             types: vec![
@@ -69,7 +91,8 @@ fn test_comma() {
                 Type::Unsigned,
                 Type::Unsigned,
                 Type::Unsigned
-            ]
+            ],
+            ..metadata.clone()
         }
     );
 }
@@ -92,9 +115,19 @@ fn test_flexible() {
                 header: Header {
                     has_header_row: true,
                     num_preamble_rows: 0,
+                    num_footer_rows: 0,
                 },
                 quote: Quote::None,
-                flexible: true
+                flexible: true,
+                terminator: Terminator::LF,
+                escape: Escape::Disabled,
+                comment: Comment::Disabled,
+                doublequote_escapes: true,
+                quote_style: QuoteStyle::Necessary,
+                num_trailing_empty_fields: 0,
+                trim: Trim::None,
+                skip_blank_lines: false,
+                encoding: Encoding::Utf8
             },
             num_fields: 7,
             types: vec![
@@ -105,7 +138,176 @@ fn test_flexible() {
                 Type::Text,
                 Type::Unsigned,
                 Type::Text
-            ]
+            ],
+            ..metadata.clone()
         }
     );
 }
+
+#[test]
+fn test_confidence() {
+    let data_filepath = Path::new(file!())
+        .parent()
+        .unwrap()
+        .join("data/library-visitors.csv");
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::All)
+        .sniff_path(data_filepath)
+        .unwrap();
+    assert!(metadata.confidence.delimiter > 0.9);
+    assert_eq!(metadata.confidence.header, 1.0);
+    assert_eq!(metadata.confidence.quote, 1.0);
+
+    // both ';' and ',' are equally plausible delimiters here
+    let data = "a;b,c\n1;2,3\n4;5,6\n7;8,9\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.confidence.delimiter, 0.5);
+}
+
+#[test]
+fn test_candidates() {
+    let data = "a;b,c\n1;2,3\n4;5,6\n7;8,9\n";
+    let candidates = Sniffer::new()
+        .sniff_candidates(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates[0].0.delimiter, b',');
+    assert_eq!(candidates[0].1, 0.5);
+    assert_eq!(candidates[1].0.delimiter, b';');
+    assert_eq!(candidates[1].1, 0.5);
+}
+
+#[test]
+fn test_delimiter_candidates() {
+    // the colons in the timestamps are just as consistent as the commas
+    let data = "time,value\n12:01,1\n12:02,2\n12:03,3\n";
+    let metadata = Sniffer::new()
+        .delimiter_candidates(b":")
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b':');
+    let metadata = Sniffer::new()
+        .delimiter_candidates(b",;\t|")
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert_eq!(metadata.dialect.header.num_preamble_rows, 0);
+}
+
+#[test]
+fn test_delimiter_priors() {
+    // spaces are just as consistent as the commas here, but commas are preferred by default
+    let data = "first name,age\nJohn Smith,42\nJane Doe,39\nAl Jones,27\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b',');
+    let candidates = Sniffer::new()
+        .sniff_candidates(Cursor::new(data.as_bytes()))
+        .unwrap();
+    let space_score = candidates
+        .iter()
+        .find(|(dialect, _)| dialect.delimiter == b' ')
+        .unwrap()
+        .1;
+    assert!((metadata.confidence.delimiter - 2.0 * space_score).abs() < 1e-9);
+
+    let metadata = Sniffer::new()
+        .delimiter_prior(b' ', 2.0)
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b' ');
+}
+
+#[test]
+fn test_time_colons() {
+    // the colons in times of day are as consistent as the delimiters, but aren't delimiters
+    let data = "\
+event;start;end;logged
+a;12:01;12:05:30;2021-03-04T12:01:00+01:00
+b;12:02;12:06:30;2021-03-04T12:02:00+01:00
+c;12:03;12:07:30;2021-03-04T12:03:00+01:00
+d;12:04;12:08:30;2021-03-04T12:04:00+01:00
+";
+    let metadata = Sniffer::new()
+        .delimiter_candidates(b";:")
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert_eq!(metadata.num_fields, 4);
+    assert_eq!(
+        metadata.types,
+        vec![Type::Text, Type::Time, Type::Time, Type::DateTime]
+    );
+
+    // colons that aren't part of times of day are still counted
+    let data = "a:b:c\nx:y:z\nfoo:bar:baz\n1:2:3\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b':');
+}
+
+#[test]
+fn test_url_chars() {
+    // the colons and slashes in URLs are as consistent as the delimiters, but aren't delimiters
+    let mut data = "website;mirror\n".to_string();
+    for i in 0..20 {
+        data.push_str(&format!(
+            "https://www.org{}.com/;http://mirror.org{}.com:8080/\n",
+            i, i
+        ));
+    }
+    let metadata = Sniffer::new()
+        .delimiter_candidates(b";:/")
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert_eq!(metadata.num_fields, 2);
+    assert_eq!(metadata.confidence.delimiter, 1.0);
+
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert_eq!(metadata.num_fields, 2);
+}
+
+#[test]
+fn test_fixed_delimiter() {
+    let mut text = "Quarterly report\nname|city|count\n".to_string();
+    for i in 0..20 {
+        text.push_str(&format!("\"n {}\"|\"City, {}\"|{}\n", i, i, i * 2));
+    }
+    let metadata = Sniffer::new().delimiter(b'|').sniff_str(&text).unwrap();
+    assert_eq!(metadata.dialect.delimiter, b'|');
+    assert_eq!(metadata.confidence.delimiter, 1.0);
+    assert_eq!(metadata.dialect.quote, Quote::Some(b'"'));
+    assert_eq!(
+        metadata.dialect.header,
+        Header {
+            has_header_row: true,
+            num_preamble_rows: 1,
+            num_footer_rows: 0,
+        }
+    );
+    assert_eq!(metadata.field_names, ["name", "city", "count"]);
+    assert_eq!(metadata.types, [Type::Text, Type::Text, Type::Unsigned]);
+}
+
+#[test]
+fn test_delimiter_candidate_counts() {
+    // the same delimiter is found whether counting a few candidates or many
+    let text = "a;b|c|d\n1;2|x|3\n4|5;y|6\n7|8|z;9\n10|11|12\n13|14|15\n";
+    for candidates in [&b"|;"[..], &b",;:|\t #"[..], &b",;:|\t #!$%^&*"[..]] {
+        let metadata = Sniffer::new()
+            .delimiter_candidates(candidates)
+            .sniff_str(text)
+            .unwrap();
+        assert_eq!(metadata.dialect.delimiter, b'|');
+        assert_eq!(metadata.num_fields, 3);
+        assert!(!metadata.dialect.flexible);
+    }
+}
@@ -0,0 +1,311 @@
+extern crate csv_sniffer;
+
+use std::io::Cursor;
+
+use csv_sniffer::error::SnifferError;
+use csv_sniffer::metadata::*;
+use csv_sniffer::{Sniffer, Type};
+
+#[test]
+fn test_terminator_cr() {
+    let data = "name,count\rfoo,1\rbar,2\rbaz,3\r";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.terminator, Terminator::CR);
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert!(metadata.dialect.header.has_header_row);
+
+    let mut reader = metadata
+        .dialect
+        .open_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(reader.records().count(), 3);
+}
+
+#[test]
+fn test_terminator_record_separator() {
+    let data = "name,count\x1efoo,1\x1ebar,2\x1ebaz,3\x1e";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.terminator, Terminator::Any(0x1e));
+    assert_eq!(metadata.num_fields, 2);
+}
+
+#[test]
+fn test_escape() {
+    let data = "id,quote,name\n\
+                1,\"He said \\\"hi\\\"\",\"x\"\n\
+                2,\"plain\",\"y\"\n\
+                3,\"a \\\"b\\\" c\",\"z\"\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.escape, Escape::Enabled(b'\\'));
+    assert_eq!(metadata.dialect.quote, Quote::Some(b'"'));
+    assert!(!metadata.dialect.doublequote_escapes);
+    assert_eq!(metadata.num_fields, 3);
+
+    let mut reader = metadata
+        .dialect
+        .open_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    let record = reader.records().next().unwrap().unwrap();
+    assert_eq!(&record[1], "He said \"hi\"");
+}
+
+#[test]
+fn test_comment() {
+    let data = "# exported 2024\nname,count\n# interleaved\nfoo,1\nbar,2\nbaz,3\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.comment, Comment::Enabled(b'#'));
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert_eq!(metadata.dialect.header.num_preamble_rows, 1);
+    assert_eq!(metadata.preamble, vec!["# exported 2024"]);
+    assert_eq!(metadata.data_offset, 16);
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(metadata.num_fields, 2);
+
+    let mut reader = metadata
+        .dialect
+        .open_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(reader.records().count(), 3);
+}
+
+#[test]
+fn test_doublequote_escapes() {
+    let data = "id,quote,name\n\
+                1,\"He said \"\"hi\"\"\",\"x\"\n\
+                2,\"plain\",\"y\"\n\
+                3,\"a \"\"b\"\" c\",\"z\"\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert!(metadata.dialect.doublequote_escapes);
+
+    let mut reader = metadata
+        .dialect
+        .open_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    let record = reader.records().next().unwrap().unwrap();
+    assert_eq!(&record[1], "He said \"hi\"");
+}
+
+#[test]
+fn test_quote_style() {
+    let always = "\"name\",\"count\"\n\"foo\",\"1\"\n\"bar\",\"2\"\n\"baz\",\"3\"\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(always.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.quote_style, QuoteStyle::Always);
+
+    let non_numeric = "\"name\",\"count\"\n\"foo\",1\n\"bar\",2\n\"baz\",3\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(non_numeric.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.quote_style, QuoteStyle::NonNumeric);
+
+    let necessary = "name,count\n\"foo, inc.\",1\nbar,2\n\"baz, ltd.\",3\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(necessary.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.quote_style, QuoteStyle::Necessary);
+
+    // writing with the detected dialect reproduces the original quoting
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(non_numeric.as_bytes()))
+        .unwrap();
+    let mut reader = metadata
+        .dialect
+        .open_reader(Cursor::new(non_numeric.as_bytes()))
+        .unwrap();
+    let mut writer = csv::WriterBuilder::from(metadata.dialect).from_writer(vec![]);
+    writer.write_record(reader.headers().unwrap()).unwrap();
+    for record in reader.records() {
+        writer.write_record(&record.unwrap()).unwrap();
+    }
+    assert_eq!(writer.into_inner().unwrap(), non_numeric.as_bytes());
+}
+
+#[test]
+fn test_writer_round_trip() {
+    // rewriting a file with the detected dialect keeps its delimiter, quoting and terminator
+    let mut data = "name;note;count\r".to_string();
+    for i in 0..20 {
+        if i % 2 == 0 {
+            data.push_str(&format!("'n{}; a';'x; {}';{}\r", i, i, i));
+        } else {
+            data.push_str(&format!("n{};y{};{}\r", i, i, i));
+        }
+    }
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert_eq!(metadata.dialect.quote, Quote::Some(b'\''));
+    assert_eq!(metadata.dialect.terminator, Terminator::CR);
+
+    let mut reader = metadata
+        .dialect
+        .open_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    let mut writer = csv::WriterBuilder::from(metadata.dialect).from_writer(vec![]);
+    writer.write_record(reader.headers().unwrap()).unwrap();
+    for record in reader.records() {
+        writer.write_record(&record.unwrap()).unwrap();
+    }
+    assert_eq!(writer.into_inner().unwrap(), data.as_bytes());
+}
+
+#[test]
+fn test_dialect_presets() {
+    let mut writer = csv::WriterBuilder::from(Dialect::excel()).from_writer(vec![]);
+    writer.write_record(["name", "note"]).unwrap();
+    writer.write_record(["foo", "a, \"b\""]).unwrap();
+    let data = writer.into_inner().unwrap();
+    assert_eq!(data, b"name,note\r\nfoo,\"a, \"\"b\"\"\"\r\n");
+    let mut reader = Dialect::excel().open_reader(Cursor::new(data)).unwrap();
+    let record = reader.records().next().unwrap().unwrap();
+    assert_eq!(&record[1], "a, \"b\"");
+
+    let tsv = Dialect::tsv();
+    assert_eq!((tsv.delimiter, tsv.terminator), (b'\t', Terminator::CRLF));
+    let unix = Dialect::unix();
+    assert_eq!(
+        (unix.terminator, unix.quote_style),
+        (Terminator::LF, QuoteStyle::Always)
+    );
+    let mut writer = csv::WriterBuilder::from(unix).from_writer(vec![]);
+    writer.write_record(["foo", "1"]).unwrap();
+    assert_eq!(writer.into_inner().unwrap(), b"\"foo\",\"1\"\n");
+    assert!(!Dialect::excel().skip_blank_lines);
+    assert!(!tsv.skip_blank_lines);
+    assert!(!Dialect::unix().skip_blank_lines);
+    assert_eq!(Dialect::rfc4180_strict(), Dialect::excel());
+}
+
+#[test]
+fn test_dialect_spec() {
+    let dialect: Dialect = "delim=;;quote=\";header=yes;preamble=2".parse().unwrap();
+    assert_eq!(dialect.delimiter, b';');
+    assert_eq!(dialect.quote, Quote::Some(b'"'));
+    assert!(dialect.header.has_header_row);
+    assert_eq!(dialect.header.num_preamble_rows, 2);
+    assert_eq!(dialect.to_spec(), "delim=;;quote=\";header=yes;preamble=2");
+
+    let dialect = Dialect {
+        quote: Quote::None,
+        terminator: Terminator::LF,
+        comment: Comment::Enabled(b'#'),
+        trim: Trim::All,
+        encoding: Encoding::Windows1252,
+        ..Dialect::tsv()
+    };
+    let spec = dialect.to_spec();
+    assert_eq!(
+        spec,
+        "delim=tab;quote=none;header=yes;preamble=0;comment=#;terminator=lf;trim=all;\
+         encoding=windows-1252"
+    );
+    assert_eq!(Dialect::from_spec(&spec).unwrap(), dialect);
+
+    for spec in [
+        "delim",
+        "delim=ab",
+        "header=maybe",
+        "preamble=-1",
+        "separator=,",
+    ] {
+        assert!(matches!(
+            Dialect::from_spec(spec),
+            Err(SnifferError::DialectSpec(_))
+        ));
+    }
+}
+
+#[test]
+fn test_trailing_empty_fields() {
+    let data = "name,count,,,\nfoo,1,,,\nbar,2,,,\nbaz,3,,,\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.num_fields, 5);
+    assert_eq!(metadata.num_effective_fields, 2);
+    assert_eq!(metadata.dialect.num_trailing_empty_fields, 3);
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Text,
+            Type::Unsigned,
+            Type::Null,
+            Type::Null,
+            Type::Null
+        ]
+    );
+    assert_eq!(metadata.nullable, vec![false, false, true, true, true]);
+
+    let mut reader = metadata
+        .dialect
+        .open_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(reader.headers().unwrap().len(), 5);
+
+    let options = ReadOptions {
+        drop_trailing_empty_fields: true,
+        ..ReadOptions::default()
+    };
+    let mut reader = metadata
+        .dialect
+        .open_reader_with(Cursor::new(data.as_bytes()), &options)
+        .unwrap();
+    assert_eq!(reader.headers().unwrap().len(), 2);
+    let records = reader.records().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(records.len(), 3);
+    assert!(records.iter().all(|record| record.len() == 2));
+}
+
+#[test]
+fn test_trim() {
+    let data = "name , count , ratio\nfoo , 1 , 0.5\nbar , 2 , 1.5\nbaz , 3 , 2.5\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.trim, Trim::All);
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.types,
+        vec![Type::Text, Type::Unsigned, Type::Float]
+    );
+    assert_eq!(metadata.field_names, vec!["name", "count", "ratio"]);
+
+    let mut reader = metadata
+        .dialect
+        .open_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(reader.headers().unwrap(), vec!["name", "count", "ratio"]);
+}
+
+#[test]
+fn test_blank_lines() {
+    let data = "name,count\nfoo,1\n\nbar,2\n  \nbaz,3\nqux,4\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert!(metadata.dialect.header.has_header_row);
+    assert!(metadata.dialect.skip_blank_lines);
+    assert_eq!(metadata.num_blank_lines, 2);
+    assert_eq!(metadata.types, vec![Type::Text, Type::Unsigned]);
+
+    let mut reader = metadata
+        .dialect
+        .open_reader_with(Cursor::new(data.as_bytes()), &ReadOptions::default())
+        .unwrap();
+    let records = reader.records().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(records.len(), 4);
+}
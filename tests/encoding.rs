@@ -0,0 +1,155 @@
+extern crate csv_sniffer;
+
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+
+use csv_sniffer::metadata::*;
+use csv_sniffer::{SampleSize, Sniffer, Type};
+
+// A reader counting the bytes read from it.
+struct CountingReader<R> {
+    inner: R,
+    n_read: usize,
+}
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n_read = self.inner.read(buf)?;
+        self.n_read += n_read;
+        Ok(n_read)
+    }
+}
+impl<R: Seek> Seek for CountingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn test_encoded_sample_size() {
+    // "Renée" in Windows-1252
+    let mut data = b"name,count\n".to_vec();
+    for i in 0..50_000 {
+        data.extend_from_slice(b"Ren\xe9e ");
+        data.extend_from_slice(format!("{:05},{:05}\n", i, i).as_bytes());
+    }
+    data.extend_from_slice(b"Total,\n");
+
+    let mut reader = CountingReader {
+        inner: Cursor::new(&data),
+        n_read: 0,
+    };
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::Bytes(1 << 12))
+        .sniff_reader(&mut reader)
+        .unwrap();
+    assert_eq!(metadata.encoding, Encoding::Windows1252);
+    assert_eq!(metadata.field_names, vec!["name", "count"]);
+    assert_eq!(metadata.types, vec![Type::Text, Type::Unsigned]);
+    // the footer row is found at the end of the file, past the sample
+    assert_eq!(metadata.dialect.header.num_footer_rows, 1);
+    let estimate = metadata.estimated_num_records.unwrap();
+    assert!((45_000..55_000).contains(&estimate), "{}", estimate);
+    // only the start and the end of the file are read
+    assert!(reader.n_read < data.len() / 4, "{}", reader.n_read);
+
+    // characters split between the chunks read while decoding are decoded whole
+    for n_padding in 0..5 {
+        let mut text = format!("{}name;symbol\n", "x".repeat(n_padding));
+        for _ in 0..2000 {
+            text.push_str("a;\u{1d160}\n");
+        }
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend(text.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        let metadata = Sniffer::new()
+            .sample_size(SampleSize::Records(1000))
+            .detect_categorical(4)
+            .sniff_reader(Cursor::new(&utf16))
+            .unwrap();
+        assert_eq!(metadata.encoding, Encoding::Utf16Le);
+        assert_eq!(metadata.bom, Some(Bom::Utf16Le));
+        assert_eq!(metadata.levels[1], vec!["\u{1d160}"]);
+    }
+}
+
+#[test]
+fn test_bom() {
+    let data = "\u{feff}# exported 2024\nname,count\nfoo,1\nbar,2\nbaz,3\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.bom, Some(Bom::Utf8));
+    assert_eq!(metadata.dialect.comment, Comment::Enabled(b'#'));
+    assert_eq!(metadata.preamble, vec!["# exported 2024"]);
+
+    let data = "\u{feff}name,count\nfoo,1\nbar,2\nbaz,3\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.bom, Some(Bom::Utf8));
+    assert_eq!(metadata.data_offset, 3);
+    assert_eq!(metadata.field_names, vec!["name", "count"]);
+
+    let mut reader = metadata
+        .dialect
+        .open_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(reader.headers().unwrap(), vec!["name", "count"]);
+}
+
+#[test]
+fn test_encoding() {
+    // "name,city\nJosé,Zürich\n..." in Windows-1252 / Latin-1
+    let latin1 =
+        b"name,city,count\nJos\xe9,Z\xfcrich,1\nRen\xe9e,G\xe9n\xe8ve,2\nJ\xfcrgen,K\xf6ln,3\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(&latin1[..]))
+        .unwrap();
+    assert_eq!(metadata.encoding, Encoding::Windows1252);
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert_eq!(metadata.field_names, vec!["name", "city", "count"]);
+    assert_eq!(metadata.types, vec![Type::Text, Type::Text, Type::Unsigned]);
+
+    let text = "name;count\nfoo;1\nbar;2\nbaz;3\n";
+    let mut utf16 = vec![0xFF, 0xFE];
+    utf16.extend(text.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+    let metadata = Sniffer::new().sniff_reader(Cursor::new(&utf16)).unwrap();
+    assert_eq!(metadata.encoding, Encoding::Utf16Le);
+    assert_eq!(metadata.bom, Some(Bom::Utf16Le));
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert_eq!(metadata.field_names, vec!["name", "count"]);
+    assert_eq!(metadata.data_offset, 2);
+
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(text.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.encoding, Encoding::Utf8);
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn test_open_reader_transcoded() {
+    let latin1 =
+        b"name,city,count\nJos\xe9,Z\xfcrich,1\nRen\xe9e,G\xe9n\xe8ve,2\nJ\xfcrgen,K\xf6ln,3\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(&latin1[..]))
+        .unwrap();
+    assert_eq!(metadata.dialect.encoding, Encoding::Windows1252);
+    let mut reader = metadata
+        .dialect
+        .open_reader_transcoded(Cursor::new(&latin1[..]))
+        .unwrap();
+    let record = reader.records().next().unwrap().unwrap();
+    assert_eq!(&record[0], "José");
+    assert_eq!(&record[1], "Zürich");
+
+    let text = "title\n\nname;count\nfoo;1\nbar;2\nbaz;3\n";
+    let mut utf16 = vec![0xFE, 0xFF];
+    utf16.extend(text.encode_utf16().flat_map(|unit| unit.to_be_bytes()));
+    let metadata = Sniffer::new().sniff_reader(Cursor::new(&utf16)).unwrap();
+    assert_eq!(metadata.dialect.encoding, Encoding::Utf16Be);
+    let mut reader = metadata
+        .dialect
+        .open_reader_transcoded(Cursor::new(&utf16))
+        .unwrap();
+    assert_eq!(reader.headers().unwrap(), vec!["name", "count"]);
+    assert_eq!(reader.records().count(), 3);
+}
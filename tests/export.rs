@@ -0,0 +1,641 @@
+extern crate csv_sniffer;
+
+use std::io::Cursor;
+
+use csv_sniffer::metadata::*;
+use csv_sniffer::Sniffer;
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_metadata_json() {
+    use csv_sniffer::{ExampleValues, Type};
+
+    let mut text = "# exported \"daily\"\nid;price;name;when\n".to_string();
+    for i in 0..30 {
+        text.push_str(&format!(
+            "{};{}.{:02};\"n\u{e9}\\{}\";2021-03-{:02}\n",
+            i + 1,
+            i * 7,
+            i,
+            if i % 3 == 0 {
+                String::new()
+            } else {
+                i.to_string()
+            },
+            i % 28 + 1
+        ));
+    }
+    let metadata = Sniffer::new()
+        .compute_statistics(true)
+        .example_values(ExampleValues::First(2))
+        .detect_primary_keys(true)
+        .sniff_str(&text)
+        .unwrap();
+    let json = metadata.to_json();
+    assert!(json.starts_with("{\"version\":1,\"dialect\":{\"delimiter\":\";\""));
+    assert!(json.contains("\"terminator\":\"LF\""));
+    assert!(json.contains("\"types\":[{\"type\":\"Unsigned\"},{\"type\":\"Float\"}"));
+    assert_eq!(Metadata::from_json(&json).unwrap(), metadata);
+
+    // types with details have further keys
+    let mut metadata = metadata;
+    metadata.types[1] = Type::Decimal {
+        precision: 5,
+        scale: 2,
+    };
+    let json = metadata.to_json();
+    assert!(json.contains("{\"type\":\"Decimal\",\"precision\":5,\"scale\":2}"));
+    assert_eq!(Metadata::from_json(&json).unwrap(), metadata);
+
+    // the serde implementations use the same shape
+    assert_eq!(serde_json::to_string(&metadata).unwrap(), json);
+    let parsed: Metadata = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, metadata);
+    assert_eq!(
+        serde_json::from_value::<Dialect>(serde_json::to_value(&metadata.dialect).unwrap())
+            .unwrap(),
+        metadata.dialect
+    );
+
+    // unknown keys are ignored, and missing optional keys take default values
+    let json = json
+        .replacen(
+            "{\"version\":1,",
+            "{\"version\":1,\"added_later\":[1,{}],",
+            1,
+        )
+        .replace("\"increasing\":", "\"ignored\":");
+    let parsed = Metadata::from_json(&json).unwrap();
+    assert!(parsed.increasing.is_empty());
+    assert_eq!(parsed.types, metadata.types);
+
+    assert!(Metadata::from_json("{\"version\":1}").is_err());
+    assert!(Metadata::from_json(&json.replacen("\"version\":1", "\"version\":2", 1)).is_err());
+    assert!(Metadata::from_json(&json[..json.len() - 1]).is_err());
+}
+
+#[test]
+fn test_sql_ddl() {
+    let mut text = "id,price,day,at,name\n".to_string();
+    for i in 0..30 {
+        text.push_str(&format!(
+            "{},{}.{:02},2021-03-{:02},2021-03-04T05:06:{:02}Z,n{}\n",
+            i,
+            i * 7,
+            i,
+            i % 28 + 1,
+            i,
+            i * 1000
+        ));
+    }
+    let mut metadata = Sniffer::new()
+        .detect_decimals(true)
+        .compute_statistics(true)
+        .verify(true)
+        .sniff_reader(Cursor::new(text.as_bytes()))
+        .unwrap();
+    // names are quoted
+    metadata.field_names[4] = "na\"me".into();
+    assert_eq!(
+        metadata.to_sql_ddl(SqlDialect::Postgres, "daily"),
+        "CREATE TABLE \"daily\" (\n    \"id\" BIGINT NOT NULL,\n    \"price\" NUMERIC(5, 2) NOT NULL,\n    \
+         \"day\" DATE NOT NULL,\n    \"at\" TIMESTAMP WITH TIME ZONE NOT NULL,\n    \
+         \"na\"\"me\" VARCHAR(6) NOT NULL\n);\n"
+    );
+    assert_eq!(
+        metadata.to_sql_ddl(SqlDialect::MySql, "daily"),
+        "CREATE TABLE `daily` (\n    `id` BIGINT UNSIGNED NOT NULL,\n    \
+         `price` DECIMAL(5, 2) NOT NULL,\n    `day` DATE NOT NULL,\n    \
+         `at` DATETIME NOT NULL,\n    `na\"me` VARCHAR(6) NOT NULL\n);\n"
+    );
+    assert!(metadata
+        .to_sql_ddl(SqlDialect::Sqlite, "daily")
+        .contains("\"id\" INTEGER NOT NULL,\n    \"price\" NUMERIC NOT NULL,\n    \"day\" TEXT"));
+
+    // without lengths or verification, text columns aren't sized and columns are nullable
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    let ddl = metadata.to_sql_ddl(SqlDialect::Ansi, "daily");
+    assert!(ddl.contains("\"price\" DOUBLE PRECISION,\n"));
+    assert!(ddl.ends_with("\"name\" CLOB\n);\n"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_json_schema() {
+    let mut text = "id,delta,day,email,label\n".to_string();
+    for i in 0..30 {
+        text.push_str(&format!(
+            "{},{},2021-03-{:02},user{}@example.com,{}\n",
+            i,
+            i as i64 - 10,
+            i % 28 + 1,
+            i,
+            ["a", "b", "c"][i % 3]
+        ));
+    }
+    let metadata = Sniffer::new()
+        .detect_categorical(5)
+        .detect_semantic_types(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(
+        metadata.to_json_schema(),
+        "{\"$schema\":\"https://json-schema.org/draft/2020-12/schema\",\"type\":\"object\",\
+         \"properties\":{\"id\":{\"type\":[\"integer\",\"null\"],\"minimum\":0},\
+         \"delta\":{\"type\":[\"integer\",\"null\"]},\
+         \"day\":{\"type\":[\"string\",\"null\"],\"format\":\"date\"},\
+         \"email\":{\"type\":[\"string\",\"null\"],\"format\":\"email\"},\
+         \"label\":{\"type\":[\"string\",\"null\"],\"enum\":[\"a\",\"b\",\"c\",null]}},\
+         \"required\":[\"id\",\"delta\",\"day\",\"email\",\"label\"],\
+         \"additionalProperties\":false}"
+    );
+
+    // verified fields without null values aren't nullable
+    let metadata = Sniffer::new()
+        .verify(true)
+        .sniff_reader(Cursor::new(text.as_bytes()))
+        .unwrap();
+    assert!(metadata
+        .to_json_schema()
+        .contains("\"id\":{\"type\":\"integer\",\"minimum\":0}"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_avro_schema() {
+    let mut text = "id,unit price,day,at,1st\n".to_string();
+    for i in 0..30 {
+        text.push_str(&format!(
+            "{},{}.{:02},2021-03-{:02},2021-03-04T05:06:{:02}Z,n{}\n",
+            i,
+            i * 7,
+            i,
+            i % 28 + 1,
+            i,
+            i
+        ));
+    }
+    let metadata = Sniffer::new()
+        .detect_decimals(true)
+        .verify(true)
+        .sniff_reader(Cursor::new(text.as_bytes()))
+        .unwrap();
+    assert_eq!(
+        metadata.to_avro_schema("com.example.Daily"),
+        "{\"type\":\"record\",\"name\":\"com.example.Daily\",\"fields\":[\
+         {\"name\":\"id\",\"type\":\"long\"},\
+         {\"name\":\"unit_price\",\"doc\":\"unit price\",\"type\":\
+         {\"type\":\"bytes\",\"logicalType\":\"decimal\",\"precision\":5,\"scale\":2}},\
+         {\"name\":\"day\",\"type\":{\"type\":\"int\",\"logicalType\":\"date\"}},\
+         {\"name\":\"at\",\"type\":{\"type\":\"long\",\"logicalType\":\"timestamp-millis\"}},\
+         {\"name\":\"_1st\",\"doc\":\"1st\",\"type\":\"string\"}]}"
+    );
+
+    // fields that might have null values are unions with null
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert!(metadata
+        .to_avro_schema("Daily")
+        .contains("{\"name\":\"id\",\"type\":[\"null\",\"long\"],\"default\":null}"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_frictionless_resource() {
+    let mut text = "id;price;day;label\r\n".to_string();
+    for i in 0..30 {
+        text.push_str(&format!(
+            "{};{},{:02};03/{:02}/2021;{}\r\n",
+            i,
+            i * 7,
+            i,
+            i % 28 + 1,
+            ["a", "b", "c"][i % 3]
+        ));
+    }
+    let metadata = Sniffer::new()
+        .detect_categorical(5)
+        .verify(true)
+        .sniff_reader(Cursor::new(text.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.number_format.decimal_separator, ',');
+    assert_eq!(
+        metadata.to_frictionless_resource("data/Daily Sales.csv.gz"),
+        "{\"name\":\"daily-sales\",\"path\":\"data/Daily Sales.csv.gz\",\
+         \"profile\":\"tabular-data-resource\",\"format\":\"csv\",\"mediatype\":\"text/csv\",\
+         \"encoding\":\"utf-8\",\"dialect\":{\"csvddfVersion\":\"1.2\",\"delimiter\":\";\",\
+         \"lineTerminator\":\"\\r\\n\",\"doubleQuote\":true,\"header\":true,\
+         \"skipInitialSpace\":false},\"schema\":{\"fields\":[\
+         {\"name\":\"id\",\"type\":\"integer\",\"constraints\":{\"required\":true,\"minimum\":0}},\
+         {\"name\":\"price\",\"type\":\"number\",\"decimalChar\":\",\",\"constraints\":{\"required\":true}},\
+         {\"name\":\"day\",\"type\":\"date\",\"format\":\"any\",\"constraints\":{\"required\":true}},\
+         {\"name\":\"label\",\"type\":\"string\",\
+         \"constraints\":{\"required\":true,\"enum\":[\"a\",\"b\",\"c\"]}}]}}"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_csvw_metadata() {
+    let mut text = "exported daily\nid;amount;day;unit price\n".to_string();
+    for i in 0..30 {
+        text.push_str(&format!(
+            "{};{};{:02}/03/2021;{},5\n",
+            i,
+            i as i64 - 10,
+            i % 28 + 1,
+            i * 7
+        ));
+    }
+    let metadata = Sniffer::new()
+        .verify(true)
+        .sniff_reader(Cursor::new(text.as_bytes()))
+        .unwrap();
+    assert_eq!(
+        metadata.to_csvw_metadata("daily.csv"),
+        "{\"@context\":\"http://www.w3.org/ns/csvw\",\"url\":\"daily.csv\",\
+         \"dialect\":{\"encoding\":\"utf-8\",\"delimiter\":\";\",\"lineTerminators\":[\"\\n\"],\
+         \"quoteChar\":null,\"doubleQuote\":true,\"header\":true,\"skipRows\":1,\
+         \"skipBlankRows\":false,\"trim\":false},\"tableSchema\":{\"columns\":[\
+         {\"name\":\"id\",\"titles\":\"id\",\"datatype\":\"nonNegativeInteger\",\"required\":true},\
+         {\"name\":\"amount\",\"titles\":\"amount\",\"datatype\":\"integer\",\"required\":true},\
+         {\"name\":\"day\",\"titles\":\"day\",\
+         \"datatype\":{\"base\":\"date\",\"format\":\"dd/MM/yyyy\"},\"required\":true},\
+         {\"name\":\"unit%20price\",\"titles\":\"unit price\",\"datatype\":{\"base\":\"double\",\
+         \"format\":{\"decimalChar\":\",\"}},\"required\":true}]}}"
+    );
+}
+
+#[test]
+fn test_rust_struct() {
+    let mut text = "id,unitPrice,type,Day Of Week,2nd\n".to_string();
+    for i in 0..30 {
+        text.push_str(&format!(
+            "{},{}.5,{},{},{}\n",
+            i,
+            i,
+            ["a", "b", "c"][i % 3],
+            ["Mon", "Tue"][i % 2],
+            if i % 5 == 0 {
+                String::new()
+            } else {
+                i.to_string()
+            }
+        ));
+    }
+    let metadata = Sniffer::new()
+        .verify(true)
+        .sniff_reader(Cursor::new(text.as_bytes()))
+        .unwrap();
+    assert_eq!(
+        metadata.to_rust_struct("daily record"),
+        "#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]\n\
+         pub struct DailyRecord {\n    \
+         pub id: u64,\n    \
+         #[serde(rename = \"unitPrice\")]\n    \
+         pub unit_price: f64,\n    \
+         pub r#type: String,\n    \
+         #[serde(rename = \"Day Of Week\")]\n    \
+         pub day_of_week: String,\n    \
+         #[serde(rename = \"2nd\")]\n    \
+         pub _2nd: Option<u64>,\n\
+         }\n"
+    );
+}
+
+#[test]
+fn test_rust_struct_deserialize() {
+    #[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+    pub struct Record {
+        pub id: u64,
+        pub active: bool,
+        pub member: String,
+        pub amount: String,
+        pub price: f64,
+        pub score: Option<u64>,
+    }
+
+    // `y`/`n` booleans and grouped integers don't deserialize as `bool`s and `u64`s
+    let mut text = "id;active;member;amount;price;score\n".to_string();
+    for i in 1..=30 {
+        text.push_str(&format!(
+            "{};{};{};{},{:03};{}.25;{}\n",
+            i,
+            i % 2 == 0,
+            ["y", "n"][i % 2],
+            i,
+            i * 7,
+            i,
+            if i % 5 == 0 {
+                String::new()
+            } else {
+                i.to_string()
+            }
+        ));
+    }
+    let metadata = Sniffer::new().verify(true).sniff_str(&text).unwrap();
+    assert_eq!(
+        metadata.to_rust_struct("record"),
+        "#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]\n\
+         pub struct Record {\n    \
+         pub id: u64,\n    \
+         pub active: bool,\n    \
+         pub member: String,\n    \
+         pub amount: String,\n    \
+         pub price: f64,\n    \
+         pub score: Option<u64>,\n\
+         }\n"
+    );
+    let records = metadata
+        .dialect
+        .open_reader(Cursor::new(text.as_bytes()))
+        .unwrap()
+        .deserialize()
+        .collect::<Result<Vec<Record>, _>>()
+        .unwrap();
+    assert_eq!(records.len(), 30);
+    assert_eq!(
+        records[4],
+        Record {
+            id: 5,
+            active: false,
+            member: "n".into(),
+            amount: "5,035".into(),
+            price: 5.25,
+            score: None,
+        }
+    );
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn test_arrow_schema() {
+    use arrow_schema::{DataType, TimeUnit};
+
+    let mut text = "id,amount,day,at,label\n".to_string();
+    for i in 0..30 {
+        text.push_str(&format!(
+            "{},{}.5,2021-03-{:02},2021-03-04T05:06:{:02}Z,{}\n",
+            i,
+            -(i as i64),
+            i % 28 + 1,
+            i,
+            ["a", "b", "c"][i % 3]
+        ));
+    }
+    let metadata = Sniffer::new()
+        .detect_categorical(5)
+        .sniff_str(&text)
+        .unwrap();
+    let schema = metadata.to_arrow_schema();
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| (field.name().as_str(), field.data_type().clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        vec![
+            ("id", DataType::UInt64),
+            ("amount", DataType::Float64),
+            ("day", DataType::Date32),
+            (
+                "at",
+                DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into()))
+            ),
+            (
+                "label",
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+            ),
+        ]
+    );
+    assert!(schema.fields().iter().all(|field| field.is_nullable()));
+
+    let metadata = Sniffer::new()
+        .verify(true)
+        .sniff_reader(Cursor::new(text.as_bytes()))
+        .unwrap();
+    assert!(!metadata.to_arrow_schema().field(0).is_nullable());
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn test_arrow_schema_read() {
+    use std::sync::Arc;
+
+    use arrow_schema::{DataType, TimeUnit};
+
+    // values that `arrow-csv` doesn't parse (`yes`/`no` booleans, grouped integers and
+    // non-ISO dates) are strings
+    let mut text = "id;flag;member;amount;price;day;us_day;at;minute;time;clock\n".to_string();
+    for i in 1..=30 {
+        text.push_str(&format!(
+            "{};{};{};{},{:03};{}.5;2021-03-{:02};03/{:02}/2021;2021-03-04T05:06:{:02};\
+             2021-03-04 05:{:02};05:06:{:02};{}:30 PM\n",
+            i,
+            i % 2 == 0,
+            ["yes", "no"][i % 2],
+            i,
+            i * 7,
+            i,
+            i % 28 + 1,
+            i % 28 + 1,
+            i,
+            i,
+            i,
+            i % 12 + 1
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    let schema = metadata.to_arrow_schema();
+    assert_eq!(
+        schema
+            .fields()
+            .iter()
+            .map(|field| field.data_type().clone())
+            .collect::<Vec<_>>(),
+        vec![
+            DataType::UInt64,
+            DataType::Boolean,
+            DataType::Utf8,
+            DataType::Utf8,
+            DataType::Float64,
+            DataType::Date32,
+            DataType::Utf8,
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            DataType::Utf8,
+            DataType::Time64(TimeUnit::Nanosecond),
+            DataType::Time64(TimeUnit::Nanosecond),
+        ]
+    );
+
+    // the sample reads back with the schema
+    let reader = arrow_csv::ReaderBuilder::new(Arc::new(schema))
+        .with_header(true)
+        .with_delimiter(metadata.dialect.delimiter)
+        .build(Cursor::new(text.as_bytes()))
+        .unwrap();
+    let num_rows = reader.map(|batch| batch.unwrap().num_rows()).sum::<usize>();
+    assert_eq!(num_rows, 30);
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn test_parquet_schema() {
+    use parquet::basic::{ConvertedType, Repetition, Type as PhysicalType};
+
+    let mut text = "id,delta,price,day,name\n".to_string();
+    for i in 0..30 {
+        text.push_str(&format!(
+            "{},{},{}.{:02},2021-03-{:02},n{}\n",
+            i,
+            i as i64 - 10,
+            i * 7,
+            i,
+            i % 28 + 1,
+            i
+        ));
+    }
+    let metadata = Sniffer::new()
+        .detect_decimals(true)
+        .verify(true)
+        .sniff_reader(Cursor::new(text.as_bytes()))
+        .unwrap();
+    let schema = metadata.to_parquet_schema();
+    let columns = schema
+        .columns()
+        .iter()
+        .map(|column| {
+            (
+                column.name().to_string(),
+                column.physical_type(),
+                column.converted_type(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        columns,
+        vec![
+            (
+                "id".to_string(),
+                PhysicalType::INT64,
+                ConvertedType::UINT_64
+            ),
+            (
+                "delta".to_string(),
+                PhysicalType::INT64,
+                ConvertedType::NONE
+            ),
+            // small decimals are stored as 32-bit integers
+            (
+                "price".to_string(),
+                PhysicalType::INT32,
+                ConvertedType::DECIMAL
+            ),
+            ("day".to_string(), PhysicalType::INT32, ConvertedType::DATE),
+            (
+                "name".to_string(),
+                PhysicalType::BYTE_ARRAY,
+                ConvertedType::UTF8
+            ),
+        ]
+    );
+    assert!(schema
+        .columns()
+        .iter()
+        .all(|column| column.self_type().get_basic_info().repetition() == Repetition::REQUIRED));
+}
+
+#[cfg(feature = "polars")]
+#[test]
+fn test_polars_read_options() {
+    use polars::prelude::{DataType, SerReader};
+
+    let mut text = "exported 2021-03-04\nby admin\nid;price;day;name\n".to_string();
+    for i in 0..30 {
+        text.push_str(&format!(
+            "{};{}.{:02};2021-03-{:02};\"n {}, x\"\n",
+            i,
+            i * 7,
+            i,
+            i % 28 + 1,
+            i
+        ));
+    }
+    let metadata = Sniffer::new().delimiter(b';').sniff_str(&text).unwrap();
+    assert_eq!(metadata.dialect.header.num_preamble_rows, 2);
+    let schema = metadata.to_polars_schema();
+    assert_eq!(
+        schema.iter_values().cloned().collect::<Vec<_>>(),
+        vec![
+            DataType::UInt64,
+            DataType::Float64,
+            DataType::Date,
+            DataType::String
+        ]
+    );
+
+    let frame = metadata
+        .to_polars_read_options()
+        .into_reader_with_file_handle(Cursor::new(text.into_bytes()))
+        .finish()
+        .unwrap();
+    assert_eq!(frame.shape(), (30, 4));
+    assert_eq!(
+        frame
+            .get_column_names()
+            .iter()
+            .map(|name| name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["id", "price", "day", "name"]
+    );
+    assert_eq!(frame.schema().as_ref(), &schema);
+}
+
+#[cfg(feature = "datafusion")]
+#[tokio::test]
+async fn test_datafusion_read_options() {
+    use datafusion::prelude::SessionContext;
+
+    let mut text = "# exported daily\nid;price;name;member\n".to_string();
+    for i in 0..30 {
+        text.push_str(&format!(
+            "{};{}.5;'n {}; x';{}\n",
+            i,
+            i * 2,
+            i,
+            ["yes", "no"][i % 2]
+        ));
+    }
+    let path = std::env::temp_dir().join(format!("csv-sniffer-{}.csv", std::process::id()));
+    std::fs::write(&path, &text).unwrap();
+    let metadata = Sniffer::new()
+        .delimiter(b';')
+        .quote(Quote::Some(b'\''))
+        .sniff_path(&path)
+        .unwrap();
+    assert_eq!(metadata.dialect.comment, Comment::Enabled(b'#'));
+
+    let ctx = SessionContext::new();
+    let schema = metadata.to_arrow_schema();
+    ctx.register_csv(
+        "sniffed",
+        path.to_str().unwrap(),
+        metadata.to_datafusion_read_options(&schema),
+    )
+    .await
+    .unwrap();
+    let batches = ctx.table("sniffed").await.unwrap().collect().await.unwrap();
+    assert_eq!(
+        batches.iter().map(|batch| batch.num_rows()).sum::<usize>(),
+        30
+    );
+    let table = datafusion::arrow::util::pretty::pretty_format_batches(&batches)
+        .unwrap()
+        .to_string();
+    assert!(
+        table.contains("| 29 | 58.5  | n 29; x | no     |"),
+        "{}",
+        table
+    );
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,40 @@
+extern crate csv_sniffer;
+
+use std::io::Cursor;
+use std::path::Path;
+
+use csv_sniffer::error::SnifferError;
+use csv_sniffer::{SampleSize, Sniffer};
+
+#[test]
+fn test_fixed_width() {
+    let data = "\
+city         state  population
+Durham       NC         278993
+Cary         NC         170282
+Raleigh      NC         467665
+";
+    match Sniffer::new()
+        .detect_fixed_width(true)
+        .sniff_reader(Cursor::new(data.as_bytes()))
+    {
+        Err(SnifferError::FixedWidth(column_starts)) => {
+            assert_eq!(column_starts, vec![0, 13, 20]);
+        }
+        other => panic!("expected fixed-width error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_delimited_not_fixed_width() {
+    let data_filepath = Path::new(file!())
+        .parent()
+        .unwrap()
+        .join("data/library-visitors.csv");
+    let metadata = Sniffer::new()
+        .detect_fixed_width(true)
+        .sample_size(SampleSize::All)
+        .sniff_path(data_filepath)
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b',');
+}
@@ -0,0 +1,237 @@
+extern crate csv_sniffer;
+
+use std::io::Cursor;
+use std::path::Path;
+
+use csv_sniffer::metadata::*;
+use csv_sniffer::{HeaderHint, Sniffer, Type};
+
+#[test]
+fn test_header_text_columns() {
+    // every column is text, and the top-row values look like the values below them, so the top
+    // row is taken as data
+    let data = "name,city\nalice,paris\nbob,london\ncarol,rome\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert!(!metadata.dialect.header.has_header_row);
+}
+
+#[test]
+fn test_header_detection() {
+    // the top row doesn't fit the numeric columns below it
+    let data = "id,score\n1,2.5\n2,3.5\n3,4.5\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(metadata.field_names, vec!["id", "score"]);
+
+    // the top row fits the numeric columns below it
+    let data = "1,2.5\n2,3.5\n3,4.5\n4,5.5\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert!(!metadata.dialect.header.has_header_row);
+    assert_eq!(metadata.types, vec![Type::Unsigned, Type::Float]);
+}
+
+#[test]
+fn test_known_header_row() {
+    // the first data row is sniffed as data, even though it doesn't fit the rows below it
+    let data = "code,count\nA1,1\n2,3\n4,5\n6,7\n";
+    let metadata = Sniffer::new()
+        .header(HeaderHint::HasHeader)
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(metadata.field_names, vec!["code", "count"]);
+    assert_eq!(metadata.types, vec![Type::Text, Type::Unsigned]);
+}
+
+#[test]
+fn test_header_hint() {
+    let mut text = "# generated\n".to_string();
+    for i in 0..20 {
+        text.push_str(&format!("item {},colour {},size {}\n", i, i % 3, i % 5));
+    }
+    let metadata = Sniffer::new()
+        .header(HeaderHint::NoHeader)
+        .sniff_str(&text)
+        .unwrap();
+    assert!(!metadata.dialect.header.has_header_row);
+    assert_eq!(metadata.confidence.header, 1.0);
+    assert!(metadata.field_names.is_empty());
+    assert_eq!(metadata.dialect.comment, Comment::Enabled(b'#'));
+
+    let metadata = Sniffer::new()
+        .header(HeaderHint::HasHeader)
+        .sniff_str(&text)
+        .unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(metadata.field_names, ["item 0", "colour 0", "size 0"]);
+
+    // a specified header row presence can be reset to being detected
+    let detected = Sniffer::new().sniff_str(&text).unwrap();
+    let metadata = Sniffer::new()
+        .header(HeaderHint::NoHeader)
+        .header(HeaderHint::Detect)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(
+        metadata.dialect.header.has_header_row,
+        detected.dialect.header.has_header_row
+    );
+    assert_eq!(metadata.confidence.header, detected.confidence.header);
+}
+
+#[test]
+fn test_footer_rows() {
+    let data = "date,description,amount\n\
+                2024-01-01,Coffee,-3.50\n\
+                2024-01-02,Groceries,-54.20\n\
+                2024-01-03,Salary,2500.00\n\
+                2024-01-04,Rent,-1200.00\n\
+                2024-01-05,Lunch,-12.75\n\
+                2024-01-06,Books,-31.00\n\
+                2024-01-07,Refund,20.00\n\
+                2024-01-08,Dinner,-45.10\n\
+                Total,,1173.45\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.header.num_footer_rows, 1);
+    assert_eq!(metadata.types, vec![Type::Date, Type::Text, Type::Float]);
+
+    let mut reader = metadata
+        .dialect
+        .open_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(reader.records().count(), 9);
+
+    let options = ReadOptions {
+        skip_footer_rows: true,
+        ..ReadOptions::default()
+    };
+    let mut reader = metadata
+        .dialect
+        .open_reader_with(Cursor::new(data.as_bytes()), &options)
+        .unwrap();
+    let records = reader.records().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(records.len(), 8);
+    assert_eq!(&records[7][1], "Dinner");
+}
+
+#[test]
+fn test_header_all_text() {
+    let data = "first_name,last_name,city\nAl,Li,Oslo\nBo,Ng,Rome\nCy,Wu,Lima\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert!(metadata.confidence.header > 0.75);
+
+    let data = "Al,Li,Oslo\nBo,Ng,Rome\nCy,Wu,Lima\nDi,Xu,Oslo\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert!(!metadata.dialect.header.has_header_row);
+    assert!(metadata.confidence.header > 0.5);
+    assert!(metadata.field_names.is_empty());
+}
+
+#[test]
+fn test_preamble() {
+    let data_filepath = Path::new(file!()).parent().unwrap().join("data/gdp.csv");
+    let metadata = Sniffer::new().sniff_path(&data_filepath).unwrap();
+    assert_eq!(metadata.dialect.header.num_preamble_rows, 4);
+    assert_eq!(metadata.preamble.len(), 4);
+    let contents = std::fs::read(&data_filepath).unwrap();
+    assert!(contents[metadata.data_offset as usize..].starts_with(b"\"Country Name\""));
+    assert!(metadata.preamble[0].contains("World Development Indicators"));
+    assert_eq!(
+        metadata.preamble[2],
+        "\"Last Updated Date\",\"2018-03-01\","
+    );
+}
+
+#[test]
+fn test_tables() {
+    let data =
+        "name,count\nfoo,1\nbar,2\nbaz,3\n\n\nid;score;ratio\n1;x;0.5\n2;y;1.5\n3;z;2.5\n4;w;3.5\n";
+    let tables = Sniffer::new()
+        .sniff_tables(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(tables.len(), 2);
+
+    assert_eq!(tables[0].range, 0..29);
+    assert_eq!(tables[0].metadata.dialect.delimiter, b',');
+    assert_eq!(tables[0].metadata.num_fields, 2);
+
+    assert_eq!(tables[1].range, 31..data.len() as u64);
+    assert_eq!(tables[1].metadata.dialect.delimiter, b';');
+    assert_eq!(tables[1].metadata.num_fields, 3);
+    assert_eq!(tables[1].metadata.data_offset, 31);
+
+    let mut reader = tables[1].open_reader(Cursor::new(data.as_bytes())).unwrap();
+    assert_eq!(reader.headers().unwrap(), vec!["id", "score", "ratio"]);
+    assert_eq!(reader.records().count(), 4);
+}
+
+#[test]
+fn test_commented_preamble() {
+    // the comment marker is also the delimiter, so it appears on every line
+    let data = "; exported by tool\n; 2024-01-01\nid;name;score\n1;a;0.5\n2;b;1.5\n3;c;2.5\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.comment, Comment::Enabled(b';'));
+    assert_eq!(metadata.dialect.header.num_preamble_rows, 2);
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert!(!metadata.dialect.flexible);
+    assert_eq!(metadata.field_names, vec!["id", "name", "score"]);
+}
+
+#[test]
+fn test_footer_rows_small() {
+    // with few rows, each footer row is compared to all the rows above it
+    let data = "date,desc,ref,amount\n\
+                2024-01-01,Coffee,A1,3.50\n\
+                2024-01-02,Lunch,A2,4.25\n\
+                2024-01-03,Books,A3,2.75\n\
+                2024-01-04,Bus,A4,1.50\n\
+                Total,,,12.00\n";
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.header.num_footer_rows, 1);
+    assert_eq!(metadata.types[0], Type::Date);
+
+    // a footer row is told apart by the types of its values, not just whether they're numbers
+    let mut data = "date,amount\n".to_string();
+    for i in 1..=8 {
+        data.push_str(&format!("2024-01-0{},1.5\n", i));
+    }
+    data.push_str("Total,12.0\n");
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.header.num_footer_rows, 1);
+
+    // amounts aren't text, so a label below them stands out
+    let mut data = "price;item\n".to_string();
+    for (i, item) in [
+        "desk", "lamp", "sofa", "bed", "rug", "shelf", "chair", "mirror",
+    ]
+    .iter()
+    .enumerate()
+    {
+        data.push_str(&format!("${},{:03}.56;{}\n", i + 1, i, item));
+    }
+    data.push_str("Total;8 items\n");
+    let metadata = Sniffer::new()
+        .sniff_reader(Cursor::new(data.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.header.num_footer_rows, 1);
+    assert_eq!(metadata.types[0], Type::Currency { symbol: Some('$') });
+}
@@ -0,0 +1,226 @@
+extern crate csv_sniffer;
+
+use csv_sniffer::{SampleSize, Sniffer, Type};
+
+#[cfg(feature = "http")]
+#[test]
+fn test_sniff_url_empty_sample() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    use csv_sniffer::error::SnifferError;
+
+    let text = "id,name\n1,foo\n2,bar\n3,baz\n";
+
+    // serve a single request, ignoring any requested byte range
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/data.csv", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut range = None;
+        let mut reader = BufReader::new(&stream);
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line.trim().is_empty() {
+                break;
+            }
+            if let Some(value) = line.to_lowercase().strip_prefix("range:") {
+                range = Some(value.trim().to_string());
+            }
+        }
+        write!(
+            &stream,
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            text.len(),
+            text
+        )
+        .unwrap();
+        range
+    });
+
+    // no range is requested for an empty sample, which can't be sniffed
+    let result = Sniffer::new()
+        .sample_size(SampleSize::Bytes(0))
+        .strict_sample_size(true)
+        .sniff_url(&url);
+    assert_eq!(server.join().unwrap(), None);
+    assert!(matches!(result, Err(SnifferError::SniffingFailed(_))));
+}
+
+// A reader that can't seek, like a pipe or a network stream.
+struct Stream<R>(R);
+
+impl<R: std::io::Read> std::io::Read for Stream<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+#[test]
+fn test_stream() {
+    let mut text = "Exported data\n\nid,name,score\n".to_string();
+    for i in 0..50 {
+        text.push_str(&format!("{},name{},{}.5\n", i, i, i));
+    }
+
+    let metadata = Sniffer::new()
+        .sniff_stream(Stream(text.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert_eq!(metadata.dialect.header.num_preamble_rows, 2);
+    assert_eq!(metadata.num_fields, 3);
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Text, Type::Float]
+    );
+
+    // the reader covers the whole stream, not just the sample
+    let mut reader = Sniffer::new()
+        .sample_size(SampleSize::Records(10))
+        .open_stream(Stream(text.as_bytes()))
+        .unwrap();
+    assert_eq!(reader.headers().unwrap(), vec!["id", "name", "score"]);
+    let records = reader.records().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(records.len(), 50);
+    assert_eq!(&records[49][1], "name49");
+}
+
+#[test]
+fn test_sniff_bytes_str() {
+    let text = "a|b|c\n1|2|3\n4|5|6\n7|8|9\n";
+    let metadata = Sniffer::new().sniff_str(text).unwrap();
+    assert_eq!(metadata.dialect.delimiter, b'|');
+    assert_eq!(metadata.num_fields, 3);
+    assert_eq!(
+        Sniffer::new().sniff_bytes(text.as_bytes()).unwrap(),
+        metadata
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_sniff_async() {
+    use std::io::Cursor;
+    use std::path::Path;
+
+    let mut text = "id;name;score\n".to_string();
+    for i in 0..50 {
+        text.push_str(&format!("{};name{};{}.5\n", i, i, i));
+    }
+    let metadata = Sniffer::new()
+        .sniff_async(Cursor::new(text.as_bytes()))
+        .await
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Text, Type::Float]
+    );
+    assert_eq!(metadata, Sniffer::new().sniff_str(&text).unwrap());
+
+    let mut metadata = Sniffer::new()
+        .sniff_path_async(Path::new(file!()).parent().unwrap().join("data/gdp.csv"))
+        .await
+        .unwrap();
+    let expected = Sniffer::new()
+        .sniff_path(Path::new(file!()).parent().unwrap().join("data/gdp.csv"))
+        .unwrap();
+    // the asynchronous sample (of the same number of bytes) starts at the top of the file, so it
+    // has fewer records after the preamble to take statistics of (and to be as confident in the
+    // types)
+    assert!(metadata.type_counts[0][0].1 <= expected.type_counts[0][0].1);
+    metadata.type_counts.clone_from(&expected.type_counts);
+    metadata.null_counts.clone_from(&expected.null_counts);
+    metadata.mean_record_len = expected.mean_record_len;
+    // the estimated number of records is scaled up from the asynchronous sample
+    let (estimate, expected_estimate) = (
+        metadata.estimated_num_records.unwrap(),
+        expected.estimated_num_records.unwrap(),
+    );
+    assert!(estimate.abs_diff(expected_estimate) * 20 < expected_estimate);
+    metadata.estimated_num_records = expected.estimated_num_records;
+    metadata
+        .type_confidence
+        .clone_from(&expected.type_confidence);
+    assert_eq!(metadata, expected);
+}
+
+#[cfg(feature = "futures-io")]
+#[test]
+fn test_sniff_futures() {
+    let text = "id\tname\tscore\n1\tfoo\t1.5\n2\tbar\t2.5\n3\tbaz\t3.5\n";
+    let metadata = futures::executor::block_on(
+        Sniffer::new().sniff_futures(futures::io::Cursor::new(text.as_bytes())),
+    )
+    .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b'\t');
+    assert_eq!(metadata, Sniffer::new().sniff_str(text).unwrap());
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn test_sniff_url() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    let mut text = "id,name,score\n".to_string();
+    for i in 0..2000 {
+        text.push_str(&format!("{},name{},{}.5\n", i, i, i));
+    }
+
+    // serve a single request, honoring the requested byte range
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/data.csv", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut range_end = None;
+        let mut reader = BufReader::new(&stream);
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line.trim().is_empty() {
+                break;
+            }
+            if let Some(range) = line.to_lowercase().strip_prefix("range: bytes=0-") {
+                range_end = Some(range.trim().parse::<usize>().unwrap());
+            }
+        }
+        let range_end = range_end.expect("no range requested");
+        let body = &text.as_bytes()[..=range_end];
+        write!(
+            &stream,
+            "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes 0-{}/{}\r\n\
+             Connection: close\r\n\r\n",
+            body.len(),
+            range_end,
+            text.len()
+        )
+        .unwrap();
+        (&stream).write_all(body).unwrap();
+        range_end
+    });
+
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::Bytes(1 << 10))
+        .sniff_url(&url)
+        .unwrap();
+    assert!(server.join().unwrap() < 1 << 13);
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert_eq!(metadata.num_fields, 3);
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Text, Type::Float]
+    );
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_sniff_path_mmap() {
+    use std::path::Path;
+
+    let path = Path::new(file!()).parent().unwrap().join("data/gdp.csv");
+    let metadata = Sniffer::new().sniff_path_mmap(&path).unwrap();
+    assert_eq!(metadata, Sniffer::new().sniff_path(&path).unwrap());
+    assert_eq!(metadata.dialect.header.num_preamble_rows, 4);
+}
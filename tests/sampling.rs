@@ -0,0 +1,307 @@
+extern crate csv_sniffer;
+
+use std::io::Cursor;
+use std::time::Duration;
+
+use csv_sniffer::error::SnifferError;
+use csv_sniffer::progress::CancellationToken;
+use csv_sniffer::{SampleSize, Sampling, Sniffer, Type};
+
+#[test]
+fn test_time_budget_cancellation() {
+    let mut text = "id,value,label\n".to_string();
+    for i in 0..50_000 {
+        text.push_str(&format!("{},{},x{}\n", i, i % 10, i));
+    }
+
+    // cancelled while sniffing a larger sample than the first one
+    let token = CancellationToken::new();
+    let handle = token.clone();
+    let result = Sniffer::new()
+        .sample_size(SampleSize::TimeBudget(Duration::from_secs(60)))
+        .cancellation_token(token)
+        .on_progress(move |progress| {
+            if progress.bytes_read > 1 << 17 {
+                handle.cancel();
+            }
+        })
+        .sniff_str(&text);
+    assert!(matches!(result, Err(SnifferError::Cancelled)));
+}
+
+// A reader that records the furthest position read from.
+struct Tracked<R> {
+    inner: R,
+    max_pos: std::rc::Rc<std::cell::Cell<u64>>,
+}
+
+impl<R: std::io::Read + std::io::Seek> std::io::Read for Tracked<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n_read = self.inner.read(buf)?;
+        let pos = self.inner.stream_position()?;
+        self.max_pos.set(self.max_pos.get().max(pos));
+        Ok(n_read)
+    }
+}
+
+impl<R: std::io::Seek> std::io::Seek for Tracked<R> {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn test_strict_sample_size() {
+    let mut text = "id,name,score\n".to_string();
+    for i in 0..1000 {
+        text.push_str(&format!("{},name{},{}.5\n", i, i, i));
+    }
+    text.push_str("Total rows: 1000\n");
+
+    let max_pos = std::rc::Rc::new(std::cell::Cell::new(0));
+    let reader = Tracked {
+        inner: Cursor::new(text.as_bytes()),
+        max_pos: max_pos.clone(),
+    };
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::Bytes(1000))
+        .strict_sample_size(true)
+        .sniff_reader(reader)
+        .unwrap();
+    assert!(max_pos.get() <= 1000);
+    assert_eq!(metadata.num_fields, 3);
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Text, Type::Float]
+    );
+    assert_eq!(metadata.dialect.header.num_footer_rows, 0);
+
+    // without a hard limit, footer detection reads the end of the file
+    let max_pos = std::rc::Rc::new(std::cell::Cell::new(0));
+    let reader = Tracked {
+        inner: Cursor::new(text.as_bytes()),
+        max_pos: max_pos.clone(),
+    };
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::Bytes(1000))
+        .sniff_reader(reader)
+        .unwrap();
+    assert_eq!(max_pos.get(), text.len() as u64);
+    assert_eq!(metadata.dialect.header.num_footer_rows, 1);
+}
+
+#[test]
+fn test_time_budget() {
+    let mut text = "id,name,score\n".to_string();
+    for i in 0..20000 {
+        text.push_str(&format!("{},name{},{}.5\n", i, i, i));
+    }
+
+    let started = std::time::Instant::now();
+    let mut sniffer = Sniffer::new();
+    let metadata = sniffer
+        .sample_size(SampleSize::TimeBudget(std::time::Duration::from_millis(
+            200,
+        )))
+        .sniff_str(&text)
+        .unwrap();
+    assert!(started.elapsed() < std::time::Duration::from_secs(2));
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Text, Type::Float]
+    );
+
+    // small files are sniffed in full
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::TimeBudget(std::time::Duration::from_secs(60)))
+        .sniff_str("a;b\n1;x\n2;y\n3;z\n")
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert_eq!(metadata.types, vec![Type::Unsigned, Type::Text]);
+}
+
+#[test]
+fn test_random_blocks_sampling() {
+    // the head of the file is unrepresentative of the rest
+    let mut text = "id,value\n".to_string();
+    for i in 0..6000 {
+        text.push_str(&format!("{},{}\n", i, i % 10));
+    }
+    for i in 6000..12000 {
+        text.push_str(&format!("{},v{}\n", i, i % 10));
+    }
+
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(metadata.types, vec![Type::Unsigned, Type::Unsigned]);
+
+    let metadata = Sniffer::new()
+        .sampling(Sampling::RandomBlocks {
+            num_blocks: 8,
+            block_size: 1024,
+        })
+        .sniff_str(&text)
+        .unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(metadata.types, vec![Type::Unsigned, Type::Text]);
+}
+
+#[test]
+fn test_reservoir_sampling() {
+    // the head of the file is unrepresentative of the rest
+    let mut text = "id,value\n".to_string();
+    for i in 0..6000 {
+        text.push_str(&format!("{},{}\n", i, i % 10));
+    }
+    for i in 6000..12000 {
+        text.push_str(&format!("{},{}.5\n", i, i % 10));
+    }
+
+    let metadata = Sniffer::new()
+        .sampling(Sampling::Reservoir { num_records: 100 })
+        .sniff_str(&text)
+        .unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(metadata.types, vec![Type::Unsigned, Type::Float]);
+}
+
+#[test]
+fn test_verify() {
+    let mut text = "id,value,label\n".to_string();
+    for i in 0..3000 {
+        text.push_str(&format!("{},{},x{}\n", i, i % 10, i));
+        if i == 1500 {
+            text.push_str("1500,7,x1500,extra\n");
+        }
+    }
+    text.push_str("3000,-4,x3000\n3001,7,x3001\n");
+
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::Records(100))
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.verification, None);
+    assert!(!metadata.dialect.flexible);
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Unsigned, Type::Text]
+    );
+
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::Records(100))
+        .verify(true)
+        .sniff_str(&text)
+        .unwrap();
+    let verification = metadata.verification.unwrap();
+    assert!(!verification.is_valid());
+    assert_eq!(verification.num_records, 3003);
+    assert_eq!(verification.num_field_count_violations, 1);
+    assert_eq!(verification.num_type_violations, 1);
+    assert!(metadata.dialect.flexible);
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Signed, Type::Text]
+    );
+}
+
+#[test]
+fn test_on_progress() {
+    use csv_sniffer::progress::{Progress, Stage};
+    use std::sync::{Arc, Mutex};
+
+    let mut text = "id,value,label\n".to_string();
+    for i in 0..1000 {
+        text.push_str(&format!("{},{},x{}\n", i, i % 10, i));
+    }
+
+    let reports: Arc<Mutex<Vec<Progress>>> = Arc::default();
+    let sink = reports.clone();
+    let metadata = Sniffer::new()
+        .verify(true)
+        .on_progress(move |progress| sink.lock().unwrap().push(progress))
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b',');
+
+    let reports = reports.lock().unwrap();
+    let mut stages = reports.iter().map(|p| p.stage).collect::<Vec<_>>();
+    stages.dedup();
+    for stage in &[
+        Stage::Encoding,
+        Stage::Terminator,
+        Stage::Delimiter,
+        Stage::Preamble,
+        Stage::Footer,
+        Stage::Header,
+        Stage::Types,
+        Stage::Verification,
+    ] {
+        assert!(stages.contains(stage), "missing stage {:?}", stage);
+    }
+    assert_eq!(stages.last(), Some(&Stage::Verification));
+    // bytes read never decrease, and verification reads the whole file
+    assert!(reports
+        .windows(2)
+        .all(|w| w[0].bytes_read <= w[1].bytes_read));
+    assert!(reports.last().unwrap().bytes_read >= text.len() as u64);
+}
+
+#[test]
+fn test_cancellation() {
+    use csv_sniffer::error::SnifferError;
+    use csv_sniffer::progress::CancellationToken;
+
+    let mut text = "id,value,label\n".to_string();
+    for i in 0..10000 {
+        text.push_str(&format!("{},{},x{}\n", i, i % 10, i));
+    }
+
+    // cancelled before sniffing
+    let token = CancellationToken::new();
+    token.cancel();
+    let result = Sniffer::new().cancellation_token(token).sniff_str(&text);
+    assert!(matches!(result, Err(SnifferError::Cancelled)));
+
+    // cancelled mid-sniff, from the progress callback
+    let token = CancellationToken::new();
+    let handle = token.clone();
+    let result = Sniffer::new()
+        .sample_size(SampleSize::All)
+        .cancellation_token(token)
+        .on_progress(move |progress| {
+            if progress.bytes_read > 10_000 {
+                handle.cancel();
+            }
+        })
+        .sniff_str(&text);
+    assert!(matches!(result, Err(SnifferError::Cancelled)));
+
+    // an uncancelled token doesn't affect sniffing
+    let metadata = Sniffer::new()
+        .cancellation_token(CancellationToken::new())
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b',');
+}
+
+#[test]
+fn test_sample_read_once() {
+    use std::sync::{Arc, Mutex};
+
+    let mut text = "id,value,label\n".to_string();
+    for i in 0..1000 {
+        text.push_str(&format!("{},{},\"x {}\"\n", i, i % 10, i));
+    }
+    assert!(text.len() < 1 << 14);
+
+    // every stage reads the sample from memory, so the file is only read once
+    let bytes_read = Arc::new(Mutex::new(0));
+    let sink = bytes_read.clone();
+    let metadata = Sniffer::new()
+        .on_progress(move |progress| *sink.lock().unwrap() = progress.bytes_read)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.num_fields, 3);
+    assert_eq!(*bytes_read.lock().unwrap(), text.len() as u64);
+}
@@ -0,0 +1,184 @@
+extern crate csv_sniffer;
+
+use std::io::Cursor;
+
+use csv_sniffer::metadata::*;
+use csv_sniffer::{Sniffer, Type};
+
+#[test]
+fn test_merge() {
+    let mut first = "id,amount,name,day\n".to_string();
+    let mut second = "day,id,amount,extra\n".to_string();
+    for i in 0..20 {
+        first.push_str(&format!(
+            "{},{},name {},2021-03-{:02}\n",
+            i,
+            i * 3,
+            i,
+            i + 1
+        ));
+        second.push_str(&format!(
+            "2021-04-{:02},{},{}.5,x{}\n",
+            i + 1,
+            i as i64 - 10,
+            i,
+            i
+        ));
+    }
+    let dir = std::env::temp_dir();
+    let paths = ["first", "second"].map(|name| {
+        dir.join(format!(
+            "csv-sniffer-merge-{}-{}.csv",
+            std::process::id(),
+            name
+        ))
+    });
+    std::fs::write(&paths[0], &first).unwrap();
+    std::fs::write(&paths[1], &second).unwrap();
+    let merged = Sniffer::new().sniff_paths(&paths);
+    for path in &paths {
+        std::fs::remove_file(path).unwrap();
+    }
+    let merged = merged.unwrap();
+    assert_eq!(merged.field_names, ["id", "amount", "name", "day", "extra"]);
+    assert_eq!(
+        merged.types,
+        [
+            Type::Signed,
+            Type::Float,
+            Type::Text,
+            Type::Date,
+            Type::Text
+        ]
+    );
+    assert_eq!(merged.formats[3].as_deref(), Some("%Y-%m-%d"));
+    assert_eq!(merged.num_fields, 5);
+    assert_eq!(merged.estimated_num_records, Some(40));
+    assert_eq!(merged.nullable, [false, false, true, false, true]);
+
+    // fields are matched by position without header rows
+    let metadata = Sniffer::new()
+        .sniff_str("1,2021-03-04\n2,2021-03-05\n3,2021-03-06\n")
+        .unwrap();
+    let other = Sniffer::new()
+        .sniff_str("-1,04/03/2021\n-2,05/03/2021\n-3,06/03/2021\n")
+        .unwrap();
+    let merged = metadata.merge(&other);
+    assert_eq!(merged.types, [Type::Signed, Type::Text]);
+    assert!(Sniffer::new().sniff_paths(Vec::<String>::new()).is_err());
+}
+
+#[test]
+fn test_schema_diff() {
+    let mut old = "id,amount,name,day\n".to_string();
+    let mut new = "id;amount;day;extra\n".to_string();
+    for i in 0..20 {
+        old.push_str(&format!(
+            "{},{},name {},2021-03-{:02}\n",
+            i,
+            i * 3,
+            i,
+            i + 1
+        ));
+        new.push_str(&format!("{};{}.5;{:02}/04/2021;x{}\n", i, i, i + 1, i));
+    }
+    let old = Sniffer::new().sniff_str(&old).unwrap();
+    let new = Sniffer::new().sniff_str(&new).unwrap();
+    assert!(old.diff(&old).is_empty());
+
+    let diff = old.diff(&new);
+    assert_eq!(diff.added, ["extra"]);
+    assert_eq!(diff.removed, ["name"]);
+    assert_eq!(
+        diff.type_changes,
+        [
+            TypeChange {
+                field: "amount".into(),
+                from: Type::Unsigned,
+                to: Type::Float,
+                from_format: None,
+                to_format: None,
+            },
+            TypeChange {
+                field: "day".into(),
+                from: Type::Date,
+                to: Type::Date,
+                from_format: Some("%Y-%m-%d".into()),
+                to_format: Some("%d/%m/%Y".into()),
+            },
+        ]
+    );
+    assert_eq!(
+        diff.dialect_changes,
+        [DialectChange {
+            setting: "delim".into(),
+            from: ",".into(),
+            to: ";".into(),
+        }]
+    );
+    assert_eq!(
+        diff.to_string(),
+        "Schema diff:\n\tAdded fields: extra\n\tRemoved fields: name\n\tType changes:\n\
+         \t\tamount: Unsigned -> Float\n\t\tday: Date (%Y-%m-%d) -> Date (%d/%m/%Y)\n\
+         \tDialect changes:\n\t\tdelim: , -> ;\n"
+    );
+}
+
+#[test]
+fn test_validate() {
+    let mut good = "id,amount,day\n".to_string();
+    for i in 0..20 {
+        good.push_str(&format!("{},{}.5,2021-03-{:02}\n", i, i, i + 1));
+    }
+    let expected = Sniffer::new().sniff_str(&good).unwrap();
+    let validation = Sniffer::new()
+        .validate_reader(Cursor::new(good.as_bytes()), &expected)
+        .unwrap();
+    assert!(validation.is_valid());
+    assert_eq!(validation.num_records, 20);
+
+    let bad = good
+        .replace("3,3.5,", "3,abc,")
+        .replace("2021-03-06", "06/03/2021")
+        .replace("7,7.5,2021-03-08", "7,7.5,2021-03-08,x");
+    let validation = Sniffer::new()
+        .validate_reader(Cursor::new(bad.as_bytes()), &expected)
+        .unwrap();
+    assert_eq!(validation.num_field_count_violations, 1);
+    assert_eq!(validation.num_type_violations, 2);
+    assert_eq!(
+        validation.violations,
+        [
+            Violation::Type {
+                record: 3,
+                field: "amount".into(),
+                value: "abc".into(),
+                expected: Type::Float,
+            },
+            Violation::Type {
+                record: 5,
+                field: "day".into(),
+                value: "06/03/2021".into(),
+                expected: Type::Date,
+            },
+            Violation::FieldCount {
+                record: 7,
+                expected: 3,
+                found: 4,
+            },
+        ]
+    );
+
+    let with_preamble = format!("Daily export\nGenerated at 06:00\n\n{}", good);
+    let validation = Sniffer::new()
+        .validate_reader(Cursor::new(with_preamble.as_bytes()), &expected)
+        .unwrap();
+    assert!(!validation.is_valid());
+    assert_eq!(
+        validation.violations[0],
+        Violation::Preamble {
+            expected: 0,
+            found: 3,
+        }
+    );
+}
@@ -0,0 +1,352 @@
+extern crate csv_sniffer;
+
+use std::io::Cursor;
+
+use csv_sniffer::metadata::*;
+use csv_sniffer::{ExampleValues, SampleSize, Sniffer, Type};
+
+#[test]
+fn test_numeric_statistics() {
+    let mut text = "id,delta,price,share,name\n".to_string();
+    for i in 1..=10 {
+        text.push_str(&format!(
+            "{},{},{},{}%,name{}\n",
+            i,
+            if i == 3 {
+                String::new()
+            } else {
+                (i - 5).to_string()
+            },
+            i as f64 * 1.5,
+            i * 10,
+            i
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(metadata.statistics, vec![None; 5]);
+
+    let metadata = Sniffer::new()
+        .compute_statistics(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Signed,
+            Type::Float,
+            Type::Percentage,
+            Type::Text
+        ]
+    );
+    let expected = [
+        (10, 1.0, 10.0, 5.5),
+        (9, -4.0, 5.0, 7.0 / 9.0),
+        (10, 1.5, 15.0, 8.25),
+        (10, 10.0, 100.0, 55.0),
+    ];
+    for (stats, &(count, min, max, mean)) in metadata.statistics.iter().zip(&expected) {
+        let stats = stats.unwrap();
+        assert_eq!((stats.count, stats.min, stats.max), (count, min, max));
+        assert!((stats.mean - mean).abs() < 1e-9);
+    }
+    assert_eq!(metadata.statistics[4], None);
+
+    // offending values aren't included, and verifying covers the whole file
+    let text = text.replacen("\n6,", "\n11,n/a,16.5,110%,name11\n6,", 1);
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::Records(5))
+        .type_tolerance(0.9)
+        .compute_statistics(true)
+        .verify(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.types[1], Type::Signed);
+    let stats = metadata.statistics[1].unwrap();
+    assert_eq!((stats.count, stats.min, stats.max), (9, -4.0, 5.0));
+    assert_eq!(metadata.statistics[0].unwrap().count, 11);
+    assert_eq!(metadata.statistics[2].unwrap().max, 16.5);
+}
+
+#[test]
+fn test_length_statistics() {
+    let data = "name,code,note\nZoë,1,\nJosé Álvarez,22,n/a\nAl,333,\nMaría,4444,\n";
+    let metadata = Sniffer::new().sniff_str(data).unwrap();
+    assert_eq!(metadata.lengths, vec![None; 3]);
+
+    let metadata = Sniffer::new()
+        .compute_statistics(true)
+        .sniff_str(data)
+        .unwrap();
+    let name = metadata.lengths[0].unwrap();
+    assert_eq!(name.count, 4);
+    assert_eq!((name.min_chars, name.max_chars), (2, 12));
+    assert_eq!((name.min_bytes, name.max_bytes), (2, 14));
+    assert_eq!(name.mean_chars, 5.5);
+    assert_eq!(name.mean_bytes, 6.5);
+    let code = metadata.lengths[1].unwrap();
+    assert_eq!(
+        (code.min_chars, code.max_chars, code.mean_chars),
+        (1, 4, 2.5)
+    );
+    // null values aren't counted
+    let note = metadata.lengths[2].unwrap();
+    assert_eq!((note.count, note.max_bytes), (1, 3));
+}
+
+#[test]
+fn test_distinct_counts() {
+    let mut text = "id,group,flag,empty\n".to_string();
+    for i in 0..5000 {
+        text.push_str(&format!("{},g{},{},\n", i, i % 40, i % 2 == 0));
+    }
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::All)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.distinct_counts, vec![None; 4]);
+
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::All)
+        .compute_statistics(true)
+        .sniff_str(&text)
+        .unwrap();
+    let ids = metadata.distinct_counts[0].unwrap() as f64;
+    assert!((ids - 5000.0).abs() / 5000.0 < 0.05);
+    assert_eq!(metadata.distinct_counts[1], Some(40));
+    assert_eq!(metadata.distinct_counts[2], Some(2));
+    // null values aren't counted
+    assert_eq!(metadata.distinct_counts[3], Some(0));
+}
+
+#[test]
+fn test_null_counts() {
+    let mut text = "id,score,note\n".to_string();
+    for i in 0..20 {
+        text.push_str(&format!(
+            "{},{},{}\n",
+            i,
+            match i % 5 {
+                0 => "",
+                1 => "NA",
+                _ => "7",
+            },
+            if i == 4 { "-" } else { "ok" }
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Unsigned, Type::Text]
+    );
+    assert_eq!(metadata.nullable, vec![false, true, false]);
+    assert_eq!(metadata.null_counts, vec![0, 8, 0]);
+
+    let metadata = Sniffer::new()
+        .null_tokens(&["", "NA", "-"])
+        .verify(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.null_counts, vec![0, 8, 1]);
+}
+
+#[test]
+fn test_example_values() {
+    let mut text = "id,name\n".to_string();
+    for i in 0..100 {
+        let name = if i % 10 == 3 {
+            String::new()
+        } else {
+            format!("name{}", i)
+        };
+        text.push_str(&format!("{},{}\n", i, name));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(metadata.examples, vec![Vec::<String>::new(); 2]);
+
+    let metadata = Sniffer::new()
+        .example_values(ExampleValues::First(3))
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(
+        metadata.examples,
+        vec![
+            vec!["0".to_string(), "1".to_string(), "2".to_string()],
+            // null values aren't examples
+            vec![
+                "name0".to_string(),
+                "name1".to_string(),
+                "name2".to_string()
+            ],
+        ]
+    );
+
+    let sniff_random = || {
+        Sniffer::new()
+            .sample_size(SampleSize::All)
+            .example_values(ExampleValues::Random(5))
+            .sniff_str(&text)
+            .unwrap()
+    };
+    let metadata = sniff_random();
+    assert_eq!(metadata.examples[0].len(), 5);
+    assert_ne!(metadata.examples[0], ["0", "1", "2", "3", "4"]);
+    assert!(metadata.examples[1]
+        .iter()
+        .all(|name| name.starts_with("name")));
+    // the examples are chosen deterministically
+    assert_eq!(sniff_random().examples, metadata.examples);
+}
+
+#[test]
+fn test_estimated_num_records() {
+    let mut text = "id,name,amount\n".to_string();
+    for i in 0..20000 {
+        text.push_str(&format!(
+            "{:05},name {},{}.{}\n",
+            i,
+            i % 977,
+            i * 7 % 100000,
+            i % 100
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    let estimate = metadata.estimated_num_records.unwrap() as f64;
+    assert!((estimate - 20000.0).abs() / 20000.0 < 0.05);
+
+    // exact if the whole file is sampled
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::All)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.estimated_num_records, Some(20000));
+    let metadata = Sniffer::new().verify(true).sniff_str(&text).unwrap();
+    assert_eq!(metadata.estimated_num_records, Some(20000));
+    let data = "id,note,count\n1,\"two\nlines\",5\n2,plain,6\n3,\"quoted, text\",7\n4,x,8\n";
+    let metadata = Sniffer::new()
+        .delimiter(b',')
+        .quote(Quote::Some(b'"'))
+        .sniff_str(data)
+        .unwrap();
+    assert_eq!(metadata.estimated_num_records, Some(4));
+
+    // the rest of a stream isn't read
+    let metadata = Sniffer::new()
+        .sniff_stream(Cursor::new(text.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.estimated_num_records, None);
+    let head = text.lines().take(41).collect::<Vec<_>>().join("\n") + "\n";
+    let metadata = Sniffer::new()
+        .sniff_stream(Cursor::new(head.as_bytes()))
+        .unwrap();
+    assert_eq!(metadata.estimated_num_records, Some(40));
+}
+
+#[test]
+fn test_record_lengths() {
+    let mut text = "id,name\n".to_string();
+    for i in 0..20 {
+        let name = if i == 7 {
+            "x".repeat(100)
+        } else {
+            "ab".to_string()
+        };
+        text.push_str(&format!("{:02},{}\n", i, name));
+    }
+    // records are 6 bytes long (including the terminator), except one of 104 bytes
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(metadata.max_record_len, 104);
+    assert_eq!(metadata.mean_record_len, (19.0 * 6.0 + 104.0) / 20.0);
+
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::Records(5))
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.max_record_len, 6);
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::Records(5))
+        .verify(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.max_record_len, 104);
+    assert_eq!(metadata.mean_record_len, (19.0 * 6.0 + 104.0) / 20.0);
+}
+
+#[test]
+fn test_increasing_columns() {
+    let mut text = "Index,id,score,delta,name\n".to_string();
+    for i in 1..=30 {
+        text.push_str(&format!(
+            "{},{},{},{},row{}\n",
+            i,
+            1000 + i * 7,
+            (i * 37) % 11,
+            -100 + i,
+            i
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(metadata.increasing, vec![true, true, false, true, false]);
+
+    // the top row is included when it isn't a header row
+    let data = text.split_once('\n').unwrap().1;
+    let metadata = Sniffer::new()
+        .header(Header {
+            has_header_row: false,
+            num_preamble_rows: 0,
+            num_footer_rows: 0,
+        })
+        .sniff_str(data)
+        .unwrap();
+    assert_eq!(metadata.increasing, vec![true, true, false, true, false]);
+
+    // verifying checks the whole file
+    let text = text + "15,2000,3,-200,row31\n";
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::Records(10))
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.increasing, vec![true, true, false, true, false]);
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::Records(10))
+        .verify(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.increasing, vec![false, true, false, false, false]);
+}
+
+#[test]
+fn test_primary_keys() {
+    let mut text = "id,code,region,year,note\n".to_string();
+    for i in 0..40 {
+        text.push_str(&format!(
+            "{},C{:03},r{},{},{}\n",
+            i + 1,
+            i * 3,
+            i % 8,
+            2000 + i / 8,
+            if i % 2 == 0 { "" } else { "x" }
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert!(metadata.primary_keys.is_empty());
+
+    let metadata = Sniffer::new()
+        .detect_primary_keys(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.primary_keys, vec![vec![0], vec![1]]);
+
+    // without single-column keys, pairs of columns are considered (null values never are keys)
+    let text = text
+        .lines()
+        .map(|line| line.splitn(3, ',').nth(2).unwrap())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let metadata = Sniffer::new()
+        .detect_primary_keys(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.types[1], Type::Unsigned);
+    assert_eq!(metadata.primary_keys, vec![vec![0, 1]]);
+}
@@ -0,0 +1,1342 @@
+extern crate csv_sniffer;
+
+use std::io::Cursor;
+
+use csv_sniffer::metadata::*;
+use csv_sniffer::{BinaryEncoding, SampleSize, SemanticType, Sniffer, Type};
+
+#[test]
+fn test_compact_dates() {
+    // 7-digit IDs aren't compact dates (e.g. 2020-12-3 or 1000-10-1)
+    let data = "id,count\n2020123,1\n1000101,2\n2021115,3\n1999012,4\n";
+    let metadata = Sniffer::new().sniff_str(data).unwrap();
+    assert_eq!(metadata.types, vec![Type::Unsigned, Type::Unsigned]);
+
+    // neither are 8-digit IDs with implausible years
+    let data = "id,count\n01010101,1\n09091212,2\n05050505,3\n";
+    let metadata = Sniffer::new().sniff_str(data).unwrap();
+    assert_ne!(metadata.types[0], Type::Date);
+
+    let data = "day,count\n20201203,1\n19991231,2\n20210101,3\n";
+    let metadata = Sniffer::new().sniff_str(data).unwrap();
+    assert_eq!(metadata.types, vec![Type::Date, Type::Unsigned]);
+    assert_eq!(metadata.formats[0].as_deref(), Some("%Y%m%d"));
+}
+
+#[test]
+fn test_verified_decimals() {
+    // with a hinted decimal column, integer columns found to have fractions past the sample are
+    // widened to decimals while verifying
+    let mut data = "price,count\n".to_string();
+    for i in 0..100 {
+        data.push_str(&format!("{}.25,{}\n", i, i));
+    }
+    data.push_str("1.50,2.75\n");
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::Records(10))
+        .type_hint(
+            "price",
+            Type::Decimal {
+                precision: 5,
+                scale: 2,
+            },
+        )
+        .verify(true)
+        .sniff_reader(Cursor::new(data))
+        .unwrap();
+    assert_eq!(
+        metadata.types[1],
+        Type::Decimal {
+            precision: 4,
+            scale: 2
+        }
+    );
+}
+
+#[test]
+fn test_datetime_types() {
+    let text = "\
+id,created,updated,local,note
+1,2021-03-04T05:06:07Z,03/04/2021 05:06:07,13/04/2021 05:06,a
+2,2021-03-04T05:06:07.123+01:00,12/31/2021 23:59:59,01/02/2021 00:00,b
+3,2021-12-31T23:59:60-08:00,01/01/2022 00:00:00,31/12/2021 12:30,c
+4,2022-01-01T00:00:00.5Z,02/28/2022 12:00:00,28/02/2022 18:45,2022-01-01T00:00:00Z
+";
+    let metadata = Sniffer::new().sniff_str(text).unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::DateTime,
+            Type::DateTime,
+            Type::DateTime,
+            Type::Text
+        ]
+    );
+    assert_eq!(
+        metadata.formats,
+        vec![
+            None,
+            Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()),
+            Some("%m/%d/%Y %H:%M:%S".to_string()),
+            Some("%d/%m/%Y %H:%M".to_string()),
+            None
+        ]
+    );
+
+    // out-of-range components aren't date-times
+    let text = "id,when\n1,2021-13-04T05:06:07Z\n2,2021-03-04T05:06:07Z\n";
+    let metadata = Sniffer::new().sniff_str(text).unwrap();
+    assert_eq!(metadata.types, vec![Type::Unsigned, Type::Text]);
+    assert_eq!(metadata.formats, vec![None, None]);
+}
+
+#[test]
+fn test_date_types() {
+    let text = "\
+id,iso,eu,us,compact,named,stamp
+1,2021-03-04,13/04/2021,03-04-2021,20210304,March 4 2021,2021-03-04T05:06:07Z
+2,2021-12-31,01/02/2021,12-31-2021,20211231,April 10 2021,2021-03-05T05:06:07Z
+3,2022-01-01,31/12/2021,01-01-2022,20220101,may 1 2022,2021-03-06T05:06:07Z
+";
+    let metadata = Sniffer::new().sniff_str(text).unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Date,
+            Type::Date,
+            Type::Date,
+            Type::Date,
+            Type::Text,
+            Type::DateTime,
+        ]
+    );
+    assert_eq!(
+        metadata.formats,
+        vec![
+            None,
+            Some("%Y-%m-%d".to_string()),
+            Some("%d/%m/%Y".to_string()),
+            Some("%m-%d-%Y".to_string()),
+            Some("%Y%m%d".to_string()),
+            None,
+            Some("%Y-%m-%dT%H:%M:%S%.f%:z".to_string()),
+        ]
+    );
+
+    // month names, in a consistent format (or not)
+    let text = "\
+id,day,when,mixed
+1,04 Mar 2021,4 March 2021,04 Mar 2021
+2,10 apr 2021,10 April 2021,10-Apr-2021
+3,31 Dec 2021,31 December 2021,31 Dec 2021
+4,01 JAN 2022,1 January 2022,01-Jan-2022
+";
+    let metadata = Sniffer::new().sniff_str(text).unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Date, Type::Date, Type::Text]
+    );
+    assert_eq!(
+        metadata.formats,
+        vec![
+            None,
+            Some("%d %b %Y".to_string()),
+            Some("%d %B %Y".to_string()),
+            None,
+        ]
+    );
+}
+
+#[test]
+fn test_time_types() {
+    let text = "\
+id,start,end,alarm,note
+1,13:45,13:45:09,1:45 PM,a
+2,00:00,23:59:59.250,12:00 am,b
+3,9:05,07:30:00,11:59 PM,c
+4,23:59,00:00:60,6:30 AM,13:00
+";
+    let metadata = Sniffer::new().sniff_str(text).unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Time,
+            Type::Time,
+            Type::Time,
+            Type::Text
+        ]
+    );
+    assert_eq!(
+        metadata.formats,
+        vec![
+            None,
+            Some("%H:%M".to_string()),
+            Some("%H:%M:%S%.f".to_string()),
+            Some("%I:%M %p".to_string()),
+            None,
+        ]
+    );
+}
+
+#[test]
+fn test_boolean_types() {
+    let text = "\
+id,flag,answer,short,letter,german
+1,TRUE,yes,Y,t,ja
+2,false,No,n,F,nein
+3,True,YES,y,T,Nein
+4,FALSE,no,N,f,ja
+";
+    let metadata = Sniffer::new().sniff_str(text).unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Boolean,
+            Type::Boolean,
+            Type::Boolean,
+            Type::Boolean,
+            Type::Text
+        ]
+    );
+
+    // custom tokens replace the defaults
+    let metadata = Sniffer::new()
+        .boolean_tokens(&["ja"], &["nein"])
+        .sniff_str(text)
+        .unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Text,
+            Type::Text,
+            Type::Text,
+            Type::Text,
+            Type::Boolean
+        ]
+    );
+}
+
+#[test]
+fn test_decimal_types() {
+    let text = "\
+id,price,rate,ratio,count
+1,1234.50,0.125,1e-3,10
+2,-3.10,0.5,2.5,20
+3,00012.00,0.07,0.25,30
+4,999999.99,0.0001,1,40
+";
+    let metadata = Sniffer::new().sniff_str(text).unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Float,
+            Type::Float,
+            Type::Float,
+            Type::Unsigned
+        ]
+    );
+
+    let metadata = Sniffer::new()
+        .detect_decimals(true)
+        .sniff_str(text)
+        .unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Decimal {
+                precision: 8,
+                scale: 2
+            },
+            Type::Decimal {
+                precision: 4,
+                scale: 4
+            },
+            Type::Float,
+            Type::Unsigned
+        ]
+    );
+    assert_eq!(metadata.types[1].to_string(), "Decimal(8, 2)");
+
+    // verification widens the precision and scale
+    let mut text = "id,amount\n".to_string();
+    for i in 0..1000 {
+        text.push_str(&format!("{},{}.25\n", i, i % 10));
+    }
+    text.push_str("1000,12345.125\n");
+    let metadata = Sniffer::new()
+        .detect_decimals(true)
+        .sample_size(SampleSize::Records(10))
+        .verify(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.verification.unwrap().num_type_violations, 1);
+    assert_eq!(
+        metadata.types[1],
+        Type::Decimal {
+            precision: 8,
+            scale: 3
+        }
+    );
+}
+
+#[test]
+fn test_currency_types() {
+    let text = "\
+id,usd,eur,balance,plain,mixed
+1,\"$1,234.56\",€12.00,\"(1,000.00)\",\"1,234\",$5
+2,$0.99,€ 7,250.00,\"12,345,678\",€6
+3,\"-$12,000\",-€3.50,\"(7.25)\",\"999\",7
+4,$5,12.00 €,-3,\"1,000\",8.25
+";
+    let metadata = Sniffer::new().sniff_str(text).unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Currency { symbol: Some('$') },
+            Type::Currency {
+                symbol: Some('€')
+            },
+            Type::Currency { symbol: None },
+            Type::Unsigned,
+            Type::Currency { symbol: Some('$') },
+        ]
+    );
+    assert_eq!(metadata.types[1].to_string(), "Currency($)");
+
+    // malformed grouping isn't an amount
+    let mut text = "id,value\n".to_string();
+    for i in 0..20 {
+        text.push_str(&format!("\"{}\",\"€{},{:03}.50\"\n", i, i + 1, i));
+    }
+    text.push_str("\"20\",\"1,23\"\n");
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::All)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.types, vec![Type::Unsigned, Type::Text]);
+}
+
+#[test]
+fn test_percentage_types() {
+    let text = "\
+id,rate,change,mixed,amount
+1,12.5%,-3%,7 %,$5
+2,0.25%,+1.5%,8,$6
+3,100%,-0.75%,9.5%,7
+4,50%,2%,10,8.25
+5,7.5%,-10%,11%,$9
+";
+    let metadata = Sniffer::new().sniff_str(text).unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Percentage,
+            Type::Percentage,
+            Type::Percentage,
+            Type::Currency { symbol: Some('$') },
+        ]
+    );
+    assert_eq!(metadata.types[1].to_string(), "Percentage");
+}
+
+#[test]
+fn test_nullable_fields() {
+    let text = "\
+id,count,score,name,flag
+1,10,1.5,alice,true
+2,NA,2.5,bob,false
+3,12,,carol,true
+4,\\N,4.5,dave,false
+5,NULL,5.5,erin,true
+6,15,6.5,frank,false
+";
+    let metadata = Sniffer::new().sniff_str(text).unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Unsigned,
+            Type::Float,
+            Type::Text,
+            Type::Boolean
+        ]
+    );
+    assert_eq!(metadata.nullable, vec![false, true, true, false, false]);
+
+    // with custom tokens, `NA` is a value (so the column is text), and `-` is null
+    let text = text.replace(",,", ",-,");
+    let metadata = Sniffer::new().null_tokens(&["-"]).sniff_str(&text).unwrap();
+    assert_eq!(metadata.types[1], Type::Text);
+    assert_eq!(metadata.types[2], Type::Float);
+    assert_eq!(metadata.nullable, vec![false, false, true, false, false]);
+}
+
+#[test]
+fn test_scientific_notation() {
+    let mut text = "time;temp;pressure;flux\n".to_string();
+    for i in 0..40 {
+        text.push_str(&format!(
+            "{};{:e};{:E};{:+e}\n",
+            i,
+            20.5 + i as f64 * 0.013,
+            101325.0 * (i + 1) as f64,
+            -1.0e-7 * (i as f64 + 0.5)
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Float, Type::Float, Type::Float]
+    );
+
+    // values with exponents aren't decimals
+    let metadata = Sniffer::new()
+        .detect_decimals(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.types[3], Type::Float);
+}
+
+#[test]
+fn test_decimal_comma() {
+    let mut text = "Datum;Artikel;Menge;Preis;Rabatt;Summe\n".to_string();
+    for i in 0..20 {
+        text.push_str(&format!(
+            "{:02}.03.2021;Artikel {};{};{},{:02};-{},5;{}.{:03},{:02} €\n",
+            i + 1,
+            i,
+            i + 1,
+            i + 3,
+            i * 3,
+            i % 4,
+            i + 1,
+            i * 7,
+            i
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.number_format,
+        NumberFormat {
+            decimal_separator: ',',
+            grouping_separator: Some('.'),
+        }
+    );
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Date,
+            Type::Text,
+            Type::Unsigned,
+            Type::Float,
+            Type::Float,
+            Type::Currency {
+                symbol: Some('€')
+            },
+        ]
+    );
+
+    // French-style numbers, with spaces between groups of thousands
+    let mut text = "produit;quantité;prix\n".to_string();
+    for i in 0..20 {
+        text.push_str(&format!(
+            "p{};{};{} {:03},{}\n",
+            i,
+            i + 1,
+            i + 1,
+            i * 11,
+            i % 10
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(
+        metadata.number_format,
+        NumberFormat {
+            decimal_separator: ',',
+            grouping_separator: Some(' '),
+        }
+    );
+    assert_eq!(metadata.types[1], Type::Unsigned);
+
+    // the decimal separator can also be specified
+    let text = "a;b\n1,5;2\n2,5;3\n3,5;4\n";
+    let metadata = Sniffer::new()
+        .number_format(NumberFormat {
+            decimal_separator: ',',
+            grouping_separator: None,
+        })
+        .sniff_str(text)
+        .unwrap();
+    assert_eq!(metadata.types, vec![Type::Float, Type::Unsigned]);
+
+    // files with decimal points keep the default
+    let metadata = Sniffer::new()
+        .sniff_str("a,b\n1.5,2\n2.5,3\n3.5,4\n")
+        .unwrap();
+    assert_eq!(metadata.number_format, NumberFormat::default());
+}
+
+#[test]
+fn test_grouping_separators() {
+    let mut text = "city,population,change,area\n".to_string();
+    for i in 0..20 {
+        text.push_str(&format!(
+            "city {},\"{},{:03},{:03}\",\"-{},{:03}\",\"{},{:03}.5\"\n",
+            i,
+            i + 1,
+            i * 17,
+            i * 31,
+            i + 2,
+            i * 7,
+            i + 3,
+            i * 13
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.number_format,
+        NumberFormat {
+            decimal_separator: '.',
+            grouping_separator: Some(','),
+        }
+    );
+    assert_eq!(
+        metadata.types,
+        vec![Type::Text, Type::Unsigned, Type::Signed, Type::Float]
+    );
+
+    // spaces between groups of thousands
+    let mut text = "city;population;area\n".to_string();
+    for i in 0..20 {
+        text.push_str(&format!(
+            "city {};{} {:03} {:03};{}.{}\n",
+            i,
+            i + 1,
+            i * 17,
+            i * 31,
+            i + 3,
+            i % 10
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert_eq!(
+        metadata.number_format,
+        NumberFormat {
+            decimal_separator: '.',
+            grouping_separator: Some(' '),
+        }
+    );
+    assert_eq!(
+        metadata.types,
+        vec![Type::Text, Type::Unsigned, Type::Float]
+    );
+
+    // numbers that could be read either way are read with a grouping separator, which is reported
+    let mut text = "city;population;area\n".to_string();
+    for i in 0..20 {
+        text.push_str(&format!("city {};{},{:03};{}\n", i, i + 1, i * 17, i + 3));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert_eq!(
+        metadata.number_format,
+        NumberFormat {
+            decimal_separator: '.',
+            grouping_separator: Some(','),
+        }
+    );
+    assert_eq!(
+        metadata.types,
+        vec![Type::Text, Type::Unsigned, Type::Unsigned]
+    );
+}
+
+#[test]
+fn test_date_order() {
+    // a day after the 12th can only be read day-first, which decides the order of the other column
+    let text = "\
+id,start,end
+1,04/03/2021,05/03/2021
+2,11/03/2021,12/03/2021
+3,25/03/2021,01/04/2021
+4,02/04/2021,03/04/2021
+5,08/04/2021,09/04/2021
+";
+    let metadata = Sniffer::new().sniff_str(text).unwrap();
+    assert_eq!(metadata.types, vec![Type::Unsigned, Type::Date, Type::Date]);
+    assert_eq!(metadata.date_order, Some(DateOrder::DayFirst));
+    assert!(!metadata.date_order_ambiguous);
+    assert_eq!(
+        metadata.formats,
+        vec![None, Some("%d/%m/%Y".into()), Some("%d/%m/%Y".into())]
+    );
+
+    // month-first
+    let text = "\
+id,start
+1,04/03/2021
+2,03/25/2021
+3,04/11/2021
+4,05/02/2021
+";
+    let metadata = Sniffer::new().sniff_str(text).unwrap();
+    assert_eq!(metadata.date_order, Some(DateOrder::MonthFirst));
+    assert!(!metadata.date_order_ambiguous);
+    assert_eq!(metadata.formats[1], Some("%m/%d/%Y".into()));
+
+    // every date can be read either way, so the order is the preferred one
+    let text = "\
+id,start
+1,04/03/2021
+2,11/03/2021
+3,02/04/2021
+4,08/04/2021
+";
+    let metadata = Sniffer::new().sniff_str(text).unwrap();
+    assert_eq!(metadata.date_order, Some(DateOrder::MonthFirst));
+    assert!(metadata.date_order_ambiguous);
+    assert_eq!(metadata.formats[1], Some("%m/%d/%Y".into()));
+    let metadata = Sniffer::new()
+        .date_preference(DateOrder::DayFirst)
+        .verify(true)
+        .sniff_str(text)
+        .unwrap();
+    assert_eq!(metadata.date_order, Some(DateOrder::DayFirst));
+    assert!(metadata.date_order_ambiguous);
+    assert_eq!(metadata.formats[1], Some("%d/%m/%Y".into()));
+
+    // year-first dates have no ambiguity
+    let metadata = Sniffer::new()
+        .sniff_str("id,start\n1,2021-03-04\n2,2021-03-05\n3,2021-03-06\n")
+        .unwrap();
+    assert_eq!(metadata.date_order, None);
+    assert!(!metadata.date_order_ambiguous);
+}
+
+#[test]
+fn test_custom_formats() {
+    let mut text = "Beleg;Gebucht;Periode;Uhrzeit\n".to_string();
+    for i in 0..20 {
+        text.push_str(&format!(
+            "{};{:02}.03.2021 {:02}:{:02};P{:02}/2021;{:02}h{:02}\n",
+            i,
+            i + 1,
+            i % 24,
+            i * 2,
+            i % 12 + 1,
+            i % 24,
+            i * 3
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Text, Type::Text, Type::Text]
+    );
+
+    let metadata = Sniffer::new()
+        .custom_formats(&["%d.%m.%Y %H:%M", "P%m/%Y", "%Hh%M", "no specifiers"])
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::DateTime, Type::Date, Type::Time]
+    );
+    assert_eq!(
+        metadata.formats,
+        vec![
+            None,
+            Some("%d.%m.%Y %H:%M".into()),
+            Some("P%m/%Y".into()),
+            Some("%Hh%M".into())
+        ]
+    );
+}
+
+#[test]
+fn test_type_hints() {
+    let text = "\
+id,zip,amount,updated,flag
+1,02134,12.50,2021-03-04,1
+2,10001,7.25,2021-03-05,0
+3,94105,100.00,2021-03-06,1
+4,60601,3.10,2021-03-07,0
+";
+    let metadata = Sniffer::new().sniff_str(text).unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Unsigned,
+            Type::Float,
+            Type::Date,
+            Type::Unsigned
+        ]
+    );
+
+    let metadata = Sniffer::new()
+        .type_hint("zip", Type::Text)
+        .type_hint(
+            2,
+            Type::Decimal {
+                precision: 10,
+                scale: 2,
+            },
+        )
+        .type_hint("updated", Type::Date)
+        .type_hint(4, Type::Boolean)
+        .type_hint(4, Type::Signed)
+        .type_hint("missing", Type::Float)
+        .verify(true)
+        .sniff_str(text)
+        .unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Text,
+            Type::Decimal {
+                precision: 10,
+                scale: 2
+            },
+            Type::Date,
+            Type::Signed
+        ]
+    );
+    assert_eq!(metadata.formats[3], Some("%Y-%m-%d".into()));
+    assert!(metadata.verification.unwrap().is_valid());
+
+    // a column of text hinted to be a number is reported as a number (with violations)
+    let metadata = Sniffer::new()
+        .type_hint(1, Type::Unsigned)
+        .verify(true)
+        .sniff_str("id,name\n1,foo\n2,bar\n3,baz\n")
+        .unwrap();
+    assert_eq!(metadata.types, vec![Type::Unsigned, Type::Unsigned]);
+    assert_eq!(metadata.verification.unwrap().num_type_violations, 3);
+
+    // the types of some columns can be specified at once, while the others are inferred
+    let metadata = Sniffer::new()
+        .type_hints([("id", Type::Signed), ("zip", Type::Text)])
+        .type_hints([(4, Type::Boolean)])
+        .sniff_str(text)
+        .unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Signed,
+            Type::Text,
+            Type::Float,
+            Type::Date,
+            Type::Boolean
+        ]
+    );
+}
+
+#[test]
+fn test_categorical_types() {
+    let mut text = "id,name,color,size,note\n".to_string();
+    let colors = ["red", "green", "blue"];
+    let sizes = ["S", "M", "L", "XL"];
+    for i in 0..30 {
+        text.push_str(&format!(
+            "{},name {},{},{},{}\n",
+            i,
+            i,
+            colors[i % colors.len()],
+            sizes[i % sizes.len()],
+            if i % 5 == 0 { "" } else { "ok" }
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Text,
+            Type::Text,
+            Type::Text,
+            Type::Text
+        ]
+    );
+    assert_eq!(metadata.levels, vec![Vec::<String>::new(); 5]);
+
+    let metadata = Sniffer::new()
+        .detect_categorical(3)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Text,
+            Type::Categorical { cardinality: 3 },
+            Type::Text,
+            Type::Categorical { cardinality: 1 },
+        ]
+    );
+    assert_eq!(metadata.levels[2], vec!["blue", "green", "red"]);
+    assert_eq!(metadata.levels[4], vec!["ok"]);
+    assert!(metadata.levels[3].is_empty());
+    assert_eq!(metadata.types[2].to_string(), "Categorical(3)");
+
+    // verification counts the levels in the whole file
+    text.push_str("30,name 30,purple,S,ok\n");
+    let metadata = Sniffer::new()
+        .detect_categorical(3)
+        .sample_size(SampleSize::Records(10))
+        .verify(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.types[2], Type::Text);
+    assert!(metadata.levels[2].is_empty());
+    assert_eq!(metadata.types[3], Type::Text);
+}
+
+#[test]
+fn test_uuid_types() {
+    let mut text = "id,guid,compact,hash\n".to_string();
+    for i in 0..20u32 {
+        text.push_str(&format!(
+            "{:08x}-e89b-12d3-a456-{:012x},{{{:08X}-E89B-12D3-A456-426614174000}},{:032x},{:031x}z\n",
+            i * 7919,
+            i as u64 * 104729,
+            i,
+            i as u128 * 0x1234_5678_9abc,
+            i
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.types,
+        vec![Type::Uuid, Type::Uuid, Type::Uuid, Type::Text]
+    );
+    assert_eq!(metadata.types[0].to_string(), "Uuid");
+}
+
+#[test]
+fn test_ip_address_types() {
+    let mut text = "timestamp,source,destination,bytes,host\n".to_string();
+    for i in 0..20 {
+        text.push_str(&format!(
+            "2021-03-04T12:{:02}:00,10.0.{}.{},{},{},{}.{}.0.1\n",
+            i,
+            i % 4,
+            i * 12,
+            if i % 3 == 0 {
+                format!("2001:db8::{:x}", i * 257)
+            } else {
+                format!("192.168.1.{}", i)
+            },
+            i * 1500,
+            250 + i,
+            i
+        ));
+    }
+    let metadata = Sniffer::new().verify(true).sniff_str(&text).unwrap();
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::DateTime,
+            Type::IpAddr,
+            Type::IpAddr,
+            Type::Unsigned,
+            Type::Text
+        ]
+    );
+    assert_eq!(metadata.types[1].to_string(), "IpAddr");
+}
+
+#[test]
+fn test_json_types() {
+    let mut text = "id,event,payload,tags,note\n".to_string();
+    for i in 0..20 {
+        text.push_str(&format!(
+            "{},click,\"{{\"\"x\"\": {}, \"\"label\"\": \"\"btn}} {}\"\", \"\"meta\"\": {{}}}}\",\
+             \"[\"\"a\"\", {}]\",{}\n",
+            i,
+            i * 10,
+            i,
+            i,
+            if i % 5 == 0 { "{draft" } else { "{ok}" }
+        ));
+    }
+    let metadata = Sniffer::new().verify(true).sniff_str(&text).unwrap();
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert_eq!(metadata.num_fields, 5);
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Text,
+            Type::Json,
+            Type::Json,
+            Type::Text
+        ]
+    );
+    assert_eq!(metadata.types[2].to_string(), "Json");
+}
+
+#[test]
+fn test_binary_types() {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let encode = |bytes: &[u8]| {
+        let mut encoded = String::new();
+        for chunk in bytes.chunks(3) {
+            let n = chunk
+                .iter()
+                .chain([0, 0].iter())
+                .take(3)
+                .fold(0u32, |n, &byte| (n << 8) | byte as u32);
+            for i in 0..=chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            }
+            for _ in chunk.len()..3 {
+                encoded.push('=');
+            }
+        }
+        encoded
+    };
+    let mut text = "id,thumbnail,digest\n".to_string();
+    for i in 0..20u32 {
+        let bytes = (0..18 + i * 3 + i % 2)
+            .map(|j| (j * 37 + i * 11) as u8)
+            .collect::<Vec<_>>();
+        text.push_str(&format!(
+            "{},{},{:040x}\n",
+            i,
+            encode(&bytes),
+            i as u128 * 0x1234_5678_9abc_def0
+        ));
+    }
+    let metadata = Sniffer::new().verify(true).sniff_str(&text).unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Binary {
+                encoding: BinaryEncoding::Base64,
+                max_len: 18 + 19 * 3 + 1
+            },
+            Type::Hex
+        ]
+    );
+    assert_eq!(metadata.types[1].to_string(), "Binary(Base64, 76)");
+}
+
+#[test]
+fn test_hex_types() {
+    let mut text = "id,register,account,code,count\n".to_string();
+    for i in 0..20u64 {
+        let account = if i % 4 == 0 {
+            format!("{:015}", i * 7_919_000_000)
+        } else {
+            format!("{:015x}", i * 0x01_2345_6789_abcd)
+        };
+        text.push_str(&format!(
+            "{},0x{:04X},{},{},{}\n",
+            i,
+            i * 0x1a2b,
+            account,
+            ["cafe", "beef", "dead", "face"][i as usize % 4],
+            i * 1000
+        ));
+    }
+    let metadata = Sniffer::new().verify(true).sniff_str(&text).unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Hex,
+            Type::Hex,
+            Type::Text,
+            Type::Unsigned
+        ]
+    );
+    assert_eq!(metadata.types[1].to_string(), "Hex");
+}
+
+#[test]
+fn test_leading_zeros() {
+    let mut text = "id,zip,account,count\n".to_string();
+    for i in 0..20u64 {
+        text.push_str(&format!(
+            "{},{:05},{:010},{}\n",
+            i,
+            i * 4999,
+            i * 987_654_321 + 1,
+            i * 10
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(metadata.types, vec![Type::Unsigned; 4]);
+
+    let metadata = Sniffer::new()
+        .preserve_leading_zeros(true)
+        .verify(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::NumericString,
+            Type::NumericString,
+            Type::Unsigned
+        ]
+    );
+    assert_eq!(metadata.types[1].to_string(), "NumericString");
+    assert_eq!(metadata.verification.unwrap().num_type_violations, 0);
+}
+
+#[test]
+fn test_big_integers() {
+    let mut text = "id,card,counter,delta,small\n".to_string();
+    for i in 0..20u128 {
+        text.push_str(&format!(
+            "{},{},{},{}{},{}\n",
+            i,
+            4_000_000_000_000_000_000 + i * 123_456_789_012_345,
+            if i % 5 == 0 {
+                i
+            } else {
+                u64::MAX as u128 + i * i
+            },
+            if i % 2 == 0 { "-" } else { "" },
+            i64::MAX as u128 + i,
+            i * 3
+        ));
+    }
+    let metadata = Sniffer::new().verify(true).sniff_str(&text).unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Unsigned,
+            Type::BigInt,
+            Type::BigInt,
+            Type::Unsigned
+        ]
+    );
+    assert_eq!(metadata.types[2].to_string(), "BigInt");
+    assert_eq!(metadata.verification.unwrap().num_type_violations, 0);
+}
+
+#[test]
+fn test_type_counts() {
+    let mut text = "count,mixed,price,comment\n".to_string();
+    for i in 0..50 {
+        text.push_str(&format!(
+            "{},{},{}.{},{}\n",
+            if i == 17 || i == 33 {
+                "bad".to_string()
+            } else {
+                (i * 3).to_string()
+            },
+            if i % 2 == 0 {
+                format!("{}", i)
+            } else {
+                format!("item {}", i)
+            },
+            i,
+            i % 10 * 11,
+            if i % 5 == 0 { "" } else { "ok" }
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![Type::Text, Type::Text, Type::Float, Type::Text]
+    );
+    assert_eq!(
+        metadata.type_counts,
+        vec![
+            vec![(Type::Unsigned, 48), (Type::Text, 2)],
+            vec![(Type::Unsigned, 25), (Type::Text, 25)],
+            vec![(Type::Float, 50)],
+            vec![(Type::Text, 40), (Type::Null, 10)],
+        ]
+    );
+
+    // the details of the values of each type are widened to fit them all
+    let metadata = Sniffer::new()
+        .detect_decimals(true)
+        .verify(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(
+        metadata.type_counts[2],
+        vec![(
+            Type::Decimal {
+                precision: 4,
+                scale: 2
+            },
+            50
+        )]
+    );
+    assert_eq!(
+        metadata.type_counts[0],
+        vec![(Type::Unsigned, 48), (Type::Text, 2)]
+    );
+}
+
+#[test]
+fn test_type_confidence() {
+    let text = "id,score\n1,1.5\n2,2.5\n3,3.5\n";
+    let small = Sniffer::new().sniff_str(text).unwrap();
+    assert_eq!(small.types, vec![Type::Unsigned, Type::Float]);
+    assert!(small
+        .type_confidence
+        .iter()
+        .all(|&confidence| confidence < 0.6));
+
+    let mut text = "id,score,code,comment,empty\n".to_string();
+    for i in 0..10_000 {
+        text.push_str(&format!(
+            "{},{}.5,{},{},\n",
+            i,
+            i,
+            if i % 100 == 0 {
+                "x".to_string()
+            } else {
+                i.to_string()
+            },
+            if i % 2 == 0 { "ok" } else { "fine" }
+        ));
+    }
+    let large = Sniffer::new()
+        .sample_size(SampleSize::All)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(
+        large.types,
+        vec![
+            Type::Unsigned,
+            Type::Float,
+            Type::Text,
+            Type::Text,
+            Type::Null
+        ]
+    );
+    assert!(large.type_confidence[0] > 0.99);
+    assert!(large.type_confidence[0] > small.type_confidence[0]);
+    assert!(large.type_confidence[1] > 0.99);
+    // a text column of mostly numbers
+    assert!(large.type_confidence[2] < 0.05);
+    assert!(large.type_confidence[3] > 0.99);
+    assert_eq!(large.type_confidence[4], 0.0);
+}
+
+#[test]
+fn test_type_tolerance() {
+    let mut text = "id,count,mixed,when\n".to_string();
+    for i in 0..200 {
+        text.push_str(&format!(
+            "{},{},{},{}\n",
+            i,
+            match i {
+                50 => "n/a".to_string(),
+                150 => "12.5".to_string(),
+                _ => (i * 3).to_string(),
+            },
+            if i % 2 == 0 {
+                "x".to_string()
+            } else {
+                i.to_string()
+            },
+            if i == 100 {
+                "unknown".to_string()
+            } else {
+                format!("2021-03-{:02}", i % 28 + 1)
+            }
+        ));
+    }
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::All)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Text, Type::Text, Type::Text]
+    );
+    assert_eq!(metadata.offending_values, vec![Vec::<String>::new(); 4]);
+
+    let metadata = Sniffer::new()
+        .sample_size(SampleSize::All)
+        .type_tolerance(0.99)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Unsigned, Type::Text, Type::Date]
+    );
+    assert_eq!(metadata.formats[3], Some("%Y-%m-%d".to_string()));
+    assert_eq!(
+        metadata.offending_values,
+        vec![
+            vec![],
+            vec!["n/a".to_string(), "12.5".to_string()],
+            vec![],
+            vec!["unknown".to_string()]
+        ]
+    );
+    assert!(metadata.type_confidence[1] > 0.95 && metadata.type_confidence[1] < 1.0);
+
+    // the tolerated values are still violations
+    let metadata = Sniffer::new()
+        .type_tolerance(0.99)
+        .verify(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Unsigned, Type::Text, Type::Date]
+    );
+    assert_eq!(metadata.verification.unwrap().num_type_violations, 3);
+}
+
+#[test]
+fn test_email_types() {
+    let mut text = "id,name,email,contact\n".to_string();
+    for i in 0..20 {
+        text.push_str(&format!(
+            "{},user {},user.{}@example{}.com,{}\n",
+            i,
+            i,
+            i,
+            i % 3,
+            if i % 4 == 0 {
+                "n/a".to_string()
+            } else {
+                format!("u{}@mail.example.org", i)
+            }
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(metadata.semantic_types, vec![None; 4]);
+
+    let metadata = Sniffer::new()
+        .detect_semantic_types(true)
+        .verify(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Text, Type::Text, Type::Text]
+    );
+    assert_eq!(
+        metadata.semantic_types,
+        vec![None, None, Some(SemanticType::Email), None]
+    );
+}
+
+#[test]
+fn test_url_types() {
+    let mut text = "id,name,website,notes\n".to_string();
+    for i in 0..20 {
+        text.push_str(&format!(
+            "{},org {},https://www.org{}.com/about?id={},{}\n",
+            i,
+            i,
+            i,
+            i,
+            if i % 4 == 0 {
+                "see site".to_string()
+            } else {
+                format!("ftp://files.org{}.com/pub", i)
+            }
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(metadata.semantic_types, vec![None; 4]);
+
+    let metadata = Sniffer::new()
+        .detect_semantic_types(true)
+        .verify(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Text, Type::Text, Type::Text]
+    );
+    assert_eq!(
+        metadata.semantic_types,
+        vec![None, None, Some(SemanticType::Url), None]
+    );
+}
+
+#[cfg(feature = "phone")]
+#[test]
+fn test_phone_types() {
+    let mut text = "id,mobile,office,compact,amount\n".to_string();
+    for i in 0..20 {
+        text.push_str(&format!(
+            "{},+49 170 {:07},({:03}) 555-{:04},+4917{:08},{} {:03} {:03}\n",
+            i,
+            i * 3571,
+            200 + i,
+            i * 37,
+            i * 104729,
+            i + 1,
+            i * 7,
+            i * 11
+        ));
+    }
+    let metadata = Sniffer::new().sniff_str(&text).unwrap();
+    assert_eq!(metadata.types[3], Type::Unsigned);
+    assert_eq!(metadata.semantic_types, vec![None; 5]);
+
+    let metadata = Sniffer::new()
+        .detect_semantic_types(true)
+        .verify(true)
+        .sniff_str(&text)
+        .unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![
+            Type::Unsigned,
+            Type::Text,
+            Type::Text,
+            Type::Text,
+            Type::Unsigned
+        ]
+    );
+    assert_eq!(
+        metadata.semantic_types,
+        vec![
+            None,
+            Some(SemanticType::Phone),
+            Some(SemanticType::Phone),
+            Some(SemanticType::Phone),
+            None
+        ]
+    );
+}